@@ -135,4 +135,128 @@ impl TclRuntimeCapabilities {
         
         capabilities
     }
+}
+
+/// How risky a single TCL command is to expose, independent of whether the active runtime
+/// happens to have it available. Drives `tcl/commands`' safe/restricted/unsafe/unavailable
+/// summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandSafety {
+    Safe,
+    Restricted,
+    Unsafe,
+    Unavailable,
+}
+
+/// One command a runtime's `CommandProvider` knows about, with the category and safety rating
+/// `tcl/commands` reports it under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandMetadata {
+    pub name: String,
+    pub category: String,
+    pub safety: CommandSafety,
+}
+
+/// Source of a runtime's full command inventory, queried by `tcl/commands`. Separate from
+/// [`TclRuntimeCapabilities`] (which reports the *active* capability set for a given
+/// `privileged` flag) because this reports every command the runtime could ever expose, tagged
+/// with the safety level that governs whether it's covered by a capability grant.
+pub trait CommandProvider {
+    /// Every known command, optionally narrowed to names containing `filter` and/or an exact
+    /// `category` match.
+    fn get_command_metadata(&self, filter: Option<&str>, category: Option<&str>) -> Vec<CommandMetadata>;
+}
+
+struct StaticCommandProvider {
+    commands: Vec<CommandMetadata>,
+}
+
+impl CommandProvider for StaticCommandProvider {
+    fn get_command_metadata(&self, filter: Option<&str>, category: Option<&str>) -> Vec<CommandMetadata> {
+        self.commands
+            .iter()
+            .filter(|c| filter.map(|f| c.name.contains(f)).unwrap_or(true))
+            .filter(|c| category.map(|cat| c.category == cat).unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+}
+
+/// A category's intrinsic risk level, used to tag every command a [`TclRuntimeCapabilities`]
+/// exposes under it. `file`/`system` commands touch the host outside the interpreter and are
+/// rated more dangerous than pure-language categories like `core`/`string`/`list`.
+fn safety_for_category(category: &str) -> CommandSafety {
+    match category {
+        "file" => CommandSafety::Restricted,
+        "system" => CommandSafety::Unsafe,
+        _ => CommandSafety::Safe,
+    }
+}
+
+fn command_metadata_from(capabilities: &TclRuntimeCapabilities) -> Vec<CommandMetadata> {
+    capabilities
+        .command_categories
+        .iter()
+        .flat_map(|(category, commands)| {
+            let safety = safety_for_category(category);
+            commands.iter().map(move |name| CommandMetadata {
+                name: name.clone(),
+                category: category.clone(),
+                safety,
+            })
+        })
+        .collect()
+}
+
+/// Builds the [`CommandProvider`] for a runtime by name, as reported by [`crate::tcl_runtime::RuntimeInfo::name`].
+pub struct CapabilityFactory;
+
+impl CapabilityFactory {
+    /// Unknown runtime names fall back to the Molt provider (the safe default runtime), the same
+    /// way [`crate::tcl_runtime::create_runtime`] defaults to Molt when nothing else is configured.
+    pub fn create_provider(runtime_name: &str) -> Box<dyn CommandProvider> {
+        let capabilities = if runtime_name.eq_ignore_ascii_case("tcl") || runtime_name.eq_ignore_ascii_case("tcl (official)") {
+            TclRuntimeCapabilities::for_tcl(true)
+        } else {
+            TclRuntimeCapabilities::for_molt(true)
+        };
+        Box::new(StaticCommandProvider { commands: command_metadata_from(&capabilities) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_provider_tags_file_commands_restricted() {
+        let provider = CapabilityFactory::create_provider("TCL (Official)");
+        let commands = provider.get_command_metadata(None, Some("file"));
+        assert!(!commands.is_empty());
+        assert!(commands.iter().all(|c| c.safety == CommandSafety::Restricted));
+    }
+
+    #[test]
+    fn test_create_provider_tags_system_commands_unsafe() {
+        let provider = CapabilityFactory::create_provider("TCL (Official)");
+        let commands = provider.get_command_metadata(None, Some("system"));
+        assert!(!commands.is_empty());
+        assert!(commands.iter().all(|c| c.safety == CommandSafety::Unsafe));
+    }
+
+    #[test]
+    fn test_create_provider_filters_by_name_substring() {
+        let provider = CapabilityFactory::create_provider("Molt");
+        let commands = provider.get_command_metadata(Some("str"), None);
+        assert!(commands.iter().all(|c| c.name.contains("str")));
+        assert!(!commands.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_runtime_name_falls_back_to_molt() {
+        let provider = CapabilityFactory::create_provider("something-else");
+        let commands = provider.get_command_metadata(None, None);
+        assert!(!commands.is_empty());
+    }
 }
\ No newline at end of file
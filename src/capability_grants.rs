@@ -0,0 +1,179 @@
+//! Fine-grained per-tool capability grants loaded from files, replacing the binary `privileged`
+//! flag with composable, declarative sets of TCL command permissions scoped to tool namespaces
+//! (`bin`, `sbin`, `docs`, or a user namespace name). Grants are additive across files so a
+//! deployment can be extended incrementally without recompiling: drop in another capability file
+//! and the union of everything loaded so far applies.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// One named grant as written in a capability file: a set of TCL command names (e.g. `exec`,
+/// `file`, `puts`) permitted for a set of tool namespaces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityGrant {
+    pub name: String,
+    pub namespaces: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+/// A capability file is a list of grants under a `[[grant]]` TOML table array; TOML is the same
+/// hand-editable format `persistence`'s `StorageFormat::Toml` receipts use.
+#[derive(Debug, Deserialize)]
+struct CapabilityFile {
+    #[serde(rename = "grant")]
+    grants: Vec<CapabilityGrant>,
+}
+
+/// The resolved set of grants loaded from zero or more capability files, indexed by tool
+/// namespace for fast lookup during `tools/call` enforcement.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CapabilityGrants {
+    grants: Vec<CapabilityGrant>,
+    by_namespace: HashMap<String, HashSet<String>>,
+}
+
+impl CapabilityGrants {
+    /// Loads and merges grants from each path in `files`, in order. A later file's commands for
+    /// a namespace are additive with an earlier one's, never subtractive.
+    pub fn load<P: AsRef<Path>>(files: &[P]) -> Result<Self> {
+        let mut grants = Vec::new();
+        for path in files {
+            let path = path.as_ref();
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read capability file {}", path.display()))?;
+            let file: CapabilityFile = toml::from_str(&text)
+                .with_context(|| format!("failed to parse capability file {}", path.display()))?;
+            grants.extend(file.grants);
+        }
+        Ok(Self::from_grants(grants))
+    }
+
+    fn from_grants(grants: Vec<CapabilityGrant>) -> Self {
+        let mut by_namespace: HashMap<String, HashSet<String>> = HashMap::new();
+        for grant in &grants {
+            for namespace in &grant.namespaces {
+                by_namespace
+                    .entry(namespace.clone())
+                    .or_default()
+                    .extend(grant.commands.iter().cloned());
+            }
+        }
+        Self { grants, by_namespace }
+    }
+
+    /// True when no capability files were configured. Enforcement is skipped entirely in this
+    /// case, so an operator who never opts in keeps today's `privileged`-only behavior.
+    pub fn is_empty(&self) -> bool {
+        self.grants.is_empty()
+    }
+
+    /// Every command in `required` that no grant covers for `namespace`. Always empty when no
+    /// grants are loaded at all, matching the unenforced state [`Self::is_empty`] describes.
+    pub fn missing_commands(&self, namespace: &str, required: &HashSet<String>) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let granted = self.by_namespace.get(namespace);
+        required
+            .iter()
+            .filter(|cmd| !granted.map(|g| g.contains(cmd.as_str())).unwrap_or(false))
+            .cloned()
+            .collect()
+    }
+
+    /// The resolved grants, keyed by namespace, for exposing through `initialize` and
+    /// `tcl/capabilities`.
+    pub fn resolved_by_namespace(&self) -> &HashMap<String, HashSet<String>> {
+        &self.by_namespace
+    }
+}
+
+/// Loads grants from every path in `TCL_MCP_CAPABILITY_FILES` (comma-separated), or an empty,
+/// unenforced `CapabilityGrants` if the variable is unset — mirroring `registry::registry_from_env`.
+pub fn grants_from_env() -> Result<CapabilityGrants> {
+    let Some(files) = std::env::var("TCL_MCP_CAPABILITY_FILES").ok().filter(|s| !s.is_empty()) else {
+        return Ok(CapabilityGrants::default());
+    };
+    let paths: Vec<PathBuf> = files.split(',').map(PathBuf::from).collect();
+    CapabilityGrants::load(&paths)
+}
+
+/// The distinct commands a TCL script invokes, taken as the leading word of each
+/// `;`-or-newline-separated statement — the same leading-word heuristic
+/// [`crate::tcl_runtime::RoutingRuntime`] routes by, though that only looks at a script's first
+/// statement. Like that heuristic, this is best-effort: a command reached
+/// only through `[bracket substitution]` or nested inside `{braces}` on the same statement isn't
+/// picked up, so grants are a speed bump against accidental misuse rather than a hard sandbox.
+pub fn required_commands(script: &str) -> HashSet<String> {
+    script
+        .split(['\n', ';'])
+        .map(|stmt| stmt.trim_start().split_whitespace().next().unwrap_or(""))
+        .filter(|cmd| !cmd.is_empty())
+        .map(|cmd| cmd.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_capability_file(dir: &Path, name: &str, contents: &str) -> std::path::PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_required_commands_splits_on_semicolons_and_newlines() {
+        let required = required_commands("set x 1; puts $x\nexpr {1 + 1}");
+        assert_eq!(required, HashSet::from(["set".to_string(), "puts".to_string(), "expr".to_string()]));
+    }
+
+    #[test]
+    fn test_no_files_means_empty_and_unenforced() {
+        let grants = CapabilityGrants::load::<String>(&[]).unwrap();
+        assert!(grants.is_empty());
+        assert!(grants.missing_commands("bin", &HashSet::from(["exec".to_string()])).is_empty());
+    }
+
+    #[test]
+    fn test_missing_commands_reports_ungranted_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_capability_file(dir.path(), "caps.toml", r#"
+            [[grant]]
+            name = "filesystem-read"
+            namespaces = ["bin"]
+            commands = ["open", "read", "close"]
+        "#);
+
+        let grants = CapabilityGrants::load(&[path]).unwrap();
+        assert!(!grants.is_empty());
+
+        let required = HashSet::from(["open".to_string(), "exec".to_string()]);
+        let missing = grants.missing_commands("bin", &required);
+        assert_eq!(missing, vec!["exec".to_string()]);
+
+        assert!(grants.missing_commands("sbin", &HashSet::from(["open".to_string()])).len() == 1);
+    }
+
+    #[test]
+    fn test_grants_merge_additively_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = write_capability_file(dir.path(), "a.toml", r#"
+            [[grant]]
+            name = "a"
+            namespaces = ["bin"]
+            commands = ["open"]
+        "#);
+        let second = write_capability_file(dir.path(), "b.toml", r#"
+            [[grant]]
+            name = "b"
+            namespaces = ["bin"]
+            commands = ["exec"]
+        "#);
+
+        let grants = CapabilityGrants::load(&[first, second]).unwrap();
+        assert!(grants.missing_commands("bin", &HashSet::from(["open".to_string(), "exec".to_string()])).is_empty());
+    }
+}
@@ -1,10 +1,10 @@
 use anyhow::Result;
 use axum::{
-    extract::{Json, State},
-    http::StatusCode,
+    extract::{Json, Path, State},
+    http::{HeaderMap, StatusCode},
     middleware,
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
@@ -12,16 +12,90 @@ use serde_json::{json, Value};
 use tower_http::cors::CorsLayer;
 use tracing::{info, debug, error};
 
-use crate::auth::{AuthConfig, auth_middleware};
-use crate::tcl_tools::{TclToolBox, TclExecuteRequest, TclToolAddRequest, TclToolRemoveRequest, TclToolListRequest, TclExecToolRequest};
-use crate::tcl_executor::TclExecutor;
+use crate::auth::{AuthConfig, CallerIdentity, Scope, ToolAccess, auth_middleware, DEFAULT_KEY_ROTATION_GRACE_SECS};
+use crate::permissions::{Permission, Principal};
+use crate::tcl_tools::{TclToolBox, TclExecuteRequest, TclToolAddRequest, TclToolRemoveRequest, TclToolListRequest, TclToolReceiptRequest, TclExecToolRequest, TclExecBatchRequest, TclPipelineRequest, TclDiscoverToolsRequest, TclToolTrustRequest, TclToolRevokeRequest, TclToolTestRequest, TclToolCoverageRequest, TclComposeRequest, ParameterDefinition};
+use crate::plugin_manager::{plugins_dir_from_env, PluginManager};
+use tokio::sync::RwLock;
+use crate::tcl_executor::{TclExecutorPool, pool_size_from_env};
 use crate::namespace::ToolPath;
 use crate::tcl_runtime::RuntimeConfig;
+use crate::registry::{registry_from_env, Registry};
+use crate::process_hardening::{harden_if_restricted, HardeningReport};
+use crate::capability_grants::{grants_from_env, required_commands, CapabilityGrants};
+use crate::capabilities::{CapabilityFactory, CommandProvider};
+use std::sync::Arc;
+
+/// Protocol versions this server understands, newest first. The `initialize` handshake picks
+/// the highest version that also appears in the client's request.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+/// Topic names shared by `docs___molt_book` and the `molt-book://{topic}` resources, so the
+/// tool and the resource listing can't drift apart on what topics exist.
+const MOLT_BOOK_TOPICS: &[&str] = &["overview", "basic_syntax", "commands", "examples", "links"];
+
+/// URI scheme for Molt Book topics exposed as MCP resources (`molt-book://{topic}`).
+const MOLT_BOOK_RESOURCE_SCHEME: &str = "molt-book://";
+
+/// The JSON-RPC version every `McpResponse` reports, per the spec's `"jsonrpc": "2.0"` envelope field.
+const JSONRPC_VERSION: &str = "2.0";
 
 #[derive(Clone)]
 pub struct HttpMcpServer {
     tool_box: TclToolBox,
     privileged: bool,
+    /// TUF-style signed registry gating `sbin___tcl_tool_add`, configured via
+    /// `TCL_MCP_UDF_REGISTRY_DIR`. `None` means UDF loading is unrestricted (the default).
+    registry: Option<Arc<Registry>>,
+    /// What OS-level hardening (dropped Linux capabilities, seccomp) got applied when this
+    /// server was constructed; see `process_hardening::harden_if_restricted`. Surfaced through
+    /// `tcl/capabilities` so a client can audit the actual sandbox rather than trusting
+    /// `privileged` alone.
+    hardening: HardeningReport,
+    /// Fine-grained per-tool-namespace TCL command grants, loaded from `TCL_MCP_CAPABILITY_FILES`.
+    /// Empty (and therefore unenforced) unless the operator opts in — see
+    /// `CapabilityGrants::is_empty` and `enforce_capability_grants`.
+    capability_grants: Arc<CapabilityGrants>,
+    /// Out-of-process tool providers loaded from `TCL_MCP_PLUGINS_DIR` by `initialize_plugins`,
+    /// merged into `tools/list` and dispatched to in `tools/call`. Starts out empty (no plugins
+    /// owned) until that's called, the same "construct empty, populate async" split
+    /// `initialize_persistence` uses for the tool store.
+    plugins: Arc<RwLock<PluginManager>>,
+}
+
+/// Returned by [`HttpMcpServer::watch_tools`]. Dropping it (or calling [`ToolWatchGuard::stop`]
+/// explicitly) stops every pool worker's tool-storage watcher via
+/// `TclCommand::StopWatchingTools`, so externally-edited tool files are no longer picked up.
+/// `Drop` can't await the pool round-trip, so it fires the stop off on its own spawned task
+/// instead; callers that need to know the stop has actually landed should call `stop` directly.
+pub struct ToolWatchGuard {
+    tool_box: Option<TclToolBox>,
+}
+
+impl ToolWatchGuard {
+    fn new(tool_box: TclToolBox) -> Self {
+        Self { tool_box: Some(tool_box) }
+    }
+
+    /// Stops watching and waits for every pool worker to confirm it.
+    pub async fn stop(mut self) -> Result<()> {
+        if let Some(tool_box) = self.tool_box.take() {
+            tool_box.stop_watching_tools().await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ToolWatchGuard {
+    fn drop(&mut self) {
+        if let Some(tool_box) = self.tool_box.take() {
+            tokio::spawn(async move {
+                if let Err(e) = tool_box.stop_watching_tools().await {
+                    tracing::warn!("Failed to stop tool storage watcher on drop: {}", e);
+                }
+            });
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,6 +107,7 @@ pub struct McpRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct McpResponse {
+    pub jsonrpc: String,
     pub result: Option<Value>,
     pub error: Option<McpError>,
     pub id: Option<Value>,
@@ -64,31 +139,183 @@ pub struct McpCallToolParams {
     pub arguments: Value,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpCallToolResult {
     pub content: Vec<McpContent>,
+    /// Set when the tool itself failed (as opposed to a protocol-level `McpError`), so clients
+    /// can distinguish "the tool ran and reported failure" from "the call couldn't be made".
+    #[serde(rename = "isError")]
+    pub is_error: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum McpContent {
     #[serde(rename = "text")]
     Text { text: String },
+    #[serde(rename = "json")]
+    Json { data: Value },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpResourceInfo {
+    pub uri: String,
+    pub name: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpListResourcesResult {
+    pub resources: Vec<McpResourceInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpReadResourceParams {
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpReadResourceResult {
+    pub contents: Vec<McpResourceContents>,
+}
+
+/// One step of a `tools/chain` request: a regular `tools/call` invocation, plus an optional
+/// set of argument keys to fill in from prior steps' results (e.g. `"x": "$steps[0].result"`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpChainStep {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+    #[serde(default)]
+    pub bind: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpChainRequest {
+    pub steps: Vec<McpChainStep>,
+    /// If true, a failed step is recorded and the chain continues; otherwise it stops at the
+    /// first failure (default).
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpChainStepResult {
+    pub name: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Vec<McpContent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpChainResult {
+    pub results: Vec<McpChainStepResult>,
+}
+
+/// Hard cap on steps per `tools/chain` request. (`bin___exec_batch` has no cap on its entry
+/// count; it only bounds how many entries run at once.)
+const MAX_CHAIN_STEPS: usize = 16;
+
+/// `McpError.code` for a call blocked by the authenticated key's `ToolAccess` restriction, mapped
+/// to HTTP 403 by `mcp_error_status` for the plain `/tools/call` endpoint.
+const ERROR_CODE_TOOL_FORBIDDEN: i32 = -32011;
+
+/// `McpError.code` for a `sbin___tcl_tool_add` rejected by the UDF registry (see
+/// `crate::registry`) — unknown target, hash mismatch, expired metadata, or insufficient
+/// signatures.
+const ERROR_CODE_UDF_REJECTED: i32 = -32012;
+
+/// Maps an `McpError` to the HTTP status the plain (non-JSON-RPC) `/tools/call` endpoint should
+/// report. `/mcp` always answers 200 with the code in the JSON-RPC `error` envelope instead, so
+/// this only matters for direct REST-style callers.
+fn mcp_error_status(error: &McpError) -> StatusCode {
+    match error.code {
+        ERROR_CODE_TOOL_FORBIDDEN | ERROR_CODE_UDF_REJECTED => StatusCode::FORBIDDEN,
+        -32602 => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Applies `harden_if_restricted` once, at server construction, and logs the outcome — this is
+/// the server's actual entry point, so doing it here (rather than leaving it for a caller to
+/// remember) means every binary that builds an `HttpMcpServer` gets the hardening for free.
+fn apply_hardening(privileged: bool) -> HardeningReport {
+    let report = harden_if_restricted(privileged);
+    if report.platform_supported {
+        if let Some(error) = &report.error {
+            tracing::warn!("Process hardening only partially applied: {error}");
+        } else {
+            info!(
+                "Process hardening applied: dropped {} Linux capabilities, seccomp {}",
+                report.capabilities_dropped.len(),
+                if report.seccomp_enabled { "enabled" } else { "not enabled" }
+            );
+        }
+    } else if !privileged {
+        info!("Process hardening not applied (unsupported platform); relying on the TCL-layer sandbox only");
+    }
+    report
+}
+
+/// Builds the `Principal` an authenticated (non-admin) caller gets for `sbin___tcl_tool_add`/
+/// `sbin___tcl_tool_remove`: named after its real identity rather than the client-supplied
+/// `request.user`, so `permissions::check_path`'s self-administer-only rule for
+/// `Namespace::User` actually constrains it, and not granted `Permission::Admin`.
+fn tool_management_principal(identity: &CallerIdentity) -> Principal {
+    Principal::new(
+        identity.0.clone(),
+        [Permission::Read, Permission::Execute, Permission::AddTool, Permission::RemoveTool],
+    )
 }
 
 impl HttpMcpServer {
     pub fn new(privileged: bool) -> Self {
-        let executor = TclExecutor::spawn(privileged);
-        let tool_box = TclToolBox::new(executor);
-        
-        Self { tool_box, privileged }
+        // Axum serves requests concurrently, so a single interpreter thread would serialize
+        // every `tools/call`; pool size defaults to `num_cpus::get()` (see `TCL_MCP_EXECUTOR_POOL_SIZE`).
+        let pool = TclExecutorPool::spawn(privileged, pool_size_from_env());
+        let tool_box = TclToolBox::with_pool(pool);
+        let registry = registry_from_env()
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load TCL_MCP_UDF_REGISTRY_DIR, UDF registry verification disabled: {e}");
+                None
+            })
+            .map(Arc::new);
+        let hardening = apply_hardening(privileged);
+        let capability_grants = Arc::new(grants_from_env().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load TCL_MCP_CAPABILITY_FILES, capability grants disabled: {e}");
+            CapabilityGrants::default()
+        }));
+
+        Self { tool_box, privileged, registry, hardening, capability_grants, plugins: Arc::new(RwLock::new(PluginManager::default())) }
     }
-    
+
     pub fn new_with_runtime(privileged: bool, runtime_config: RuntimeConfig) -> Result<Self, String> {
-        let executor = TclExecutor::spawn_with_runtime(privileged, runtime_config)?;
-        let tool_box = TclToolBox::new(executor);
-        
-        Ok(Self { tool_box, privileged })
+        // As in `new`, a pool keeps one slow script from serializing every `tools/call` behind
+        // it; `executor_pool_size` lets `--runtime`/`--eval-timeout` callers override it the same
+        // way `TCL_MCP_EXECUTOR_POOL_SIZE` does for the plain-privileged path.
+        let size = runtime_config.executor_pool_size.unwrap_or_else(pool_size_from_env);
+        let pool = TclExecutorPool::spawn_with_runtime(privileged, size, runtime_config)?;
+        let tool_box = TclToolBox::with_pool(pool);
+        let registry = registry_from_env()
+            .map_err(|e| format!("Failed to load TCL_MCP_UDF_REGISTRY_DIR: {e}"))?
+            .map(Arc::new);
+        let hardening = apply_hardening(privileged);
+        let capability_grants = Arc::new(
+            grants_from_env().map_err(|e| format!("Failed to load TCL_MCP_CAPABILITY_FILES: {e}"))?,
+        );
+
+        Ok(Self { tool_box, privileged, registry, hardening, capability_grants, plugins: Arc::new(RwLock::new(PluginManager::default())) })
     }
     
     pub async fn initialize_persistence(&self) -> Result<()> {
@@ -103,7 +330,31 @@ impl HttpMcpServer {
             }
         }
     }
-    
+
+    /// Loads plugins from `TCL_MCP_PLUGINS_DIR`, if set, merging their tools into `tools/list`
+    /// and `tools/call`. A no-op when the variable is unset (the default), same as
+    /// `initialize_persistence`'s "best effort, don't fail startup" behavior when persistence
+    /// isn't configured either.
+    pub async fn initialize_plugins(&self) -> Result<()> {
+        let Some(dir) = plugins_dir_from_env() else {
+            return Ok(());
+        };
+        let manager = PluginManager::load(&dir).await?;
+        *self.plugins.write().await = manager;
+        Ok(())
+    }
+
+    /// Starts (or confirms) this server's tool-directory watcher and returns a [`ToolWatchGuard`]
+    /// that stops it again when dropped. Watching itself already comes up automatically the
+    /// moment persistence initializes (`TclExecutor::start_watching`) — this just makes that
+    /// explicit and revocable for a caller (e.g. a `--watch` CLI flag, or a test) that wants to
+    /// turn it off later without tearing down the whole server. A watcher already stopped by an
+    /// earlier guard is not restarted by a later call.
+    pub async fn watch_tools(&self) -> Result<ToolWatchGuard> {
+        self.initialize_persistence().await?;
+        Ok(ToolWatchGuard::new(self.tool_box.clone()))
+    }
+
     pub fn router(self) -> Router {
         let auth_config = AuthConfig::new();
         
@@ -112,21 +363,52 @@ impl HttpMcpServer {
             .route("/health", get(health_check))
             .route("/mcp", post(handle_mcp_request))
             .route("/initialize", post(handle_initialize))
+            .route("/capabilities", get(handle_capabilities))
             .route("/tools/list", get(handle_tools_list))
             .route("/tools/call", post(handle_tools_call))
+            .route("/tools/chain", post(handle_tools_chain))
+            .route("/resources/list", get(handle_resources_list))
+            .route("/resources/read", post(handle_resources_read))
             .route("/auth/generate-key", post(generate_api_key_endpoint))
+            .route("/auth/rotate-api-key", post(rotate_api_key_endpoint))
+            .route("/auth/keys/:hash", delete(revoke_api_key_endpoint))
+            .route("/udf/verify", post(udf_verify_endpoint))
             .layer(middleware::from_fn_with_state(auth_config.clone(), auth_middleware))
             .layer(CorsLayer::permissive())
             .with_state(self)
             .with_state(auth_config)
     }
     
-    async fn handle_initialize(&self) -> Result<Value, McpError> {
-        info!("MCP initialize called");
+    async fn handle_initialize(&self, params: Option<Value>) -> Result<Value, McpError> {
+        let requested_version = params
+            .as_ref()
+            .and_then(|p| p.get("protocolVersion"))
+            .and_then(|v| v.as_str());
+
+        let negotiated_version = match requested_version {
+            Some(requested) if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) => requested,
+            Some(requested) => {
+                return Err(McpError {
+                    code: -32602,
+                    message: format!(
+                        "Unsupported protocolVersion '{}'; server supports: {}",
+                        requested,
+                        SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+                    ),
+                    data: None,
+                });
+            }
+            // No version requested: negotiate to the server's newest.
+            None => SUPPORTED_PROTOCOL_VERSIONS[0],
+        };
+
+        info!("MCP initialize called (protocolVersion: {})", negotiated_version);
         Ok(json!({
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": negotiated_version,
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "resources": {},
+                "tcl": self.capability_grants.resolved_by_namespace()
             },
             "serverInfo": {
                 "name": "tcl-mcp-server",
@@ -134,6 +416,155 @@ impl HttpMcpServer {
             }
         }))
     }
+
+    async fn handle_resources_list(&self) -> Result<Value, McpError> {
+        let resources = MOLT_BOOK_TOPICS.iter().map(|topic| McpResourceInfo {
+            uri: format!("{}{}", MOLT_BOOK_RESOURCE_SCHEME, topic),
+            name: format!("Molt Book: {}", topic),
+            mime_type: "text/markdown".to_string(),
+        }).collect();
+
+        Ok(json!(McpListResourcesResult { resources }))
+    }
+
+    async fn handle_resources_read(&self, params: Option<Value>) -> Result<Value, McpError> {
+        let params: McpReadResourceParams = params
+            .ok_or_else(|| McpError { code: -32602, message: "Missing params".to_string(), data: None })
+            .and_then(|p| serde_json::from_value(p).map_err(|e| McpError {
+                code: -32602,
+                message: format!("Invalid parameters: {}", e),
+                data: None,
+            }))?;
+
+        let topic = params.uri.strip_prefix(MOLT_BOOK_RESOURCE_SCHEME)
+            .ok_or_else(|| McpError {
+                code: -32602,
+                message: format!("Unsupported resource URI '{}'; expected a '{}' URI", params.uri, MOLT_BOOK_RESOURCE_SCHEME),
+                data: None,
+            })?;
+
+        let text = molt_book_topic_content(topic).ok_or_else(|| McpError {
+            code: -32602,
+            message: format!("Unknown documentation topic: {}. Available topics: {}", topic, MOLT_BOOK_TOPICS.join(", ")),
+            data: None,
+        })?;
+
+        Ok(json!(McpReadResourceResult {
+            contents: vec![McpResourceContents {
+                uri: params.uri,
+                mime_type: "text/markdown".to_string(),
+                text,
+            }],
+        }))
+    }
+
+    /// Reports which tool namespaces are exposed for the caller's scope, which runtime features
+    /// are actually present (via `has_command` probes), and each compiled-in runtime's *actual*
+    /// availability (via `RuntimeConfig::diagnose`'s smoke test), not just `cfg!(feature = ...)`.
+    async fn handle_capabilities(&self, scope: Scope) -> Result<Value, McpError> {
+        let mut namespaces = vec!["bin", "docs"];
+        if self.privileged && scope.allows(Scope::Sbin) {
+            namespaces.push("sbin");
+        }
+
+        let runtime = crate::tcl_runtime::create_runtime();
+        let probes = ["set", "expr", "proc", "foreach", "while", "namespace"];
+        let commands: serde_json::Map<String, Value> = probes
+            .iter()
+            .map(|cmd| (cmd.to_string(), json!(runtime.has_command(cmd))))
+            .collect();
+
+        let runtime_diagnostics: Vec<Value> = crate::tcl_runtime::RuntimeConfig::diagnose()
+            .into_iter()
+            .map(|status| {
+                json!({
+                    "runtime": status.runtime_type.as_str(),
+                    "compiled": status.compiled,
+                    "available": status.probed_ok,
+                    "error": status.error,
+                })
+            })
+            .collect();
+
+        let sandbox = self.tool_box.capabilities().await
+            .map_err(|e| McpError {
+                code: -32602,
+                message: e.to_string(),
+                data: None,
+            })?;
+
+        Ok(json!({
+            "protocolVersions": SUPPORTED_PROTOCOL_VERSIONS,
+            "namespaces": namespaces,
+            "features": sandbox.features,
+            "serverVersion": sandbox.server_version,
+            "privileged": self.privileged,
+            "scope": format!("{:?}", scope).to_lowercase(),
+            "sandbox": sandbox,
+            "runtime": {
+                "name": runtime.name(),
+                "version": runtime.version(),
+                "commands": commands,
+            },
+            "runtimeDiagnostics": runtime_diagnostics,
+            "hardening": self.hardening,
+            "capabilityGrants": self.capability_grants.as_ref(),
+        }))
+    }
+
+    /// Returns every command the active runtime could ever expose, tagged with category and
+    /// safety — `filter`/`category` in `params` narrow it the same way `CommandProvider::get_command_metadata`
+    /// does. Separate from `tcl/capabilities`, which reports the currently-active set; this
+    /// reports the full inventory `capability_grants` files grant against.
+    async fn handle_tcl_commands(&self, params: Option<Value>) -> Result<Value, McpError> {
+        let filter = params.as_ref().and_then(|p| p.get("filter")).and_then(|v| v.as_str());
+        let category = params.as_ref().and_then(|p| p.get("category")).and_then(|v| v.as_str());
+
+        let runtime = crate::tcl_runtime::create_runtime();
+        let provider = CapabilityFactory::create_provider(runtime.name());
+        let commands = provider.get_command_metadata(filter, category);
+
+        Ok(json!({ "commands": commands }))
+    }
+
+    /// Rejects `mcp_name`'s `tools/call` if `capability_grants` is configured and the tool's
+    /// script invokes a TCL command not covered by any grant for its namespace. A no-op when no
+    /// capability files were configured, preserving today's `privileged`-only behavior.
+    async fn enforce_capability_grants(&self, mcp_name: &str) -> Result<(), McpError> {
+        if self.capability_grants.is_empty() {
+            return Ok(());
+        }
+
+        let path = ToolPath::from_mcp_name(mcp_name).map_err(|e| McpError {
+            code: -32602,
+            message: e.to_string(),
+            data: None,
+        })?;
+        let tool_defs = self.tool_box.get_tool_definitions().await.map_err(|e| McpError {
+            code: -32603,
+            message: format!("Failed to get tool definitions: {}", e),
+            data: None,
+        })?;
+        let Some(tool_def) = tool_defs.into_iter().find(|t| t.path == path) else {
+            // Not a known custom tool; fall through and let the normal dispatch report "not found".
+            return Ok(());
+        };
+
+        let required = required_commands(&tool_def.script);
+        let missing = self.capability_grants.missing_commands(&path.namespace.grant_key(), &required);
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(McpError {
+                code: -32603,
+                message: format!(
+                    "'{}' requires TCL command(s) not covered by any active capability grant: {}",
+                    path, missing.join(", ")
+                ),
+                data: None,
+            })
+        }
+    }
     
     async fn handle_tools_list(&self) -> Result<McpListToolsResult, McpError> {
         debug!("MCP tools/list called (privileged: {})", self.privileged);
@@ -167,6 +598,17 @@ impl HttpMcpServer {
                     }
                 }
             })),
+            (ToolPath::bin("tcl_tool_receipt"), "Get a persisted tool's receipt (timestamps, checksum, origin, schema version)", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Full tool path (e.g., '/alice/utils/reverse_string:1.0')"
+                    }
+                },
+                "required": ["path"]
+            })),
             (ToolPath::docs("molt_book"), "Access Molt TCL interpreter documentation and examples", json!({
                 "$schema": "https://json-schema.org/draft/2020-12/schema",
                 "type": "object",
@@ -195,13 +637,133 @@ impl HttpMcpServer {
                 },
                 "required": ["tool_path"]
             })),
+            (ToolPath::bin("tcl_tool_test"), "Run a custom tool's attached test cases and report pass/fail per case", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Full tool path whose attached test cases should be run (e.g., '/alice/utils/reverse:1.0')"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Only run cases whose name contains this substring (optional)"
+                    }
+                },
+                "required": ["path"]
+            })),
+            (ToolPath::bin("tcl_tool_coverage"), "Run a custom tool and report which lines of its script executed", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "tool_path": {
+                        "type": "string",
+                        "description": "Tool path to run with coverage instrumentation (e.g., '/alice/utils/reverse:1.0')"
+                    },
+                    "params": {
+                        "type": "object",
+                        "description": "Parameters to pass to the tool",
+                        "default": {}
+                    }
+                },
+                "required": ["tool_path"]
+            })),
+            (ToolPath::bin("tcl_tool_compose"), "Run a named sequence of tools, interpolating earlier steps' bound output into later steps' params", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "steps": {
+                        "type": "array",
+                        "description": "Ordered steps to run; each runs through the normal tool-call path with its own parameter validation",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "tool_path": { "type": "string" },
+                                "params": {
+                                    "type": "object",
+                                    "description": "Parameters for this step; a string value containing '${name}' is replaced with the output bound to 'name' by an earlier step",
+                                    "default": {}
+                                },
+                                "bind": {
+                                    "type": "string",
+                                    "description": "Name this step's output is bound to, for interpolation into a later step's params (optional)"
+                                }
+                            },
+                            "required": ["tool_path"]
+                        }
+                    }
+                },
+                "required": ["steps"]
+            })),
             (ToolPath::bin("discover_tools"), "Discover and index tools from the filesystem", json!({
                 "$schema": "https://json-schema.org/draft/2020-12/schema",
                 "type": "object",
                 "properties": {}
             })),
+            (ToolPath::bin("exec_batch"), "Execute multiple tools concurrently on a bounded worker pool", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "entries": {
+                        "type": "array",
+                        "description": "Tools to invoke concurrently, in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "tool_path": { "type": "string" },
+                                "params": { "type": "object", "default": {} }
+                            },
+                            "required": ["tool_path"]
+                        }
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "Per-entry timeout in milliseconds (default 30000)",
+                        "default": 30000
+                    }
+                },
+                "required": ["entries"]
+            })),
+            (ToolPath::bin("pipeline"), "Compose tools into a data pipeline, threading each stage's output into the next", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "input": {
+                        "type": "string",
+                        "description": "Initial input fed to the first stage (or split element-wise in separate mode)",
+                        "default": ""
+                    },
+                    "stages": {
+                        "type": "array",
+                        "description": "Ordered stages to run the input through",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "tool_path": { "type": "string" },
+                                "params": { "type": "object", "default": {} },
+                                "input_param": {
+                                    "type": "string",
+                                    "description": "Name of the parameter that receives the previous stage's output"
+                                }
+                            },
+                            "required": ["tool_path", "input_param"]
+                        }
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["buffer", "separate"],
+                        "description": "buffer threads the whole output through as one value; separate splits it into TCL list elements run independently",
+                        "default": "buffer"
+                    },
+                    "init": {
+                        "type": "string",
+                        "description": "Script run once, before the first stage, to set up shared state"
+                    }
+                },
+                "required": ["stages"]
+            })),
         ];
-        
+
         // Add privileged tools only if in privileged mode
         if self.privileged {
             system_tools.push((ToolPath::sbin("tcl_tool_add"), "Add a new TCL tool to the available tools (PRIVILEGED)", json!({
@@ -242,10 +804,34 @@ impl HttpMcpServer {
                                 "name": { "type": "string" },
                                 "description": { "type": "string" },
                                 "required": { "type": "boolean" },
-                                "type_name": { "type": "string" }
+                                "type_name": { "type": "string" },
+                                "default": { "description": "Value injected when this optional parameter is omitted" },
+                                "enum": { "type": "array", "description": "Allowed values; a provided argument outside this set is rejected" },
+                                "min": { "type": "number", "description": "Inclusive lower bound; a provided value that is not a number is rejected" },
+                                "max": { "type": "number", "description": "Inclusive upper bound; a provided value that is not a number is rejected" },
+                                "validate": { "type": "string", "description": "Regex a provided string value must match" }
                             },
                             "required": ["name", "description", "required", "type_name"]
                         }
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "If a tool already exists at this path, replace it instead of failing (default false)",
+                        "default": false
+                    },
+                    "test_cases": {
+                        "type": "array",
+                        "description": "Test cases to attach, runnable later via tcl_tool_test",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "params": { "type": "object", "default": {} },
+                                "expect_output": { "type": "string", "description": "Expected to equal the tool's returned output exactly, if given" },
+                                "expect_error": { "type": "string", "description": "Expected to be a substring of the error message, if the call is expected to fail" }
+                            },
+                            "required": ["name"]
+                        }
                     }
                 },
                 "required": ["user", "package", "name", "description", "script"]
@@ -261,6 +847,28 @@ impl HttpMcpServer {
                 },
                 "required": ["path"]
             })));
+            system_tools.push((ToolPath::sbin("tcl_tool_trust"), "Approve a filesystem-discovered tool at its current content hash (PRIVILEGED)", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Full tool path of a filesystem-discovered tool (e.g., '/alice/utils/reverse_string:1.0')"
+                    }
+                },
+                "required": ["path"]
+            })));
+            system_tools.push((ToolPath::sbin("tcl_tool_revoke"), "Withdraw a tool's trust approval (PRIVILEGED)", json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Full tool path whose trust approval should be withdrawn"
+                    }
+                },
+                "required": ["path"]
+            })));
         }
         
         for (path, description, schema) in system_tools {
@@ -282,58 +890,58 @@ impl HttpMcpServer {
         
         // Add custom tools to the list
         for tool_def in custom_tools {
-            let mut properties = serde_json::Map::new();
-            let mut required = Vec::new();
-            
-            for param in &tool_def.parameters {
-                let json_type = match param.type_name.to_lowercase().as_str() {
-                    "string" | "str" | "text" => "string",
-                    "number" | "float" | "double" | "real" => "number",
-                    "integer" | "int" | "long" => "integer", 
-                    "boolean" | "bool" => "boolean",
-                    "array" | "list" => "array",
-                    "object" | "dict" | "map" => "object",
-                    "null" | "nil" | "none" => "null",
-                    _ => "string"
-                };
-                
-                properties.insert(
-                    param.name.clone(),
-                    json!({
-                        "type": json_type,
-                        "description": param.description,
-                    }),
-                );
-                
-                if param.required {
-                    required.push(param.name.clone());
-                }
-            }
-            
-            let mut schema_obj = serde_json::Map::new();
-            schema_obj.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
-            schema_obj.insert("type".to_string(), json!("object"));
-            schema_obj.insert("properties".to_string(), json!(properties));
-            
-            if !required.is_empty() {
-                schema_obj.insert("required".to_string(), json!(required));
-            }
-            
-            let input_schema = serde_json::Value::Object(schema_obj);
-            
             tools.push(McpToolInfo {
                 name: tool_def.path.to_mcp_name(),
                 description: Some(format!("{} [{}]", tool_def.description, tool_def.path)),
-                input_schema,
+                input_schema: input_schema_from_parameters(&tool_def.parameters),
             });
         }
-        
+
+        // Merge in tools owned by out-of-process plugins (see `PluginManager`), tagged as
+        // externally provided the same way a custom tool's description carries its `ToolPath`.
+        for plugin_tool in self.plugins.read().await.list_tools() {
+            tools.push(McpToolInfo {
+                name: plugin_tool.mcp_name,
+                description: Some(format!(
+                    "{} [externally provided by plugin '{}']",
+                    plugin_tool.definition.description, plugin_tool.plugin_name
+                )),
+                input_schema: input_schema_from_parameters(&plugin_tool.definition.parameters),
+            });
+        }
+
         Ok(McpListToolsResult { tools })
     }
-    
-    async fn handle_tools_call(&self, params: McpCallToolParams) -> Result<McpCallToolResult, McpError> {
-        info!("Calling tool: {} (privileged: {})", params.name, self.privileged);
-        
+
+    async fn handle_tools_call(&self, params: McpCallToolParams, scope: Scope, tool_access: &ToolAccess, identity: Option<CallerIdentity>) -> Result<McpCallToolResult, McpError> {
+        info!("Calling tool: {} (privileged: {}, scope: {:?})", params.name, self.privileged, scope);
+
+        if params.name.starts_with("sbin___") && !scope.allows(Scope::Sbin) {
+            return Err(McpError {
+                code: -32603,
+                message: format!("'{}' requires sbin scope", params.name),
+                data: None,
+            });
+        }
+
+        if !tool_access.permits(&params.name) {
+            return Err(McpError {
+                code: ERROR_CODE_TOOL_FORBIDDEN,
+                message: format!("'{}' is not in this API key's allowed tool set", params.name),
+                data: None,
+            });
+        }
+
+        if let Some(schema) = self.find_tool_schema(&params.name).await? {
+            if let Err(errors) = validate_arguments_against_schema(&schema, &params.arguments) {
+                return Err(McpError {
+                    code: -32602,
+                    message: format!("Invalid arguments for '{}': {}", params.name, errors.join("; ")),
+                    data: Some(json!({ "fields": errors })),
+                });
+            }
+        }
+
         let result = match params.name.as_str() {
             "bin___tcl_execute" => {
                 let request: TclExecuteRequest = serde_json::from_value(params.arguments)
@@ -342,68 +950,299 @@ impl HttpMcpServer {
                         message: format!("Invalid parameters: {}", e),
                         data: None,
                     })?;
-                self.tool_box.tcl_execute(request).await
+                self.tool_box.tcl_execute(request).await
+            }
+            "sbin___tcl_tool_add" => {
+                if !self.privileged {
+                    return Err(McpError {
+                        code: -32603,
+                        message: "Tool management requires --privileged mode".to_string(),
+                        data: None,
+                    });
+                }
+                let request: TclToolAddRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                if let Some(registry) = &self.registry {
+                    if let Err(failure) = registry.verify_udf(&request.name, &request.script) {
+                        return Err(McpError {
+                            code: ERROR_CODE_UDF_REJECTED,
+                            message: format!("UDF '{}' rejected by signed registry: {failure}", request.name),
+                            data: None,
+                        });
+                    }
+                }
+                match &identity {
+                    Some(identity) => self.tool_box.tcl_tool_add_as(&tool_management_principal(identity), request).await,
+                    None => self.tool_box.tcl_tool_add(request).await,
+                }
+            }
+            "sbin___tcl_tool_remove" => {
+                if !self.privileged {
+                    return Err(McpError {
+                        code: -32603,
+                        message: "Tool management requires --privileged mode".to_string(),
+                        data: None,
+                    });
+                }
+                let request: TclToolRemoveRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                match &identity {
+                    Some(identity) => self.tool_box.tcl_tool_remove_as(&tool_management_principal(identity), request).await,
+                    None => self.tool_box.tcl_tool_remove(request).await,
+                }
+            }
+            "sbin___tcl_tool_trust" => {
+                if !self.privileged {
+                    return Err(McpError {
+                        code: -32603,
+                        message: "Tool management requires --privileged mode".to_string(),
+                        data: None,
+                    });
+                }
+                let request: TclToolTrustRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.tcl_tool_trust(request).await
+            }
+            "sbin___tcl_tool_revoke" => {
+                if !self.privileged {
+                    return Err(McpError {
+                        code: -32603,
+                        message: "Tool management requires --privileged mode".to_string(),
+                        data: None,
+                    });
+                }
+                let request: TclToolRevokeRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.tcl_tool_revoke(request).await
+            }
+            "bin___tcl_tool_list" => {
+                let request: TclToolListRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.tcl_tool_list(request).await
+            }
+            "bin___tcl_tool_receipt" => {
+                let request: TclToolReceiptRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.tcl_tool_receipt(request).await
+            }
+            "bin___exec_tool" => {
+                let request: TclExecToolRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.exec_tool(request).await
+            }
+            "bin___tcl_tool_test" => {
+                let request: TclToolTestRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.tcl_tool_test(request).await
             }
-            "sbin___tcl_tool_add" => {
-                if !self.privileged {
-                    return Err(McpError {
-                        code: -32603,
-                        message: "Tool management requires --privileged mode".to_string(),
-                        data: None,
-                    });
-                }
-                let request: TclToolAddRequest = serde_json::from_value(params.arguments)
+            "bin___tcl_tool_coverage" => {
+                let request: TclToolCoverageRequest = serde_json::from_value(params.arguments)
                     .map_err(|e| McpError {
                         code: -32602,
                         message: format!("Invalid parameters: {}", e),
                         data: None,
                     })?;
-                self.tool_box.tcl_tool_add(request).await
+                self.tool_box.tcl_tool_coverage(request).await
             }
-            "sbin___tcl_tool_remove" => {
-                if !self.privileged {
-                    return Err(McpError {
-                        code: -32603,
-                        message: "Tool management requires --privileged mode".to_string(),
-                        data: None,
-                    });
-                }
-                let request: TclToolRemoveRequest = serde_json::from_value(params.arguments)
+            "bin___tcl_tool_compose" => {
+                let request: TclComposeRequest = serde_json::from_value(params.arguments)
                     .map_err(|e| McpError {
                         code: -32602,
                         message: format!("Invalid parameters: {}", e),
                         data: None,
                     })?;
-                self.tool_box.tcl_tool_remove(request).await
+                self.tool_box.tcl_tool_compose(request).await
             }
-            "bin___tcl_tool_list" => {
-                let request: TclToolListRequest = serde_json::from_value(params.arguments)
+            "bin___discover_tools" => {
+                let request: TclDiscoverToolsRequest = serde_json::from_value(params.arguments)
                     .map_err(|e| McpError {
                         code: -32602,
                         message: format!("Invalid parameters: {}", e),
                         data: None,
                     })?;
-                self.tool_box.tcl_tool_list(request).await
+                self.tool_box.discover_tools(request).await
             }
-            "bin___exec_tool" => {
-                let request: TclExecToolRequest = serde_json::from_value(params.arguments)
+            "bin___exec_batch" => {
+                let request: TclExecBatchRequest = serde_json::from_value(params.arguments)
                     .map_err(|e| McpError {
                         code: -32602,
                         message: format!("Invalid parameters: {}", e),
                         data: None,
                     })?;
-                self.tool_box.exec_tool(request).await
+                self.tool_box.exec_batch(request).await
             }
-            "bin___discover_tools" => {
-                self.tool_box.discover_tools().await
+            "bin___pipeline" => {
+                let request: TclPipelineRequest = serde_json::from_value(params.arguments)
+                    .map_err(|e| McpError {
+                        code: -32602,
+                        message: format!("Invalid parameters: {}", e),
+                        data: None,
+                    })?;
+                self.tool_box.pipeline(request).await
             }
             "docs___molt_book" => {
                 let topic = params.arguments.get("topic")
                     .and_then(|v| v.as_str())
                     .unwrap_or("overview");
-                
-                match topic {
-                    "overview" => Ok(format!(r#"# Molt TCL Interpreter Overview
+
+                molt_book_topic_content(topic).ok_or_else(|| anyhow::anyhow!(
+                    "Unknown documentation topic: {}. Available topics: {}",
+                    topic, MOLT_BOOK_TOPICS.join(", ")
+                ))
+            }
+            mcp_name => {
+                match self.plugins.read().await.call(mcp_name, params.arguments.clone()).await {
+                    Ok(Some(output)) => Ok(output),
+                    Ok(None) => {
+                        if let Err(e) = self.enforce_capability_grants(mcp_name).await {
+                            return Err(e);
+                        }
+                        self.tool_box.execute_custom_tool(mcp_name, params.arguments).await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        
+        match result {
+            Ok(text) => Ok(McpCallToolResult {
+                content: vec![McpContent::Text { text }],
+                is_error: false,
+            }),
+            Err(e) => {
+                // Report tool-level failures as a successful call carrying an error-flagged
+                // result rather than a JSON-RPC error, so the client keeps whatever partial
+                // output the tool produced (e.to_string()) alongside a machine-readable error.
+                Ok(McpCallToolResult {
+                    content: vec![
+                        McpContent::Text { text: e.to_string() },
+                        McpContent::Json { data: json!({ "error": e.to_string() }) },
+                    ],
+                    is_error: true,
+                })
+            }
+        }
+    }
+
+    /// Looks up a tool's `inputSchema` by its MCP name, reusing the same schema construction
+    /// `tools/list` reports so built-ins and custom tools are validated against the same source
+    /// of truth. Returns `None` if no tool by that name is registered (dispatch will report
+    /// "not found" on its own).
+    async fn find_tool_schema(&self, name: &str) -> Result<Option<Value>, McpError> {
+        let tools = self.handle_tools_list().await?;
+        Ok(tools.tools.into_iter().find(|tool| tool.name == name).map(|tool| tool.input_schema))
+    }
+
+    /// Runs a small pipeline of `tools/call` invocations in one round trip, substituting
+    /// `bind` references (`$steps[N].result`) into later steps' arguments from earlier
+    /// steps' text content before dispatching each through `handle_tools_call`.
+    async fn handle_tools_chain(&self, request: McpChainRequest, scope: Scope, tool_access: &ToolAccess, identity: Option<CallerIdentity>) -> Result<McpChainResult, McpError> {
+        if request.steps.len() > MAX_CHAIN_STEPS {
+            return Err(McpError {
+                code: -32602,
+                message: format!("Chain exceeds max of {} steps", MAX_CHAIN_STEPS),
+                data: None,
+            });
+        }
+
+        let mut results = Vec::with_capacity(request.steps.len());
+        let mut step_results: Vec<Option<McpCallToolResult>> = Vec::with_capacity(request.steps.len());
+
+        for step in request.steps {
+            let mut arguments = step.arguments;
+            for (key, reference) in &step.bind {
+                let value = resolve_chain_reference(reference, &step_results)
+                    .map_err(|message| McpError { code: -32602, message, data: None })?;
+                let map = arguments.as_object_mut().ok_or_else(|| McpError {
+                    code: -32602,
+                    message: format!("Step '{}' has a bind but non-object arguments", step.name),
+                    data: None,
+                })?;
+                map.insert(key.clone(), value);
+            }
+
+            let params = McpCallToolParams { name: step.name.clone(), arguments };
+            match self.handle_tools_call(params, scope, tool_access, identity.clone()).await {
+                Ok(result) if result.is_error => {
+                    let stop = !request.continue_on_error;
+                    results.push(McpChainStepResult {
+                        name: step.name,
+                        success: false,
+                        content: Some(result.content.clone()),
+                        error: Some("tool reported isError".to_string()),
+                    });
+                    step_results.push(Some(result));
+                    if stop {
+                        break;
+                    }
+                }
+                Ok(result) => {
+                    results.push(McpChainStepResult {
+                        name: step.name,
+                        success: true,
+                        content: Some(result.content.clone()),
+                        error: None,
+                    });
+                    step_results.push(Some(result));
+                }
+                Err(e) => {
+                    let stop = !request.continue_on_error;
+                    results.push(McpChainStepResult {
+                        name: step.name,
+                        success: false,
+                        content: None,
+                        error: Some(e.message),
+                    });
+                    step_results.push(None);
+                    if stop {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(McpChainResult { results })
+    }
+}
+
+/// Markdown content for one `docs___molt_book` topic / `molt-book://{topic}` resource,
+/// shared by both so the two can't drift apart on what a topic actually contains.
+/// `None` for an unrecognized topic.
+fn molt_book_topic_content(topic: &str) -> Option<String> {
+        match topic {
+            "overview" => Some(format!(r#"# Molt TCL Interpreter Overview
 
 ## What is Molt?
 Molt is a TCL (Tool Command Language) interpreter implemented in Rust. It provides a memory-safe, 
@@ -422,7 +1261,7 @@ embeddable scripting language with familiar TCL syntax.
 - Source Documentation: https://github.com/wduquette/molt/tree/master/molt-book/src
 
 Use 'basic_syntax', 'commands', 'examples', or 'links' for more specific information."#)),
-                    "basic_syntax" => Ok(format!(r#"# TCL Basic Syntax
+            "basic_syntax" => Some(format!(r#"# TCL Basic Syntax
 
 ## Variables
 ```tcl
@@ -467,7 +1306,7 @@ proc greet {{name}} {{
 set message [greet "World"]
 puts $message
 ```"#)),
-                    "commands" => Ok(format!(r#"# Common TCL Commands in Molt
+            "commands" => Some(format!(r#"# Common TCL Commands in Molt
 
 ## String Operations
 - `string length $str` - Get string length
@@ -502,7 +1341,7 @@ puts $message
 - `set varName $value` - Set variable
 - `unset varName` - Delete variable
 - `global varName` - Access global variable"#)),
-                    "examples" => Ok(format!(r#"# TCL Examples
+            "examples" => Some(format!(r#"# TCL Examples
 
 ## Example 1: Calculator
 ```tcl
@@ -565,7 +1404,7 @@ proc reverse_string {{str}} {{
 puts [word_count "Hello world from TCL"]  ;# 4
 puts [reverse_string "hello"]              ;# olleh
 ```"#)),
-                    "links" => Ok(format!(r#"# Molt TCL Documentation Links
+            "links" => Some(format!(r#"# Molt TCL Documentation Links
 
 ## Official Documentation
 - **Molt Book**: https://wduquette.github.io/molt/
@@ -594,25 +1433,152 @@ puts [reverse_string "hello"]              ;# olleh
 
 Note: Molt implements a subset of full TCL but covers the core language features.
 For Molt-specific capabilities and limitations, refer to the Molt Book."#)),
-                    _ => Err(anyhow::anyhow!("Unknown documentation topic: {}. Available topics: overview, basic_syntax, commands, examples, links", topic))
+            _ => None,
+        }
+}
+
+
+/// Builds a JSON Schema `inputSchema` from a tool's `ParameterDefinition`s, shared by custom TCL
+/// tools and plugin-owned tools (see `PluginToolDefinition`) in `handle_tools_list` so the two
+/// don't drift on how `default`/`enum`/`min`/`max`/`validate` map onto JSON Schema keywords.
+fn input_schema_from_parameters(parameters: &[ParameterDefinition]) -> Value {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+
+    for param in parameters {
+        let json_type = match param.type_name.to_lowercase().as_str() {
+            "string" | "str" | "text" => "string",
+            "number" | "float" | "double" | "real" => "number",
+            "integer" | "int" | "long" => "integer",
+            "boolean" | "bool" => "boolean",
+            "array" | "list" => "array",
+            "object" | "dict" | "map" => "object",
+            "null" | "nil" | "none" => "null",
+            _ => "string"
+        };
+
+        let mut property = json!({
+            "type": json_type,
+            "description": param.description,
+        });
+        let property_obj = property.as_object_mut().expect("object literal");
+        if let Some(default) = &param.default {
+            property_obj.insert("default".to_string(), typed_schema_value(&param.type_name, default));
+        }
+        if let Some(enum_values) = &param.enum_values {
+            property_obj.insert("enum".to_string(), json!(enum_values.iter()
+                .map(|v| typed_schema_value(&param.type_name, v))
+                .collect::<Vec<_>>()));
+        }
+        if let Some(min) = param.min {
+            property_obj.insert("minimum".to_string(), json!(min));
+        }
+        if let Some(max) = param.max {
+            property_obj.insert("maximum".to_string(), json!(max));
+        }
+        if let Some(pattern) = &param.validate {
+            property_obj.insert("pattern".to_string(), json!(pattern));
+        }
+
+        properties.insert(param.name.clone(), property);
+
+        if param.required {
+            required.push(param.name.clone());
+        }
+    }
+
+    let mut schema_obj = serde_json::Map::new();
+    schema_obj.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+    schema_obj.insert("type".to_string(), json!("object"));
+    schema_obj.insert("properties".to_string(), json!(properties));
+
+    if !required.is_empty() {
+        schema_obj.insert("required".to_string(), json!(required));
+    }
+
+    serde_json::Value::Object(schema_obj)
+}
+
+/// Converts a `ParameterDefinition`'s textual `default`/`enum_values` entries into a JSON value
+/// typed per the parameter's declared `type_name`, so the emitted schema's `default`/`enum` look
+/// like client-supplied values (e.g. `5`, not `"5"`) rather than always a string.
+fn typed_schema_value(type_name: &str, raw: &str) -> Value {
+    match type_name.to_lowercase().as_str() {
+        "number" | "float" | "double" | "real" => raw.parse::<f64>().map(|n| json!(n)).unwrap_or_else(|_| json!(raw)),
+        "integer" | "int" | "long" => raw.parse::<i64>().map(|n| json!(n)).unwrap_or_else(|_| json!(raw)),
+        "boolean" | "bool" => raw.parse::<bool>().map(|b| json!(b)).unwrap_or_else(|_| json!(raw)),
+        _ => json!(raw),
+    }
+}
+
+/// Resolves a `bind` reference of the form `$steps[N].result` against the results accumulated
+/// so far in a `tools/chain` run, pulling the text of the referenced step's first content item.
+fn resolve_chain_reference(reference: &str, step_results: &[Option<McpCallToolResult>]) -> Result<Value, String> {
+    let rest = reference.strip_prefix("$steps[")
+        .ok_or_else(|| format!("Unsupported reference '{}': expected '$steps[N].result'", reference))?;
+    let close = rest.find(']')
+        .ok_or_else(|| format!("Unsupported reference '{}': missing ']'", reference))?;
+    let index: usize = rest[..close].parse()
+        .map_err(|_| format!("Unsupported reference '{}': '{}' is not a step index", reference, &rest[..close]))?;
+    if &rest[close + 1..] != ".result" {
+        return Err(format!("Unsupported reference '{}': expected '.result' after the index", reference));
+    }
+
+    let step = step_results.get(index)
+        .ok_or_else(|| format!("Reference '{}' points past step {}", reference, index))?;
+    let result = step.as_ref()
+        .ok_or_else(|| format!("Reference '{}' points at step {}, which failed", reference, index))?;
+    match result.content.first() {
+        Some(McpContent::Text { text }) => Ok(Value::String(text.clone())),
+        Some(McpContent::Json { data }) => Ok(data.clone()),
+        None => Err(format!("Step {} produced no content to bind", index)),
+    }
+}
+
+/// Minimal structural JSON Schema check: confirms `required` properties are present and that
+/// each property present in `properties` matches its declared `type`. This doesn't attempt full
+/// JSON Schema (nested `$ref`s, composition, formats) — just enough to catch the missing-field
+/// and wrong-type mistakes `handle_tools_call` used to let through to the tool itself.
+fn validate_arguments_against_schema(schema: &Value, arguments: &Value) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
+        for field in required {
+            if let Some(name) = field.as_str() {
+                if arguments.get(name).is_none() {
+                    errors.push(format!("missing required property '{}'", name));
                 }
             }
-            mcp_name => {
-                self.tool_box.execute_custom_tool(mcp_name, params.arguments).await
+        }
+    }
+
+    if let Some(properties) = schema.get("properties").and_then(|v| v.as_object()) {
+        if let Some(provided) = arguments.as_object() {
+            for (name, value) in provided {
+                let Some(expected_type) = properties.get(name).and_then(|p| p.get("type")).and_then(|t| t.as_str()) else {
+                    continue;
+                };
+                if !json_value_matches_type(value, expected_type) {
+                    errors.push(format!("property '{}' should be of type '{}'", name, expected_type));
+                }
             }
-        };
-        
-        match result {
-            Ok(text) => Ok(McpCallToolResult {
-                content: vec![McpContent::Text { text }],
-            }),
-            Err(e) => Err(McpError {
-                code: -32603,
-                message: e.to_string(),
-                data: None,
-            }),
         }
     }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+fn json_value_matches_type(value: &Value, expected_type: &str) -> bool {
+    match expected_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
 }
 
 // HTTP handlers
@@ -624,57 +1590,157 @@ async fn health_check() -> impl IntoResponse {
     })
 }
 
-async fn handle_mcp_request(
-    State(server): State<HttpMcpServer>,
-    Json(request): Json<McpRequest>,
-) -> Result<Json<McpResponse>, Response> {
-    debug!("Received MCP request: {:?}", request);
-    
+/// Dispatches one already-parsed `McpRequest` and wraps its outcome in a `McpResponse` envelope.
+/// Shared by the single-request and batch paths of `handle_mcp_request` so both dispatch methods
+/// identically.
+async fn process_mcp_request(server: &HttpMcpServer, scope: Scope, tool_access: &ToolAccess, identity: Option<CallerIdentity>, request: McpRequest) -> McpResponse {
+    let id = request.id.clone();
+
     let result = match request.method.as_str() {
-        "initialize" => server.handle_initialize().await,
+        "initialize" => server.handle_initialize(request.params.clone()).await,
+        "tcl/capabilities" => server.handle_capabilities(scope).await,
+        "tcl/commands" => server.handle_tcl_commands(request.params.clone()).await,
         "tools/list" => server.handle_tools_list().await.map(|r| serde_json::to_value(r).unwrap()),
-        "tools/call" => {
-            if let Some(params) = request.params {
-                let call_params: McpCallToolParams = serde_json::from_value(params)
-                    .map_err(|e| McpError {
-                        code: -32602,
-                        message: format!("Invalid parameters: {}", e),
-                        data: None,
-                    })?;
-                server.handle_tools_call(call_params).await.map(|r| serde_json::to_value(r).unwrap())
-            } else {
-                Err(McpError {
+        "resources/list" => server.handle_resources_list().await,
+        "resources/read" => server.handle_resources_read(request.params.clone()).await,
+        "tools/call" => match request.params {
+            Some(params) => match serde_json::from_value::<McpCallToolParams>(params) {
+                Ok(call_params) => server.handle_tools_call(call_params, scope, tool_access, identity).await.map(|r| serde_json::to_value(r).unwrap()),
+                Err(e) => Err(McpError {
                     code: -32602,
-                    message: "Missing parameters".to_string(),
+                    message: format!("Invalid parameters: {}", e),
                     data: None,
-                })
-            }
-        }
+                }),
+            },
+            None => Err(McpError {
+                code: -32602,
+                message: "Missing parameters".to_string(),
+                data: None,
+            }),
+        },
+        "tools/chain" => match request.params {
+            Some(params) => match serde_json::from_value::<McpChainRequest>(params) {
+                Ok(chain_params) => server.handle_tools_chain(chain_params, scope, tool_access, identity).await.map(|r| serde_json::to_value(r).unwrap()),
+                Err(e) => Err(McpError {
+                    code: -32602,
+                    message: format!("Invalid parameters: {}", e),
+                    data: None,
+                }),
+            },
+            None => Err(McpError {
+                code: -32602,
+                message: "Missing parameters".to_string(),
+                data: None,
+            }),
+        },
         _ => Err(McpError {
             code: -32601,
             message: format!("Method not found: {}", request.method),
             data: None,
         }),
     };
-    
-    let response = match result {
+
+    match result {
         Ok(result) => McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
             result: Some(result),
             error: None,
-            id: request.id,
+            id,
         },
         Err(error) => McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
             result: None,
             error: Some(error),
-            id: request.id,
+            id,
         },
-    };
-    
-    Ok(Json(response))
+    }
+}
+
+/// Accepts either a single JSON-RPC request object or a batch array at `/mcp`, per the JSON-RPC
+/// 2.0 spec. Batch members are dispatched concurrently with `tokio::spawn` (the executor pool
+/// backing `tools/call` already supports this) and collected back into a result array in the
+/// same order, correlated by `id`. Members with no `id` are notifications: they still run, but
+/// produce no entry in the response array.
+async fn handle_mcp_request(
+    State(server): State<HttpMcpServer>,
+    scope: Option<axum::extract::Extension<Scope>>,
+    tool_access: Option<axum::extract::Extension<ToolAccess>>,
+    identity: Option<axum::extract::Extension<CallerIdentity>>,
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, Response> {
+    let scope = scope.map(|axum::extract::Extension(scope)| scope).unwrap_or(Scope::Sbin);
+    let tool_access = tool_access.map(|axum::extract::Extension(tool_access)| tool_access).unwrap_or_default();
+    let identity = identity.map(|axum::extract::Extension(identity)| identity);
+
+    if let Some(entries) = body.as_array() {
+        debug!("Received MCP batch request with {} members", entries.len());
+
+        let handles: Vec<_> = entries.iter().cloned().map(|entry| {
+            let server = server.clone();
+            let tool_access = tool_access.clone();
+            let identity = identity.clone();
+            tokio::spawn(async move {
+                match serde_json::from_value::<McpRequest>(entry) {
+                    Ok(request) => {
+                        let is_notification = request.id.is_none();
+                        let response = process_mcp_request(&server, scope, &tool_access, identity, request).await;
+                        if is_notification { None } else { Some(response) }
+                    }
+                    Err(e) => Some(McpResponse {
+                        jsonrpc: JSONRPC_VERSION.to_string(),
+                        result: None,
+                        error: Some(McpError {
+                            code: -32700,
+                            message: format!("Parse error: {}", e),
+                            data: None,
+                        }),
+                        id: None,
+                    }),
+                }
+            })
+        }).collect();
+
+        let mut responses = Vec::with_capacity(handles.len());
+        for handle in handles {
+            let outcome = handle.await.map_err(|e| {
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": format!("Batch member task panicked: {}", e) }))).into_response()
+            })?;
+            if let Some(response) = outcome {
+                responses.push(response);
+            }
+        }
+
+        Ok(Json(serde_json::to_value(responses).unwrap()))
+    } else {
+        let request: McpRequest = serde_json::from_value(body).map_err(|e| {
+            (StatusCode::BAD_REQUEST, Json(json!({ "error": format!("Parse error: {}", e) }))).into_response()
+        })?;
+        debug!("Received MCP request: {:?}", request);
+
+        let response = process_mcp_request(&server, scope, &tool_access, identity, request).await;
+        Ok(Json(serde_json::to_value(response).unwrap()))
+    }
+}
+
+async fn handle_initialize(
+    State(server): State<HttpMcpServer>,
+    body: Option<Json<Value>>,
+) -> impl IntoResponse {
+    let params = body.map(|Json(value)| value);
+    match server.handle_initialize(params).await {
+        Ok(result) => (StatusCode::OK, Json(result)),
+        Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
+            "error": error.message
+        }))),
+    }
 }
 
-async fn handle_initialize(State(server): State<HttpMcpServer>) -> impl IntoResponse {
-    match server.handle_initialize().await {
+async fn handle_capabilities(
+    State(server): State<HttpMcpServer>,
+    scope: Option<axum::extract::Extension<Scope>>,
+) -> impl IntoResponse {
+    let scope = scope.map(|axum::extract::Extension(scope)| scope).unwrap_or(Scope::Sbin);
+    match server.handle_capabilities(scope).await {
         Ok(result) => (StatusCode::OK, Json(result)),
         Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
             "error": error.message
@@ -691,11 +1757,57 @@ async fn handle_tools_list(State(server): State<HttpMcpServer>) -> impl IntoResp
     }
 }
 
+async fn handle_resources_list(State(server): State<HttpMcpServer>) -> impl IntoResponse {
+    match server.handle_resources_list().await {
+        Ok(result) => (StatusCode::OK, Json(result)),
+        Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
+            "error": error.message
+        }))),
+    }
+}
+
+async fn handle_resources_read(
+    State(server): State<HttpMcpServer>,
+    body: Option<Json<Value>>,
+) -> impl IntoResponse {
+    let params = body.map(|Json(value)| value);
+    match server.handle_resources_read(params).await {
+        Ok(result) => (StatusCode::OK, Json(result)),
+        Err(error) => (mcp_error_status(&error), Json(json!({
+            "error": error.message
+        }))),
+    }
+}
+
 async fn handle_tools_call(
     State(server): State<HttpMcpServer>,
+    scope: Option<axum::extract::Extension<Scope>>,
+    tool_access: Option<axum::extract::Extension<ToolAccess>>,
+    identity: Option<axum::extract::Extension<CallerIdentity>>,
     Json(params): Json<McpCallToolParams>,
 ) -> impl IntoResponse {
-    match server.handle_tools_call(params).await {
+    let scope = scope.map(|axum::extract::Extension(scope)| scope).unwrap_or(Scope::Sbin);
+    let tool_access = tool_access.map(|axum::extract::Extension(tool_access)| tool_access).unwrap_or_default();
+    let identity = identity.map(|axum::extract::Extension(identity)| identity);
+    match server.handle_tools_call(params, scope, &tool_access, identity).await {
+        Ok(result) => (StatusCode::OK, Json(result)),
+        Err(error) => (mcp_error_status(&error), Json(json!({
+            "error": error.message
+        }))),
+    }
+}
+
+async fn handle_tools_chain(
+    State(server): State<HttpMcpServer>,
+    scope: Option<axum::extract::Extension<Scope>>,
+    tool_access: Option<axum::extract::Extension<ToolAccess>>,
+    identity: Option<axum::extract::Extension<CallerIdentity>>,
+    Json(request): Json<McpChainRequest>,
+) -> impl IntoResponse {
+    let scope = scope.map(|axum::extract::Extension(scope)| scope).unwrap_or(Scope::Sbin);
+    let tool_access = tool_access.map(|axum::extract::Extension(tool_access)| tool_access).unwrap_or_default();
+    let identity = identity.map(|axum::extract::Extension(identity)| identity);
+    match server.handle_tools_chain(request, scope, &tool_access, identity).await {
         Ok(result) => (StatusCode::OK, Json(result)),
         Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
             "error": error.message
@@ -703,19 +1815,209 @@ async fn handle_tools_call(
     }
 }
 
-// API key generation endpoint (unprotected for initial setup)
-async fn generate_api_key_endpoint() -> impl IntoResponse {
+/// Optional body for `POST /auth/generate-key`. Leaving both fields unset mints the legacy
+/// unrestricted key (persisted via `TCL_MCP_TOKEN_FILE` if configured); setting either mints a
+/// least-privilege key for a single untrusted caller instead, returned directly since it's meant
+/// to be handed to that caller right now rather than shared through the token file.
+#[derive(Debug, Deserialize)]
+struct GenerateApiKeyRequest {
+    /// MCP tool names (e.g. `"bin___tcl_execute"`) this key may call. Empty means unrestricted.
+    #[serde(default)]
+    scopes: Vec<String>,
+    /// Key lifetime in seconds. Omitted means the key never expires on its own.
+    ttl_seconds: Option<u64>,
+    /// bin/sbin level to grant. Defaults to `sbin` to match the legacy endpoint's behavior.
+    #[serde(default = "default_generated_key_scope")]
+    scope: Scope,
+    /// Who the minted key is for, so a request authenticated with it carries a `CallerIdentity`
+    /// of that name rather than the admin's own. Defaults to the caller's own identity (if any),
+    /// matching the pre-existing behavior of minting a key for yourself. Applied only when
+    /// `scopes` or `ttl_seconds` is also set; the legacy unrestricted key carries no owner.
+    owner: Option<String>,
+}
+
+impl Default for GenerateApiKeyRequest {
+    fn default() -> Self {
+        Self { scopes: Vec::new(), ttl_seconds: None, scope: default_generated_key_scope(), owner: None }
+    }
+}
+
+fn default_generated_key_scope() -> Scope {
+    Scope::Sbin
+}
+
+/// API key generation endpoint. Unprotected only in the sense that it's reachable before any key
+/// exists at all (`auth_middleware` skips extension-setting while `!auth_config.is_enabled()`, so
+/// there's no caller `Scope` to check yet during initial setup); once at least one key is
+/// configured, every request — including this one — carries an authenticated `Scope`, and minting
+/// a new key requires `sbin` on it exactly like `rotate_api_key_endpoint`/`revoke_api_key_endpoint`
+/// do, so a `bin`-scoped or tool-restricted caller can't hand itself a broader key than it already
+/// holds.
+async fn generate_api_key_endpoint(
+    State(auth_config): State<AuthConfig>,
+    scope: Option<axum::extract::Extension<Scope>>,
+    identity: Option<axum::extract::Extension<CallerIdentity>>,
+    body: Option<Json<GenerateApiKeyRequest>>,
+) -> impl IntoResponse {
+    if let Some(axum::extract::Extension(scope)) = scope {
+        if !scope.allows(Scope::Sbin) {
+            return (StatusCode::FORBIDDEN, Json(json!({
+                "error": "Generating API keys requires sbin scope"
+            }))).into_response();
+        }
+    }
+    let caller = identity.map(|axum::extract::Extension(CallerIdentity(name))| name);
+
+    let request = body.map(|Json(r)| r).unwrap_or_default();
+    let owner = request.owner.clone().or(caller);
+
+    if !request.scopes.is_empty() || request.ttl_seconds.is_some() {
+        let tools = if request.scopes.is_empty() {
+            ToolAccess::All
+        } else {
+            ToolAccess::Allowed(request.scopes.iter().cloned().collect())
+        };
+        let new_key = auth_config.key_store.issue_scoped(request.scope, tools, request.ttl_seconds, owner);
+        let key_hash = crate::auth::hash_api_key(&new_key);
+
+        return (StatusCode::OK, Json(json!({
+            "api_key": new_key,
+            "hash": key_hash,
+            "scopes": request.scopes,
+            "ttl_seconds": request.ttl_seconds,
+            "note": "This key is scoped to the listed tools (empty means unrestricted) and is not written to TCL_MCP_TOKEN_FILE; hand it directly to the caller it was minted for."
+        }))).into_response();
+    }
+
     let new_key = crate::auth::generate_api_key();
     let key_hash = crate::auth::hash_api_key(&new_key);
-    
+
+    // When TCL_MCP_TOKEN_FILE is configured, the key is written straight to that file (0600)
+    // and registered in the in-memory store; only the file path and hash go in the response and
+    // logs, so the secret itself never lands in a response body, proxy log, or terminal scrollback.
+    match crate::auth::persist_generated_key(&auth_config, &new_key, request.scope) {
+        Some(path) => (StatusCode::OK, Json(json!({
+            "hash": key_hash,
+            "token_file": path.display().to_string(),
+            "instructions": {
+                "step_1": format!("The generated key was appended to {} instead of this response", path.display()),
+                "step_2": "Read the key from the token file and use it as 'Authorization: Bearer <key>' or 'X-API-Key: <key>'",
+                "step_3": "The server loads TCL_MCP_TOKEN_FILE on startup, so the key is already active"
+            },
+            "note": "The raw key is not returned here or logged; retrieve it from the token file."
+        }))),
+        None => (StatusCode::OK, Json(json!({
+            "api_key": new_key,
+            "hash": key_hash,
+            "instructions": {
+                "step_1": "Set TCL_MCP_API_KEY environment variable to the api_key value",
+                "step_2": "Restart the server",
+                "step_3": "Use the api_key in Authorization header: 'Bearer <api_key>' or 'X-API-Key: <api_key>'"
+            },
+            "note": "Store the api_key securely. The hash is for verification purposes only."
+        }))),
+    }.into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct RotateApiKeyRequest {
+    /// Seconds the caller's current key stays valid after rotation, so in-flight clients have
+    /// time to switch to the new key before it stops being honored.
+    #[serde(default = "default_grace_secs")]
+    grace_secs: u64,
+}
+
+fn default_grace_secs() -> u64 {
+    DEFAULT_KEY_ROTATION_GRACE_SECS
+}
+
+/// Mints a replacement key at the caller's own scope and registers it in the server's
+/// `ApiKeyStore`. If the caller authenticated with a static Bearer/`X-API-Key` value, that key is
+/// scheduled for removal after `grace_secs` rather than revoked immediately, so clients can roll
+/// over without downtime (protected; requires `sbin` scope).
+async fn rotate_api_key_endpoint(
+    State(auth_config): State<AuthConfig>,
+    headers: HeaderMap,
+    scope: Option<axum::extract::Extension<Scope>>,
+    body: Option<Json<RotateApiKeyRequest>>,
+) -> impl IntoResponse {
+    let scope = scope.map(|axum::extract::Extension(scope)| scope).unwrap_or(Scope::Sbin);
+    if !scope.allows(Scope::Sbin) {
+        return (StatusCode::FORBIDDEN, Json(json!({
+            "error": "Rotating API keys requires sbin scope"
+        }))).into_response();
+    }
+
+    let grace_secs = body.map(|Json(r)| r.grace_secs).unwrap_or_else(default_grace_secs);
+
+    let presented_key = headers.get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .or_else(|| headers.get("X-API-Key").and_then(|h| h.to_str().ok()));
+    let retired_hash = presented_key.map(crate::auth::hash_api_key);
+
+    let new_key = auth_config.key_store.rotate(scope, retired_hash.as_deref(), grace_secs);
+    let new_hash = crate::auth::hash_api_key(&new_key);
+
     (StatusCode::OK, Json(json!({
         "api_key": new_key,
-        "hash": key_hash,
+        "hash": new_hash,
+        "retired_hash": retired_hash,
+        "grace_period_secs": retired_hash.as_ref().map(|_| grace_secs),
         "instructions": {
-            "step_1": "Set TCL_MCP_API_KEY environment variable to the api_key value",
-            "step_2": "Restart the server",
-            "step_3": "Use the api_key in Authorization header: 'Bearer <api_key>' or 'X-API-Key: <api_key>'"
+            "step_1": "Start using the new api_key in 'Authorization: Bearer <api_key>' or 'X-API-Key: <api_key>'",
+            "step_2": "The previous key keeps working until the grace period elapses; call DELETE /auth/keys/{hash} to revoke it sooner"
+        }
+    }))).into_response()
+}
+
+/// Immediately removes a key hash from the server's `ApiKeyStore`, independent of any grace
+/// window (protected; requires `sbin` scope).
+async fn revoke_api_key_endpoint(
+    State(auth_config): State<AuthConfig>,
+    scope: Option<axum::extract::Extension<Scope>>,
+    Path(hash): Path<String>,
+) -> impl IntoResponse {
+    let scope = scope.map(|axum::extract::Extension(scope)| scope).unwrap_or(Scope::Sbin);
+    if !scope.allows(Scope::Sbin) {
+        return (StatusCode::FORBIDDEN, Json(json!({
+            "error": "Revoking API keys requires sbin scope"
+        }))).into_response();
+    }
+
+    if auth_config.key_store.revoke(&hash) {
+        (StatusCode::OK, Json(json!({ "revoked": hash }))).into_response()
+    } else {
+        (StatusCode::NOT_FOUND, Json(json!({
+            "error": format!("No active key with hash '{}'", hash)
+        }))).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UdfVerifyRequest {
+    /// The UDF name to check against the signed `targets` document (the same value passed as
+    /// `name` to `sbin___tcl_tool_add`).
+    name: String,
+    /// The candidate TCL script body.
+    script: String,
+}
+
+/// Reports whether `script` would pass the UDF registry's checks for `name` without registering
+/// anything. Returns `{"would_accept": true}` or `{"would_accept": false, "reason": "..."}`;
+/// when no registry is configured, everything would be accepted (UDF loading is unrestricted).
+async fn udf_verify_endpoint(
+    State(server): State<HttpMcpServer>,
+    Json(request): Json<UdfVerifyRequest>,
+) -> impl IntoResponse {
+    match &server.registry {
+        None => (StatusCode::OK, Json(json!({ "would_accept": true }))).into_response(),
+        Some(registry) => match registry.verify_udf(&request.name, &request.script) {
+            Ok(()) => (StatusCode::OK, Json(json!({ "would_accept": true }))).into_response(),
+            Err(failure) => (StatusCode::OK, Json(json!({
+                "would_accept": false,
+                "reason": failure.to_string()
+            }))).into_response(),
         },
-        "note": "Store the api_key securely. The hash is for verification purposes only."
-    })))
+    }
 }
\ No newline at end of file
@@ -5,6 +5,16 @@ pub mod tcl_tools;
 pub mod tcl_executor;
 pub mod persistence;
 pub mod tool_discovery;
+pub mod tool_watcher;
+pub mod tool_filter;
 pub mod capabilities;
 pub mod http_server;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod tls;
+pub mod registry;
+pub mod plugin_manager;
+pub mod capability_grants;
+pub mod process_hardening;
+pub mod version_resolver;
+pub mod permissions;
+pub mod trust;
\ No newline at end of file
@@ -9,6 +9,7 @@ mod tcl_runtime;
 mod namespace;
 mod persistence;
 mod tool_discovery;
+mod process_hardening;
 
 use server::TclMcpServer;
 
@@ -22,17 +23,46 @@ struct Args {
     
     /// Select TCL runtime implementation
     #[arg(
-        long, 
+        long,
         value_name = "RUNTIME",
         help = "TCL runtime to use (molt|tcl). Can also be set via TCL_MCP_RUNTIME environment variable"
     )]
     runtime: Option<String>,
+
+    /// Per-script evaluation timeout, in milliseconds
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        help = "Abort a script evaluation after this many milliseconds. Can also be set via TCL_MCP_EVAL_TIMEOUT_MS"
+    )]
+    eval_timeout: Option<u64>,
+
+    /// Number of interpreter workers to run tool calls on
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of interpreter workers dispatching tool calls (default: number of CPUs). Can also be set via TCL_MCP_POOL_SIZE"
+    )]
+    pool_size: Option<usize>,
+
+    /// Print a machine-readable report of every runtime this binary knows about and exit
+    #[arg(
+        long,
+        help = "Print a JSON capability report (name, version, availability, safety, features) for every known runtime and exit"
+    )]
+    list_runtimes: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
-    
+
+    if args.list_runtimes {
+        let report = tcl_runtime::RuntimeConfig::capability_report();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -40,9 +70,11 @@ async fn main() -> Result<()> {
 
     // Determine runtime configuration
     let env_runtime = std::env::var("TCL_MCP_RUNTIME").ok();
-    let runtime_config = match tcl_runtime::RuntimeConfig::from_args_and_env(
+    let runtime_config = match tcl_runtime::RuntimeConfig::from_args_and_env_with_pool_size(
         args.runtime.as_deref(),
         env_runtime.as_deref(),
+        args.eval_timeout,
+        args.pool_size,
     ) {
         Ok(config) => config,
         Err(e) => {
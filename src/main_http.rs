@@ -13,6 +13,12 @@ mod namespace;
 mod persistence;
 mod tool_discovery;
 mod auth;
+mod tls;
+mod registry;
+mod process_hardening;
+mod capability_grants;
+mod capabilities;
+mod plugin_manager;
 
 use http_server::HttpMcpServer;
 
@@ -26,12 +32,28 @@ struct Args {
     
     /// Select TCL runtime implementation
     #[arg(
-        long, 
+        long,
         value_name = "RUNTIME",
         help = "TCL runtime to use (molt|tcl). Can also be set via TCL_MCP_RUNTIME environment variable"
     )]
     runtime: Option<String>,
-    
+
+    /// Per-script evaluation timeout, in milliseconds
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        help = "Abort a script evaluation after this many milliseconds. Can also be set via TCL_MCP_EVAL_TIMEOUT_MS"
+    )]
+    eval_timeout: Option<u64>,
+
+    /// Number of interpreter workers to run tool calls on
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of interpreter workers dispatching tool calls (default: number of CPUs). Can also be set via TCL_MCP_POOL_SIZE"
+    )]
+    pool_size: Option<usize>,
+
     /// Port to listen on
     #[arg(long, default_value = "3000", help = "Port to listen on")]
     port: u16,
@@ -39,12 +61,38 @@ struct Args {
     /// Host to bind to
     #[arg(long, default_value = "0.0.0.0", help = "Host to bind to")]
     host: String,
+
+    /// Path to a PEM TLS certificate chain. Can also be set via TCL_MCP_TLS_CERT.
+    #[arg(long, value_name = "PATH", help = "TLS certificate chain (PEM). Can also be set via TCL_MCP_TLS_CERT")]
+    tls_cert: Option<String>,
+
+    /// Path to the PEM private key matching --tls-cert. Can also be set via TCL_MCP_TLS_KEY.
+    #[arg(long, value_name = "PATH", help = "TLS private key (PEM). Can also be set via TCL_MCP_TLS_KEY")]
+    tls_key: Option<String>,
+
+    /// Generate a throwaway self-signed certificate instead of requiring --tls-cert/--tls-key.
+    /// For local development only — requires the `self-signed-tls` cargo feature.
+    #[arg(long, help = "Serve HTTPS with a generated self-signed certificate (local development only)")]
+    tls_self_signed: bool,
+
+    /// Print a machine-readable report of every runtime this binary knows about and exit
+    #[arg(
+        long,
+        help = "Print a JSON capability report (name, version, availability, safety, features) for every known runtime and exit"
+    )]
+    list_runtimes: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
-    
+
+    if args.list_runtimes {
+        let report = tcl_runtime::RuntimeConfig::capability_report();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
@@ -52,9 +100,11 @@ async fn main() -> Result<()> {
 
     // Determine runtime configuration
     let env_runtime = std::env::var("TCL_MCP_RUNTIME").ok();
-    let runtime_config = match tcl_runtime::RuntimeConfig::from_args_and_env(
+    let runtime_config = match tcl_runtime::RuntimeConfig::from_args_and_env_with_pool_size(
         args.runtime.as_deref(),
         env_runtime.as_deref(),
+        args.eval_timeout,
+        args.pool_size,
     ) {
         Ok(config) => config,
         Err(e) => {
@@ -98,7 +148,13 @@ async fn main() -> Result<()> {
         tracing::warn!("Failed to initialize persistence: {}", e);
         // Continue without persistence rather than failing
     }
-    
+
+    // Load external plugins (TCL_MCP_PLUGINS_DIR), if configured
+    if let Err(e) = server.initialize_plugins().await {
+        tracing::warn!("Failed to initialize plugins: {}", e);
+        // Continue without plugins rather than failing
+    }
+
     // Create router with middleware
     let app = server.router()
         .layer(
@@ -106,12 +162,70 @@ async fn main() -> Result<()> {
                 .layer(TraceLayer::new_for_http())
         );
     
-    // Bind and serve
+    // Bind and serve, speaking HTTPS directly when TLS cert/key are configured
     let addr = SocketAddr::from(([0, 0, 0, 0], args.port));
-    info!("TCL MCP HTTP Server listening on http://{}:{}", args.host, args.port);
-    
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+
+    let tls_paths = match tls::resolve_tls_paths(args.tls_cert.as_deref(), args.tls_key.as_deref()) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(tls_paths) = tls_paths {
+        let mtls_enabled = tls_paths.client_cert_auth.is_some();
+        let tls_config = match tls::load_rustls_config(&tls_paths).await {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+        if mtls_enabled {
+            info!("TCL MCP HTTP Server listening on https://{}:{} (mTLS client certs enabled)", args.host, args.port);
+            axum_server::bind(addr)
+                .acceptor(tls::ClientCertAcceptor::new(tls_config))
+                .serve(app.into_make_service())
+                .await?;
+        } else {
+            info!("TCL MCP HTTP Server listening on https://{}:{}", args.host, args.port);
+            axum_server::bind_rustls(addr, tls_config)
+                .serve(app.into_make_service())
+                .await?;
+        }
+    } else if args.tls_self_signed {
+        #[cfg(feature = "self-signed-tls")]
+        {
+            let tls_config = match tls::self_signed_rustls_config(vec![args.host.clone(), "localhost".to_string()]).await {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            info!("TCL MCP HTTP Server listening on https://{}:{} (self-signed certificate — development only)", args.host, args.port);
+            axum_server::bind_rustls(addr, tls_config)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        #[cfg(not(feature = "self-signed-tls"))]
+        {
+            eprintln!("Error: --tls-self-signed requires building with --features self-signed-tls");
+            std::process::exit(1);
+        }
+    } else {
+        if args.privileged {
+            tracing::warn!(
+                "No TLS certificate configured (--tls-cert/--tls-key, TCL_MCP_TLS_CERT/KEY, or --tls-self-signed) — \
+                 serving privileged mode over cleartext HTTP"
+            );
+        }
+        info!("TCL MCP HTTP Server listening on http://{}:{}", args.host, args.port);
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, app).await?;
+    }
 
     Ok(())
 }
\ No newline at end of file
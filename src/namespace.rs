@@ -2,7 +2,8 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub enum Namespace {
     Bin,     // System tools (read-only)
     Sbin,    // System admin tools (privileged)
@@ -10,7 +11,22 @@ pub enum Namespace {
     User(String), // User namespace
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+impl Namespace {
+    /// The key this namespace is addressed by in a `capability_grants` file's `namespaces`
+    /// list: `"bin"`/`"sbin"`/`"docs"` for the system namespaces, or the user's own name for
+    /// `User`.
+    pub fn grant_key(&self) -> String {
+        match self {
+            Namespace::Bin => "bin".to_string(),
+            Namespace::Sbin => "sbin".to_string(),
+            Namespace::Docs => "docs".to_string(),
+            Namespace::User(user) => user.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub struct ToolPath {
     pub namespace: Namespace,
     pub package: Option<String>,
@@ -59,11 +75,15 @@ impl ToolPath {
         }
     }
     
-    /// Parse a tool path from a string representation
+    /// Parse a tool path from a string representation. The part after `:` is stored verbatim —
+    /// a concrete version (`"1.0"`), a semver requirement (`"^1.2"`, `"~1.0"`, `">=1.0,<2.0"`),
+    /// or `"latest"` all parse the same way here; `version_resolver` is what actually tells them
+    /// apart, at lookup time.
     /// Examples:
     /// - "/bin/tcl_execute"
     /// - "/sbin/tcl_tool_add"
     /// - "/alice/utils/reverse_string:1.0"
+    /// - "/alice/utils/reverse_string:^1.0"
     /// - "/bob/math/calculate:latest"
     pub fn parse(path: &str) -> Result<Self> {
         if !path.starts_with('/') {
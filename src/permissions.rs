@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::namespace::{Namespace, ToolPath};
+
+/// An operation a caller may attempt against a tool or the tool registry. `Read`/`Execute` cover
+/// looking up and invoking existing tools; `AddTool`/`RemoveTool` cover registry mutation;
+/// `Admin` gates anything under [`Namespace::Sbin`] regardless of the more specific permission
+/// being requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum Permission {
+    Read,
+    Execute,
+    AddTool,
+    RemoveTool,
+    Admin,
+}
+
+/// A caller identity carrying the set of [`Permission`]s it's been granted. `name` is compared
+/// against [`Namespace::User`] to decide whether a caller may add/remove tools in a given user
+/// namespace — a principal may only administer its own namespace, no matter what permissions
+/// it otherwise holds.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub name: String,
+    granted: HashSet<Permission>,
+}
+
+impl Principal {
+    /// Builds a principal with exactly the given permissions granted.
+    pub fn new(name: impl Into<String>, granted: impl IntoIterator<Item = Permission>) -> Self {
+        Self {
+            name: name.into(),
+            granted: granted.into_iter().collect(),
+        }
+    }
+
+    /// A principal with every permission, including `Admin`. Used where no caller-specific ACL
+    /// is wired up (see `TclToolBox::tcl_tool_add`/`tcl_tool_remove`) — the stdio transport in
+    /// `server.rs` has no multi-caller identity concept and always falls back to this, while
+    /// `http_server.rs` only falls back here when a request carries no `CallerIdentity` (e.g. a
+    /// legacy unscoped static key or a signed request); whenever one is present it builds a real,
+    /// non-admin `Principal` and calls the `_as` variants instead.
+    pub fn unrestricted(name: impl Into<String>) -> Self {
+        Self::new(
+            name,
+            [
+                Permission::Read,
+                Permission::Execute,
+                Permission::AddTool,
+                Permission::RemoveTool,
+                Permission::Admin,
+            ],
+        )
+    }
+
+    pub fn has(&self, permission: Permission) -> bool {
+        self.granted.contains(&permission)
+    }
+}
+
+/// Returned when [`check`] rejects a request. Carries enough detail for a caller to log or
+/// surface a clear reason, mirroring `ToolError`'s shape in `tcl_executor`.
+#[derive(Debug, Clone)]
+pub struct PermissionDenied {
+    pub principal: String,
+    pub namespace: Namespace,
+    pub permission: Permission,
+    pub reason: String,
+}
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "permission denied: '{}' may not {:?} in {:?}: {}",
+            self.principal, self.permission, self.namespace, self.reason
+        )
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+/// Enforces the namespace invariants `Namespace`'s doc comments only describe: `Bin`/`Docs` are
+/// read-only (no `AddTool`/`RemoveTool` regardless of what the principal is otherwise granted),
+/// `Sbin` is privileged (any access requires `Admin`), and a `User` namespace may only be
+/// administered by its own principal. Deny-by-default: a permission not explicitly granted to
+/// the principal is rejected even if no namespace invariant applies.
+pub fn check(principal: &Principal, namespace: &Namespace, permission: Permission) -> Result<(), PermissionDenied> {
+    let deny = |reason: &str| {
+        Err(PermissionDenied {
+            principal: principal.name.clone(),
+            namespace: namespace.clone(),
+            permission,
+            reason: reason.to_string(),
+        })
+    };
+
+    match namespace {
+        Namespace::Bin | Namespace::Docs => {
+            if matches!(permission, Permission::AddTool | Permission::RemoveTool) {
+                return deny("bin and docs are read-only namespaces");
+            }
+        }
+        Namespace::Sbin => {
+            if !principal.has(Permission::Admin) {
+                return deny("sbin requires the admin permission");
+            }
+        }
+        Namespace::User(owner) => {
+            if matches!(permission, Permission::AddTool | Permission::RemoveTool) && &principal.name != owner {
+                return deny("a principal may only add or remove tools in its own user namespace");
+            }
+        }
+    }
+
+    if principal.has(permission) {
+        Ok(())
+    } else {
+        deny("permission not granted to this principal")
+    }
+}
+
+/// Convenience for call sites that check a permission against a tool's full path rather than a
+/// bare namespace.
+pub fn check_path(principal: &Principal, path: &ToolPath, permission: Permission) -> Result<(), PermissionDenied> {
+    check(principal, &path.namespace, permission)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bin_rejects_add_and_remove_even_for_admin() {
+        let admin = Principal::unrestricted("root");
+        assert!(check(&admin, &Namespace::Bin, Permission::AddTool).is_err());
+        assert!(check(&admin, &Namespace::Docs, Permission::RemoveTool).is_err());
+        assert!(check(&admin, &Namespace::Bin, Permission::Read).is_ok());
+    }
+
+    #[test]
+    fn sbin_requires_admin() {
+        let no_admin = Principal::new("alice", [Permission::Read, Permission::Execute]);
+        assert!(check(&no_admin, &Namespace::Sbin, Permission::Read).is_err());
+
+        let admin = Principal::unrestricted("root");
+        assert!(check(&admin, &Namespace::Sbin, Permission::Execute).is_ok());
+    }
+
+    #[test]
+    fn user_namespace_is_self_administered() {
+        let alice = Principal::new("alice", [Permission::AddTool, Permission::RemoveTool]);
+        assert!(check(&alice, &Namespace::User("alice".to_string()), Permission::AddTool).is_ok());
+        assert!(check(&alice, &Namespace::User("bob".to_string()), Permission::AddTool).is_err());
+    }
+
+    #[test]
+    fn deny_by_default_without_explicit_grant() {
+        let bystander = Principal::new("mallory", []);
+        assert!(check(&bystander, &Namespace::User("mallory".to_string()), Permission::Read).is_err());
+    }
+}
@@ -1,13 +1,36 @@
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
+use rkyv::Deserialize as ArchivedDeserialize;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::fs;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::tcl_tools::ToolDefinition;
 use crate::namespace::{ToolPath, Namespace};
+use crate::tool_watcher::{self, ChangeKind, ToolChange};
+use crate::tool_filter::ToolPathMatcher;
+
+/// Where a persisted tool's definition came from, so a receipt can explain provenance rather
+/// than just "it's on disk". Every tool persisted today goes through `tcl_tool_add`, so this is
+/// always `UserAdded` in practice, but the field exists so a future write path for
+/// filesystem-discovered tools (see `ToolDiscovery`) doesn't need a schema change to record it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToolOrigin {
+    UserAdded,
+    Discovered,
+}
+
+impl Default for ToolOrigin {
+    fn default() -> Self {
+        ToolOrigin::UserAdded
+    }
+}
 
 /// Metadata associated with a persisted tool
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,7 +39,141 @@ pub struct ToolMetadata {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub checksum: String,
+    /// Algorithm `checksum` was computed with. Absent in receipts written before this field
+    /// existed, which means the hash came from `DefaultHasher` — explicitly not stable across
+    /// Rust versions or platforms. Those receipts are recognized as [`ChecksumAlgo::DefaultHasher`]
+    /// (see its `Default` impl) and self-heal to [`ChecksumAlgo::Sha256`] the next time
+    /// `FilePersistence::upsert_tool` runs against them, even if the script itself is unchanged.
+    #[serde(default)]
+    pub checksum_algo: ChecksumAlgo,
     pub file_version: u32,
+    /// Absent in receipts written before this field existed; defaults to `UserAdded` since that
+    /// was the only origin possible at the time.
+    #[serde(default)]
+    pub origin: ToolOrigin,
+}
+
+/// Algorithm a [`ToolMetadata::checksum`] was computed with, so old-scheme receipts can be
+/// recognized and migrated on load instead of silently comparing a stable hash against an
+/// unstable one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChecksumAlgo {
+    /// `std::collections::hash_map::DefaultHasher`, used before this field existed. Unstable
+    /// across Rust versions/platforms, so a mismatch here doesn't necessarily mean corruption.
+    DefaultHasher,
+    Sha256,
+}
+
+impl Default for ChecksumAlgo {
+    /// Receipts predating this field were always hashed with `DefaultHasher`.
+    fn default() -> Self {
+        ChecksumAlgo::DefaultHasher
+    }
+}
+
+/// A genuine content-integrity failure: the stored checksum (computed with the scheme recorded
+/// in the receipt) doesn't match the script actually on disk. Carries both digests so a caller
+/// can log or surface them, rather than `load_tool` silently warning and returning the tool
+/// anyway.
+#[derive(Debug, Clone)]
+pub struct IntegrityError {
+    pub path: ToolPath,
+    pub algo: ChecksumAlgo,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum mismatch for tool '{}' ({:?}): expected {}, got {}",
+            self.path, self.algo, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// On-disk encoding for a persisted tool file. `Json` is the original `serde_json::to_string_pretty`
+/// format; `Toml` writes the same [`PersistedTool`] shape (a `[metadata]` table, a `[tool]` table
+/// with the script as a multi-line string and `parameters` as an array of tables) so the file is
+/// diffable and hand-editable, mirroring uv's move to a human-editable `receipt.toml`. Chosen for
+/// new writes via `FilePersistence::new`'s `TCL_MCP_STORAGE_FORMAT` env var; existing files keep
+/// whatever extension they were written with; see `get_tool_file_path` and `load_receipt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageFormat {
+    Json,
+    Toml,
+}
+
+impl StorageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            StorageFormat::Json => "json",
+            StorageFormat::Toml => "toml",
+        }
+    }
+
+    /// Every format recognized on read, JSON first; `load_receipt`'s fallback probes them in this
+    /// order whatever the instance's own write format is.
+    fn all() -> [StorageFormat; 2] {
+        [StorageFormat::Json, StorageFormat::Toml]
+    }
+
+    fn from_extension(ext: &str) -> Option<StorageFormat> {
+        match ext {
+            "json" => Some(StorageFormat::Json),
+            "toml" => Some(StorageFormat::Toml),
+            _ => None,
+        }
+    }
+
+    fn serialize(self, persisted: &PersistedTool) -> Result<String> {
+        match self {
+            StorageFormat::Json => Ok(serde_json::to_string_pretty(persisted)?),
+            StorageFormat::Toml => Ok(toml::to_string_pretty(persisted)?),
+        }
+    }
+
+    fn deserialize(self, content: &str) -> Result<PersistedTool> {
+        match self {
+            StorageFormat::Json => Ok(serde_json::from_str(content)?),
+            StorageFormat::Toml => Ok(toml::from_str(content)?),
+        }
+    }
+}
+
+impl Default for StorageFormat {
+    fn default() -> Self {
+        StorageFormat::Json
+    }
+}
+
+impl std::str::FromStr for StorageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(StorageFormat::Json),
+            "toml" => Ok(StorageFormat::Toml),
+            _ => Err(anyhow!("Invalid storage format '{}'. Valid options: json, toml", s)),
+        }
+    }
+}
+
+/// Outcome of `FilePersistence::upsert_tool`, letting `add_tool` report accurately instead of
+/// treating every add as either a hard failure or a blind overwrite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// No receipt existed yet for this path; one was created.
+    Created,
+    /// A receipt existed and `overwrite` was set; the script changed, so the definition was
+    /// replaced and the receipt's `updated_at`/`file_version` were bumped.
+    Upgraded,
+    /// A receipt existed and `overwrite` was set, but the script was byte-for-byte identical to
+    /// what's stored, so nothing was written and the existing receipt is untouched.
+    Unchanged,
 }
 
 /// A tool with its metadata for persistence
@@ -46,6 +203,13 @@ pub struct FilePersistence {
     storage_dir: PathBuf,
     index_path: PathBuf,
     index: ToolIndex,
+    /// Path to the rkyv-backed tool definition cache, or `None` if caching is disabled. See
+    /// `RuntimeConfig::tool_cache_enabled`.
+    cache_path: Option<PathBuf>,
+    /// Encoding used for tools this instance writes. Reads accept either encoding regardless of
+    /// this value (see `load_receipt`/`scan_storage_dir`), so a directory can contain a mix left
+    /// over from switching formats. See `TCL_MCP_STORAGE_FORMAT`.
+    format: StorageFormat,
 }
 
 impl FilePersistence {
@@ -53,158 +217,389 @@ impl FilePersistence {
     pub async fn new() -> Result<Self> {
         let storage_dir = get_storage_directory()?;
         let index_path = storage_dir.join("index.json");
-        
+
         // Create storage directory if it doesn't exist
         fs::create_dir_all(&storage_dir).await?;
-        
+
         // Load or create index
-        let index = Self::load_or_create_index(&index_path).await?;
-        
+        let index = Self::load_or_create_index(&storage_dir, &index_path).await?;
+        let cache_path = resolve_cache_path(&storage_dir);
+        let format = resolve_storage_format();
+
         Ok(Self {
             storage_dir,
             index_path,
             index,
+            cache_path,
+            format,
         })
     }
-    
+
     /// Create with custom storage directory (for testing)
     #[cfg(test)]
     pub async fn with_directory(storage_dir: PathBuf) -> Result<Self> {
+        Self::with_directory_and_format(storage_dir, StorageFormat::Json).await
+    }
+
+    /// Create with a custom storage directory and write format (for testing the TOML backend).
+    #[cfg(test)]
+    pub async fn with_directory_and_format(storage_dir: PathBuf, format: StorageFormat) -> Result<Self> {
         let index_path = storage_dir.join("index.json");
-        
+
         fs::create_dir_all(&storage_dir).await?;
-        let index = Self::load_or_create_index(&index_path).await?;
-        
+        let index = Self::load_or_create_index(&storage_dir, &index_path).await?;
+        let cache_path = resolve_cache_path(&storage_dir);
+
         Ok(Self {
             storage_dir,
             index_path,
             index,
+            cache_path,
+            format,
         })
     }
-    
-    async fn load_or_create_index(index_path: &Path) -> Result<ToolIndex> {
-        if index_path.exists() {
-            let content = fs::read_to_string(index_path).await?;
-            match serde_json::from_str(&content) {
-                Ok(index) => Ok(index),
-                Err(e) => {
-                    tracing::warn!("Failed to parse index file, creating new one: {}", e);
-                    Ok(ToolIndex::default())
+
+    /// Starts a background watcher over `storage_dir` so tools edited, added, or deleted
+    /// out-of-band — another process, a `git pull`, a human editing the JSON by hand — are
+    /// picked up without restarting the server. Returns a receiver of debounced [`ToolChange`]
+    /// events; the caller owns applying them to whatever in-memory tool cache it's tracking
+    /// (see `TclExecutor::apply_tool_change`), since this struct's own `&mut self` methods can't
+    /// be driven from `notify`'s callback.
+    ///
+    /// Classification of created/modified/deleted is tracked in a small `path -> checksum` map
+    /// seeded from the current index, independent of `self.index` itself, so this can run
+    /// without holding a lock on `self`.
+    pub fn watch(&self) -> Result<broadcast::Receiver<ToolChange>> {
+        let storage_dir = self.storage_dir.clone();
+        let known: Arc<Mutex<HashMap<PathBuf, String>>> = Arc::new(Mutex::new(
+            self.index
+                .tools
+                .values()
+                .map(|entry| (entry.file_path.clone(), entry.checksum.clone()))
+                .collect(),
+        ));
+
+        tool_watcher::watch(storage_dir.clone(), self.index_path.clone(), move |file_path| {
+            let known = Arc::clone(&known);
+            let storage_dir = storage_dir.clone();
+            async move {
+                let path = tool_watcher::tool_path_from_file(&storage_dir, &file_path)?;
+
+                if !file_path.exists() {
+                    known.lock().unwrap().remove(&file_path);
+                    return Some(ToolChange { path, kind: ChangeKind::Deleted });
+                }
+
+                let persisted = FilePersistence::read_receipt(&file_path).await.ok()?;
+                let checksum = calculate_checksum(&persisted.tool.script);
+                let previous = known.lock().unwrap().insert(file_path.clone(), checksum.clone());
+                match previous {
+                    None => Some(ToolChange { path, kind: ChangeKind::Created }),
+                    Some(prev) if prev == checksum => None,
+                    Some(_) => Some(ToolChange { path, kind: ChangeKind::Modified }),
                 }
             }
-        } else {
-            Ok(ToolIndex::default())
+        })
+    }
+
+    /// Loads `index_path`, or starts with an empty index if it doesn't exist yet (fresh
+    /// install). A parse failure — truncated by a crash mid-write, hand-edited into invalid
+    /// JSON — no longer discards the index: it's rebuilt from the tool files still on disk (see
+    /// `scan_storage_dir`) and the repaired index is written back immediately, so a damaged
+    /// `index.json` self-heals instead of silently orphaning the user's whole tool library.
+    async fn load_or_create_index(storage_dir: &Path, index_path: &Path) -> Result<ToolIndex> {
+        if !index_path.exists() {
+            return Ok(ToolIndex::default());
+        }
+
+        let content = fs::read_to_string(index_path).await?;
+        match serde_json::from_str(&content) {
+            Ok(index) => Ok(index),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to parse index file ({}), rebuilding it from the tool files on disk",
+                    e
+                );
+                let rebuilt = scan_storage_dir(storage_dir).await?;
+                if let Ok(json) = serde_json::to_string_pretty(&rebuilt) {
+                    if let Err(e) = write_atomic(index_path, json.as_bytes()).await {
+                        tracing::warn!("Failed to persist rebuilt index: {}", e);
+                    }
+                }
+                Ok(rebuilt)
+            }
         }
     }
-    
-    /// Save a tool to persistent storage
+
+    /// Save a tool to persistent storage, always creating a fresh receipt (new id, `file_version`
+    /// 1). Used for a brand new path; `upsert_tool` is the entry point that also handles an
+    /// already-existing path.
     pub async fn save_tool(&mut self, tool: &ToolDefinition) -> Result<()> {
+        self.write_tool(tool, ToolMetadata {
+            id: Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            checksum: calculate_checksum(&tool.script),
+            checksum_algo: ChecksumAlgo::Sha256,
+            file_version: 1,
+            origin: ToolOrigin::UserAdded,
+        }).await
+    }
+
+    /// Saves or upgrades a tool's receipt, depending on whether one already exists for
+    /// `tool.path`. With no existing receipt, this is equivalent to `save_tool` (tagged with
+    /// `origin`). With one, `overwrite` must be set or the call fails outright (preserving
+    /// `tcl_tool_add`'s historical all-or-nothing behavior by default); when it is set, the
+    /// script's checksum decides whether anything actually changes — an identical script is a
+    /// no-op rather than bumping `updated_at`/`file_version` for nothing.
+    pub async fn upsert_tool(&mut self, tool: &ToolDefinition, origin: ToolOrigin, overwrite: bool) -> Result<UpsertOutcome> {
+        let Some(existing) = self.load_receipt(&tool.path).await? else {
+            self.write_tool(tool, ToolMetadata {
+                id: Uuid::new_v4().to_string(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                checksum: calculate_checksum(&tool.script),
+                checksum_algo: ChecksumAlgo::Sha256,
+                file_version: 1,
+                origin,
+            }).await?;
+            return Ok(UpsertOutcome::Created);
+        };
+
+        if !overwrite {
+            return Err(anyhow!("Tool '{}' already exists", tool.path));
+        }
+
+        // Compare under whatever scheme the existing receipt was hashed with, so a script that
+        // genuinely hasn't changed isn't reported as changed just because it predates SHA-256.
+        let unchanged_checksum = calculate_checksum_with(existing.metadata.checksum_algo, &tool.script);
+        if unchanged_checksum == existing.metadata.checksum {
+            if existing.metadata.checksum_algo != ChecksumAlgo::Sha256 {
+                // Self-heal: re-hash under the stable scheme without treating this as a content
+                // change (same id/file_version/updated_at).
+                self.write_tool(tool, ToolMetadata {
+                    checksum: calculate_checksum(&tool.script),
+                    checksum_algo: ChecksumAlgo::Sha256,
+                    ..existing.metadata
+                }).await?;
+            }
+            return Ok(UpsertOutcome::Unchanged);
+        }
+
+        self.write_tool(tool, ToolMetadata {
+            id: existing.metadata.id,
+            created_at: existing.metadata.created_at,
+            updated_at: Utc::now(),
+            checksum: calculate_checksum(&tool.script),
+            checksum_algo: ChecksumAlgo::Sha256,
+            file_version: existing.metadata.file_version + 1,
+            origin,
+        }).await?;
+        Ok(UpsertOutcome::Upgraded)
+    }
+
+    /// Writes `tool` under the receipt `metadata` (fresh or carried over from an existing one)
+    /// and updates the index. Shared by `save_tool`, `upsert_tool`, and `rollback` so they stay in
+    /// sync. Before overwriting, archives whatever receipt currently lives at this path into
+    /// `history_dir` — unless the incoming script is byte-identical to what's archived already, in
+    /// which case there's nothing new to preserve and archiving would just be history noise (a
+    /// `load_tool`/`save_tool` round-trip with no actual edit, for instance).
+    async fn write_tool(&mut self, tool: &ToolDefinition, metadata: ToolMetadata) -> Result<()> {
         let file_path = self.get_tool_file_path(&tool.path);
-        
+        let path_key = tool.path.to_string();
+
+        if let Some(previous) = self.index.tools.get(&path_key) {
+            if previous.checksum != metadata.checksum && previous.file_path.exists() {
+                if let Ok(persisted) = Self::read_receipt(&previous.file_path).await {
+                    self.archive_version(&tool.path, &persisted).await?;
+                }
+            }
+        }
+
         // Create directory structure if needed
         if let Some(parent) = file_path.parent() {
             fs::create_dir_all(parent).await?;
         }
-        
-        // Calculate checksum
-        let checksum = calculate_checksum(&tool.script);
-        
-        // Create persisted tool
-        let now = Utc::now();
+
+        let checksum = metadata.checksum.clone();
+        let updated_at = metadata.updated_at;
         let persisted = PersistedTool {
-            metadata: ToolMetadata {
-                id: Uuid::new_v4().to_string(),
-                created_at: now,
-                updated_at: now,
-                checksum: checksum.clone(),
-                file_version: 1,
-            },
+            metadata,
             tool: tool.clone(),
         };
-        
+
         // Write tool file
-        let json = serde_json::to_string_pretty(&persisted)?;
-        fs::write(&file_path, json).await?;
-        
+        let encoded = self.format.serialize(&persisted)?;
+        write_atomic(&file_path, encoded.as_bytes()).await?;
+
+        // If this path was previously written under a different `StorageFormat` (the server's
+        // configured format changed between runs), drop the stale file so the directory doesn't
+        // end up with both a `.json` and a `.toml` receipt for the same tool.
+        if let Some(previous) = self.index.tools.get(&path_key) {
+            if previous.file_path != file_path && previous.file_path.exists() {
+                if let Err(e) = fs::remove_file(&previous.file_path).await {
+                    tracing::warn!("Failed to remove stale receipt {}: {}", previous.file_path.display(), e);
+                }
+            }
+        }
+
         // Update index
-        let path_key = tool.path.to_string();
         self.index.tools.insert(path_key, ToolIndexEntry {
             path: tool.path.clone(),
             file_path: file_path.clone(),
             checksum,
-            updated_at: now,
+            updated_at,
         });
-        self.index.last_updated = now;
-        
+        self.index.last_updated = updated_at;
+
         // Save index
         self.save_index().await?;
-        
+        self.invalidate_tool_cache().await;
+
         tracing::info!("Saved tool to {}", file_path.display());
         Ok(())
     }
-    
+
     /// Load a tool from persistent storage
     pub async fn load_tool(&self, path: &ToolPath) -> Result<Option<ToolDefinition>> {
+        Ok(self.load_receipt(path).await?.map(|persisted| persisted.tool))
+    }
+
+    /// Loads a tool's full receipt (definition plus metadata), verifying its script against the
+    /// checksum stored in the receipt itself (see `verify_integrity`). Returns `None` if no
+    /// receipt exists for `path`.
+    async fn load_receipt(&self, path: &ToolPath) -> Result<Option<PersistedTool>> {
         let path_key = path.to_string();
-        
-        // Check index first
+
+        // Check index first. The entry's own `file_path` (and therefore extension) is
+        // authoritative, so this works regardless of `self.format`.
         if let Some(entry) = self.index.tools.get(&path_key) {
             if entry.file_path.exists() {
-                let content = fs::read_to_string(&entry.file_path).await?;
-                let persisted: PersistedTool = serde_json::from_str(&content)?;
-                
-                // Verify checksum if desired
-                if persisted.metadata.checksum == entry.checksum {
-                    return Ok(Some(persisted.tool));
-                } else {
-                    tracing::warn!("Checksum mismatch for tool {}, file may be corrupted", path);
-                }
+                let persisted = Self::read_receipt(&entry.file_path).await?;
+                Self::verify_integrity(path, &persisted)?;
+                return Ok(Some(persisted));
             }
         }
-        
-        // Fallback: try to load directly from expected path
-        let file_path = self.get_tool_file_path(path);
-        if file_path.exists() {
-            let content = fs::read_to_string(&file_path).await?;
-            let persisted: PersistedTool = serde_json::from_str(&content)?;
-            return Ok(Some(persisted.tool));
+
+        // Fallback: the index doesn't know about this path (or its file vanished) — try every
+        // recognized extension at the expected location, not just `self.format`'s, so a tool
+        // written under a different `StorageFormat` than this instance's current one still loads.
+        for format in StorageFormat::all() {
+            let file_path = self.tool_file_path_with_format(path, format);
+            if file_path.exists() {
+                let persisted = Self::read_receipt(&file_path).await?;
+                Self::verify_integrity(path, &persisted)?;
+                return Ok(Some(persisted));
+            }
         }
-        
+
         Ok(None)
     }
+
+    /// Recomputes `persisted`'s checksum (using whichever [`ChecksumAlgo`] its receipt was
+    /// written with, so a file predating SHA-256 isn't flagged just for being old) and compares
+    /// it against the stored one. A mismatch is a genuine integrity failure, not the stale-hash
+    /// false positive `DefaultHasher` used to produce across toolchain upgrades.
+    fn verify_integrity(path: &ToolPath, persisted: &PersistedTool) -> Result<(), IntegrityError> {
+        let actual = calculate_checksum_with(persisted.metadata.checksum_algo, &persisted.tool.script);
+        if actual == persisted.metadata.checksum {
+            return Ok(());
+        }
+
+        Err(IntegrityError {
+            path: path.clone(),
+            algo: persisted.metadata.checksum_algo,
+            expected: persisted.metadata.checksum.clone(),
+            actual,
+        })
+    }
+
+    /// Reads and decodes a receipt file, dispatching on its extension so JSON and TOML receipts
+    /// can sit side by side in the same directory.
+    async fn read_receipt(file_path: &Path) -> Result<PersistedTool> {
+        let format = file_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(StorageFormat::from_extension)
+            .ok_or_else(|| anyhow!("Unrecognized tool receipt extension: {}", file_path.display()))?;
+
+        let content = fs::read_to_string(file_path).await?;
+        format.deserialize(&content)
+    }
+
+    /// Returns just the receipt metadata for `path` (no script/description), for
+    /// `TclCommand::GetToolReceipt`.
+    pub async fn get_receipt(&self, path: &ToolPath) -> Result<Option<ToolMetadata>> {
+        Ok(self.load_receipt(path).await?.map(|persisted| persisted.metadata))
+    }
     
-    /// List all persisted tools
-    pub async fn list_tools(&self, namespace_filter: Option<&str>) -> Result<Vec<ToolDefinition>> {
-        let mut tools = Vec::new();
-        
-        for entry in self.index.tools.values() {
-            // Apply namespace filter if specified
-            if let Some(filter) = namespace_filter {
-                let matches = match &entry.path.namespace {
-                    Namespace::User(user) => user == filter,
-                    Namespace::Bin => filter == "bin",
-                    Namespace::Sbin => filter == "sbin", 
-                    Namespace::Docs => filter == "docs",
-                };
-                
-                if !matches {
-                    continue;
+    /// Lists persisted tools whose path matches `matcher` (see [`ToolPathMatcher`]). Pass
+    /// [`ToolPathMatcher::all`] for the unfiltered case.
+    pub async fn list_tools(&self, matcher: &ToolPathMatcher) -> Result<Vec<ToolDefinition>> {
+        let tools = self.load_all_tools().await?;
+
+        Ok(tools
+            .into_iter()
+            .filter(|tool| matcher.matches(&tool.path))
+            .collect())
+    }
+
+    /// Loads every persisted tool, preferring the on-disk rkyv cache (memory-mapped and
+    /// validated in place, no per-file JSON parsing) when one is enabled and valid. Falls back
+    /// to reading each tool's JSON file individually when the cache is disabled, missing, or
+    /// fails validation, and opportunistically rebuilds the cache from that full read so the
+    /// next call can use it.
+    async fn load_all_tools(&self) -> Result<Vec<ToolDefinition>> {
+        if let Some(cache_path) = &self.cache_path {
+            match load_cache(cache_path).await {
+                Ok(Some(tools)) => {
+                    tracing::debug!("Loaded {} tool definitions from cache", tools.len());
+                    return Ok(tools);
                 }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(
+                    "Tool definition cache failed validation, falling back to full discovery: {}",
+                    e
+                ),
             }
-            
-            // Load tool
+        }
+
+        let mut tools = Vec::new();
+        for entry in self.index.tools.values() {
             if let Ok(Some(tool)) = self.load_tool(&entry.path).await {
                 tools.push(tool);
             }
         }
-        
+
+        if let Some(cache_path) = &self.cache_path {
+            if let Err(e) = write_cache(cache_path, &tools).await {
+                tracing::warn!("Failed to write tool definition cache: {}", e);
+            }
+        }
+
         Ok(tools)
     }
+
+    /// Deletes the on-disk tool definition cache, if any, so the next `list_tools` call rebuilds
+    /// it from a full read instead of serving stale data. Called whenever the tool registry
+    /// changes (`tcl_tool_add`/`tcl_tool_remove`).
+    async fn invalidate_tool_cache(&self) {
+        if let Some(cache_path) = &self.cache_path {
+            if cache_path.exists() {
+                if let Err(e) = fs::remove_file(cache_path).await {
+                    tracing::warn!("Failed to remove stale tool definition cache: {}", e);
+                }
+            }
+        }
+    }
     
-    /// Delete a tool from persistent storage
-    pub async fn delete_tool(&mut self, path: &ToolPath) -> Result<bool> {
+    /// Delete a tool from persistent storage. `purge_history` controls whether its archived
+    /// revisions (see `history_dir`) are deleted along with the live file — pass `false` to keep
+    /// them around in case the tool is re-added and someone wants its old versions back, `true`
+    /// to actually reclaim the disk space.
+    pub async fn delete_tool(&mut self, path: &ToolPath, purge_history: bool) -> Result<bool> {
         let path_key = path.to_string();
-        
+
         // Remove from index
         if let Some(entry) = self.index.tools.remove(&path_key) {
             // Delete file
@@ -212,53 +607,181 @@ impl FilePersistence {
                 fs::remove_file(&entry.file_path).await?;
                 tracing::info!("Deleted tool file {}", entry.file_path.display());
             }
-            
+
             // Clean up empty directories
             self.cleanup_empty_dirs(&entry.file_path).await?;
-            
+
+            if purge_history {
+                let history_dir = self.history_dir(path);
+                if history_dir.exists() {
+                    if let Err(e) = fs::remove_dir_all(&history_dir).await {
+                        tracing::warn!("Failed to purge history for {}: {}", path, e);
+                    }
+                }
+            }
+
             // Update index
             self.index.last_updated = Utc::now();
             self.save_index().await?;
-            
+            self.invalidate_tool_cache().await;
+
             Ok(true)
         } else {
             Ok(false)
         }
     }
-    
-    
-    
-    
+
+    /// Regenerates the index from scratch by walking every tool file actually on disk, in case
+    /// `index.json` itself is intact but has drifted from reality (e.g. tool files restored from
+    /// a backup, or moved into place by something other than `FilePersistence`). `load_or_create_
+    /// index` calls the same underlying scan automatically when the index fails to parse; this
+    /// is the same repair exposed for a caller to trigger on demand.
+    pub async fn rebuild_index(&mut self) -> Result<()> {
+        self.index = scan_storage_dir(&self.storage_dir).await?;
+        self.save_index().await?;
+        self.invalidate_tool_cache().await;
+        Ok(())
+    }
+
     async fn save_index(&self) -> Result<()> {
         let json = serde_json::to_string_pretty(&self.index)?;
-        fs::write(&self.index_path, json).await?;
+        write_atomic(&self.index_path, json.as_bytes()).await?;
         Ok(())
     }
-    
+
     fn get_tool_file_path(&self, path: &ToolPath) -> PathBuf {
-        let mut file_path = self.storage_dir.clone();
-        
+        self.tool_file_path_with_format(path, self.format)
+    }
+
+    /// Like `get_tool_file_path`, but for an explicit format rather than `self.format` — used to
+    /// probe for a receipt written under a different `StorageFormat` than this instance's current
+    /// one (see `load_receipt`'s fallback).
+    fn tool_file_path_with_format(&self, path: &ToolPath, format: StorageFormat) -> PathBuf {
+        self.storage_dir
+            .join(Self::namespace_rel_dir(path))
+            .join(format!("{}.{}", Self::file_stem(path), format.extension()))
+    }
+
+    /// The directory a `path` lives under, relative to `storage_dir` — shared by
+    /// `tool_file_path_with_format` (joined under `storage_dir` directly) and `history_dir`
+    /// (joined under `storage_dir/history` instead, mirroring the same namespace layout).
+    fn namespace_rel_dir(path: &ToolPath) -> PathBuf {
+        let mut rel = PathBuf::new();
         match &path.namespace {
             Namespace::User(user) => {
-                file_path = file_path.join("users").join(user);
+                rel = rel.join("users").join(user);
                 if let Some(package) = &path.package {
-                    file_path = file_path.join(package);
+                    rel = rel.join(package);
                 }
             }
-            Namespace::Bin => file_path = file_path.join("system").join("bin"),
-            Namespace::Sbin => file_path = file_path.join("system").join("sbin"),
-            Namespace::Docs => file_path = file_path.join("system").join("docs"),
+            Namespace::Bin => rel = rel.join("system").join("bin"),
+            Namespace::Sbin => rel = rel.join("system").join("sbin"),
+            Namespace::Docs => rel = rel.join("system").join("docs"),
         }
-        
-        let filename = if path.version == "latest" {
-            format!("{}.json", path.name)
+        rel
+    }
+
+    /// The file stem `path` is written under (no extension): `name` for "latest", `name_version`
+    /// otherwise. See `tool_file_path_with_format`/`tool_watcher::tool_path_from_file`.
+    fn file_stem(path: &ToolPath) -> String {
+        if path.version == "latest" {
+            path.name.clone()
         } else {
-            format!("{}_{}.json", path.name, path.version)
+            format!("{}_{}", path.name, path.version)
+        }
+    }
+
+    /// The directory archived revisions of `path` live under: `storage_dir/history/<same
+    /// namespace layout>/<file_stem>/<file_version>.json`. Always JSON regardless of
+    /// `self.format` — history is an internal archive, not something meant to be hand-edited, so
+    /// there's no reason to carry the live format's diffability/editability tradeoff into it.
+    fn history_dir(&self, path: &ToolPath) -> PathBuf {
+        self.storage_dir
+            .join("history")
+            .join(Self::namespace_rel_dir(path))
+            .join(Self::file_stem(path))
+    }
+
+    fn history_file_path(&self, path: &ToolPath, file_version: u32) -> PathBuf {
+        self.history_dir(path).join(format!("{file_version}.json"))
+    }
+
+    /// Archives `persisted` (the receipt about to be replaced) into `history_dir(path)` before a
+    /// new version overwrites it, keyed by its own `file_version` so `list_versions`/`load_version`
+    /// can address it later. Called from `write_tool` only when the incoming script actually
+    /// differs from what's archived — see its call site.
+    async fn archive_version(&self, path: &ToolPath, persisted: &PersistedTool) -> Result<()> {
+        let history_path = self.history_file_path(path, persisted.metadata.file_version);
+        if let Some(parent) = history_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let encoded = serde_json::to_string_pretty(persisted)?;
+        write_atomic(&history_path, encoded.as_bytes()).await
+    }
+
+    /// Lists the archived revisions of `path`, oldest first. Does not include the current live
+    /// receipt — pair with `get_receipt` for that.
+    pub async fn list_versions(&self, path: &ToolPath) -> Result<Vec<ToolMetadata>> {
+        let history_dir = self.history_dir(path);
+        let mut entries = match fs::read_dir(&history_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
         };
-        
-        file_path.join(filename)
+
+        let mut versions = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let entry_path = entry.path();
+            if entry_path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let content = fs::read_to_string(&entry_path).await?;
+            let persisted: PersistedTool = serde_json::from_str(&content)?;
+            versions.push(persisted.metadata);
+        }
+
+        versions.sort_by_key(|metadata| metadata.file_version);
+        Ok(versions)
     }
-    
+
+    /// Loads a specific archived revision of `path`, or `None` if that `file_version` was never
+    /// archived (including the common case of "this is the current live version, never archived").
+    pub async fn load_version(&self, path: &ToolPath, file_version: u32) -> Result<Option<PersistedTool>> {
+        let history_path = self.history_file_path(path, file_version);
+        if !history_path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&history_path).await?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Re-promotes archived revision `file_version` of `path` back to the live file and index
+    /// entry, archiving whatever was live beforehand (so rolling back doesn't lose it) the same
+    /// way any other overwrite does. The identity (`id`, `created_at`) carries over from the
+    /// *current* live receipt, not the archived one, since this is a new revision of the same
+    /// tool rather than a resurrection of the old one; `file_version` keeps incrementing forward.
+    /// Returns `false` if `file_version` was never archived.
+    pub async fn rollback(&mut self, path: &ToolPath, file_version: u32) -> Result<bool> {
+        let Some(archived) = self.load_version(path, file_version).await? else {
+            return Ok(false);
+        };
+        let Some(current) = self.load_receipt(path).await? else {
+            return Ok(false);
+        };
+
+        self.write_tool(&archived.tool, ToolMetadata {
+            id: current.metadata.id,
+            created_at: current.metadata.created_at,
+            updated_at: Utc::now(),
+            checksum: calculate_checksum(&archived.tool.script),
+            checksum_algo: ChecksumAlgo::Sha256,
+            file_version: current.metadata.file_version + 1,
+            origin: current.metadata.origin,
+        }).await?;
+
+        Ok(true)
+    }
+
     fn cleanup_empty_dirs<'a>(&'a self, file_path: &'a Path) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
         Box::pin(async move {
             if let Some(parent) = file_path.parent() {
@@ -282,6 +805,82 @@ impl FilePersistence {
     }
 }
 
+/// Writes `contents` to `path` by first writing to a sibling `<path>.tmp` file and then
+/// `fs::rename`-ing it over `path`. Rename is atomic on the same filesystem, so a crash between
+/// the write and the rename leaves whatever was previously at `path` untouched, rather than the
+/// truncated partial write a direct `fs::write` would risk. Writers here are always serialized
+/// behind `&mut self`, so a fixed `.tmp` name (rather than one salted per-call) can't collide.
+async fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp_path = PathBuf::from(tmp);
+
+    fs::write(&tmp_path, contents).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+/// Builds a fresh [`ToolIndex`] by recursively walking `storage_dir` for tool `.json`/`.toml` files
+/// (skipping `index.json` and anything that doesn't parse as a [`PersistedTool`]), reconstructing
+/// each one's [`ToolPath`] from the directory layout `get_tool_file_path` writes — inverted by
+/// `tool_watcher::tool_path_from_file` — and recomputing its checksum rather than trusting
+/// whatever the file's own receipt claims. Shared by `load_or_create_index`'s self-healing path
+/// and `FilePersistence::rebuild_index`.
+async fn scan_storage_dir(storage_dir: &Path) -> Result<ToolIndex> {
+    let mut tools = HashMap::new();
+    let mut pending_dirs = vec![storage_dir.to_path_buf()];
+
+    while let Some(dir) = pending_dirs.pop() {
+        let mut entries = match fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Skipping unreadable directory {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let entry_path = entry.path();
+
+            if entry_path.is_dir() {
+                pending_dirs.push(entry_path);
+                continue;
+            }
+
+            let is_recognized_format = entry_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(StorageFormat::from_extension)
+                .is_some();
+            if !is_recognized_format {
+                continue;
+            }
+
+            let Some(tool_path) = tool_watcher::tool_path_from_file(storage_dir, &entry_path) else {
+                continue;
+            };
+
+            let persisted = match FilePersistence::read_receipt(&entry_path).await {
+                Ok(persisted) => persisted,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable/malformed tool file {}: {}", entry_path.display(), e);
+                    continue;
+                }
+            };
+
+            let checksum = calculate_checksum(&persisted.tool.script);
+            tools.insert(tool_path.to_string(), ToolIndexEntry {
+                path: tool_path,
+                file_path: entry_path,
+                checksum,
+                updated_at: persisted.metadata.updated_at,
+            });
+        }
+    }
+
+    Ok(ToolIndex { tools, last_updated: Utc::now() })
+}
+
 /// Get the appropriate storage directory for the current platform
 fn get_storage_directory() -> Result<PathBuf> {
     let data_dir = dirs::data_local_dir()
@@ -290,16 +889,103 @@ fn get_storage_directory() -> Result<PathBuf> {
     Ok(data_dir.join("tcl-mcp-server").join("tools.storage"))
 }
 
-/// Calculate a simple checksum for tool script content
+/// Calculate a stable content checksum for tool script content, used for every freshly written
+/// receipt. See [`ChecksumAlgo`] for why this replaced `DefaultHasher`.
 fn calculate_checksum(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Calculates `content`'s checksum under a specific `algo`, so an existing receipt can be
+/// verified (or recognized as unchanged) against the scheme it was actually written with.
+fn calculate_checksum_with(algo: ChecksumAlgo, content: &str) -> String {
+    match algo {
+        ChecksumAlgo::Sha256 => calculate_checksum(content),
+        ChecksumAlgo::DefaultHasher => legacy_default_hasher_checksum(content),
+    }
+}
+
+/// The pre-SHA-256 checksum scheme. Kept only so receipts written under it can still be
+/// recognized and verified; never used for new writes.
+fn legacy_default_hasher_checksum(content: &str) -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
-    
+
     let mut hasher = DefaultHasher::new();
     content.hash(&mut hasher);
     format!("{:x}", hasher.finish())
 }
 
+/// Resolves the on-disk path for the tool definition cache from the environment, or `None` if
+/// caching is disabled. See `TCL_MCP_TOOL_CACHE_ENABLED` / `TCL_MCP_TOOL_CACHE_PATH` and
+/// `RuntimeConfig::tool_cache_enabled` / `RuntimeConfig::tool_cache_path`.
+fn resolve_cache_path(storage_dir: &Path) -> Option<PathBuf> {
+    let enabled = std::env::var("TCL_MCP_TOOL_CACHE_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    if !enabled {
+        return None;
+    }
+
+    Some(
+        std::env::var("TCL_MCP_TOOL_CACHE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| storage_dir.join("tools.cache.rkyv")),
+    )
+}
+
+/// Resolves the [`StorageFormat`] new tool writes use from `TCL_MCP_STORAGE_FORMAT` (`"json"` or
+/// `"toml"`, case-insensitive), defaulting to [`StorageFormat::Json`] — unset, empty, or
+/// unrecognized all fall back to the default rather than failing startup over it.
+fn resolve_storage_format() -> StorageFormat {
+    std::env::var("TCL_MCP_STORAGE_FORMAT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Memory-maps `path` and validates it as an archived `Vec<ToolDefinition>`, returning `None`
+/// when the file doesn't exist and an error when it exists but fails validation (corrupt or
+/// written by an incompatible version), so callers can distinguish "no cache yet" from "cache
+/// unusable" and fall back to full discovery in both cases.
+async fn load_cache(path: &Path) -> Result<Option<Vec<ToolDefinition>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let path = path.to_path_buf();
+    let tools = tokio::task::spawn_blocking(move || -> Result<Vec<ToolDefinition>> {
+        let file = std::fs::File::open(&path)?;
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let archived = rkyv::check_archived_root::<Vec<ToolDefinition>>(&mmap)
+            .map_err(|e| anyhow!("Archive validation failed: {}", e))?;
+        let tools: Vec<ToolDefinition> = archived
+            .deserialize(&mut rkyv::Infallible)
+            .expect("rkyv::Infallible deserializer cannot fail");
+        Ok(tools)
+    })
+    .await
+    .map_err(|e| anyhow!("Tool cache load task panicked: {}", e))??;
+
+    Ok(Some(tools))
+}
+
+/// Serializes `tools` into an rkyv archive and atomically writes it to `path` (write to a
+/// temp file, then rename, so a reader never observes a half-written cache).
+async fn write_cache(path: &Path, tools: &[ToolDefinition]) -> Result<()> {
+    let tools = tools.to_vec();
+    let bytes = rkyv::to_bytes::<_, 4096>(&tools)
+        .map_err(|e| anyhow!("Failed to archive tool definitions: {}", e))?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, &*bytes).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,8 +1009,10 @@ mod tests {
                     description: "Message to display".to_string(),
                     required: true,
                     type_name: "string".to_string(),
+                    ..Default::default()
                 }
             ],
+            test_cases: String::new(),
         }
     }
     
@@ -359,23 +1047,56 @@ mod tests {
             description: "Calculator tool".to_string(),
             script: "expr $a + $b".to_string(),
             parameters: vec![],
+            test_cases: String::new(),
         };
         
         persistence.save_tool(&tool1).await?;
         persistence.save_tool(&tool2).await?;
         
         // List all tools
-        let all_tools = persistence.list_tools(None).await?;
+        let all_tools = persistence.list_tools(&ToolPathMatcher::all()).await?;
         assert_eq!(all_tools.len(), 2);
-        
-        // List tools by namespace
-        let alice_tools = persistence.list_tools(Some("alice")).await?;
+
+        // List tools by namespace, via a glob matching the whole `alice/**` subtree
+        let alice_tools = persistence.list_tools(&ToolPathMatcher::new(["/alice/**"])).await?;
         assert_eq!(alice_tools.len(), 1);
         assert_eq!(alice_tools[0].path.namespace, Namespace::User("alice".to_string()));
-        
+
         Ok(())
     }
-    
+
+    #[tokio::test]
+    async fn test_list_tools_pattern_filtering() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+
+        let stable = ToolDefinition {
+            path: ToolPath::user("bob", "math", "calculate", "2.0"),
+            description: "Calculator tool".to_string(),
+            script: "expr $a + $b".to_string(),
+            parameters: vec![],
+            test_cases: String::new(),
+        };
+        let experimental = ToolDefinition {
+            path: ToolPath::user("bob", "math", "calculate", "experimental"),
+            description: "Calculator tool, unstable branch".to_string(),
+            script: "expr $a + $b".to_string(),
+            parameters: vec![],
+            test_cases: String::new(),
+        };
+
+        persistence.save_tool(&create_test_tool()).await?;
+        persistence.save_tool(&stable).await?;
+        persistence.save_tool(&experimental).await?;
+
+        // Every bob/math tool except the experimental version.
+        let matcher = ToolPathMatcher::new(["/*/math/**", "!**:experimental"]);
+        let tools = persistence.list_tools(&matcher).await?;
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].path, stable.path);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_tool() -> Result<()> {
         let (mut persistence, _temp) = create_test_persistence().await?;
@@ -388,16 +1109,186 @@ mod tests {
         assert!(persistence.load_tool(&tool.path).await?.is_some());
         
         // Delete tool
-        let deleted = persistence.delete_tool(&tool.path).await?;
+        let deleted = persistence.delete_tool(&tool.path, false).await?;
         assert!(deleted);
-        
+
         // Verify it's gone
         assert!(persistence.load_tool(&tool.path).await?.is_none());
-        
+
         // Try to delete again
-        let deleted_again = persistence.delete_tool(&tool.path).await?;
+        let deleted_again = persistence.delete_tool(&tool.path, false).await?;
         assert!(!deleted_again);
-        
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_toml_storage_format_round_trips_and_is_hand_editable() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut persistence =
+            FilePersistence::with_directory_and_format(temp_dir.path().to_path_buf(), StorageFormat::Toml).await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+
+        let file_path = persistence.get_tool_file_path(&tool.path);
+        assert_eq!(file_path.extension().and_then(|e| e.to_str()), Some("toml"));
+
+        // The script should be readable as plain text, not JSON-escaped.
+        let raw = std::fs::read_to_string(&file_path)?;
+        assert!(raw.contains(&tool.script));
+
+        let loaded = persistence.load_tool(&tool.path).await?;
+        assert_eq!(loaded.unwrap().script, tool.script);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_tool_fallback_finds_receipt_under_a_different_format() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let tool = create_test_tool();
+
+        let mut persistence =
+            FilePersistence::with_directory_and_format(temp_dir.path().to_path_buf(), StorageFormat::Toml).await?;
+        persistence.save_tool(&tool).await?;
+
+        // Simulate the index not knowing about this path (stale index, restored from a backup,
+        // ...) so `load_tool` has to fall back to probing every recognized extension at the
+        // expected location rather than trusting the index's own recorded `file_path`.
+        persistence.index.tools.remove(&tool.path.to_string());
+
+        let loaded = persistence.load_tool(&tool.path).await?;
+        assert_eq!(loaded.unwrap().script, tool.script);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_tool_rejects_overwrite_without_flag() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+
+        let result = persistence.upsert_tool(&tool, ToolOrigin::UserAdded, false).await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_tool_is_noop_when_script_unchanged() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+        let before = persistence.get_receipt(&tool.path).await?.unwrap();
+
+        let outcome = persistence.upsert_tool(&tool, ToolOrigin::UserAdded, true).await?;
+        assert_eq!(outcome, UpsertOutcome::Unchanged);
+
+        let after = persistence.get_receipt(&tool.path).await?.unwrap();
+        assert_eq!(after.file_version, before.file_version);
+        assert_eq!(after.updated_at, before.updated_at);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_tool_bumps_version_when_script_changes() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+
+        let mut changed = tool.clone();
+        changed.script = "puts \"a different script\"".to_string();
+
+        let outcome = persistence.upsert_tool(&changed, ToolOrigin::UserAdded, true).await?;
+        assert_eq!(outcome, UpsertOutcome::Upgraded);
+
+        let receipt = persistence.get_receipt(&tool.path).await?.unwrap();
+        assert_eq!(receipt.file_version, 2);
+
+        let loaded = persistence.load_tool(&tool.path).await?.unwrap();
+        assert_eq!(loaded.script, changed.script);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_archives_previous_version_for_rollback() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+        assert!(persistence.list_versions(&tool.path).await?.is_empty());
+
+        let mut v2 = tool.clone();
+        v2.script = "puts \"version two\"".to_string();
+        persistence.upsert_tool(&v2, ToolOrigin::UserAdded, true).await?;
+
+        let versions = persistence.list_versions(&tool.path).await?;
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].file_version, 1);
+
+        let archived = persistence.load_version(&tool.path, 1).await?.unwrap();
+        assert_eq!(archived.tool.script, tool.script);
+
+        // A no-op upsert (identical script) shouldn't add history noise.
+        persistence.upsert_tool(&v2, ToolOrigin::UserAdded, true).await?;
+        assert_eq!(persistence.list_versions(&tool.path).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rollback_restores_an_archived_script_and_keeps_bumping_file_version() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+        let mut v2 = tool.clone();
+        v2.script = "puts \"version two\"".to_string();
+        persistence.upsert_tool(&v2, ToolOrigin::UserAdded, true).await?;
+
+        let original_id = persistence.get_receipt(&tool.path).await?.unwrap().id;
+
+        let rolled_back = persistence.rollback(&tool.path, 1).await?;
+        assert!(rolled_back);
+
+        let loaded = persistence.load_tool(&tool.path).await?.unwrap();
+        assert_eq!(loaded.script, tool.script);
+
+        let receipt = persistence.get_receipt(&tool.path).await?.unwrap();
+        assert_eq!(receipt.id, original_id);
+        assert_eq!(receipt.file_version, 3);
+
+        // Rolling back to a version that was never archived is a no-op, not an error.
+        assert!(!persistence.rollback(&tool.path, 99).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_tool_preserves_history_unless_purged() -> Result<()> {
+        let (mut persistence, _temp) = create_test_persistence().await?;
+        let tool = create_test_tool();
+
+        persistence.save_tool(&tool).await?;
+        let mut v2 = tool.clone();
+        v2.script = "puts \"version two\"".to_string();
+        persistence.upsert_tool(&v2, ToolOrigin::UserAdded, true).await?;
+        assert_eq!(persistence.list_versions(&tool.path).await?.len(), 1);
+
+        persistence.delete_tool(&tool.path, false).await?;
+        assert_eq!(persistence.list_versions(&tool.path).await?.len(), 1);
+
+        persistence.save_tool(&tool).await?;
+        persistence.delete_tool(&tool.path, true).await?;
+        assert!(persistence.list_versions(&tool.path).await?.is_empty());
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,490 @@
+//! Loads out-of-process tool providers ("plugins") from a directory at startup. Each plugin is
+//! an executable the server launches with piped stdin/stdout, exchanging newline-delimited
+//! JSON-RPC 2.0: a `describe` request at startup enumerates the plugin's tools, and an `exec`
+//! request per invocation forwards validated `params` and reads back one line of JSON response,
+//! mapping its `result`/`error` into the caller's reply. This lets a tool be implemented in any
+//! language while still showing up in `tools/list` and `tools/call` like a native TCL tool.
+//! Mirrors `tool_watcher`'s directory-driven model, but for process lifecycles instead of
+//! filesystem events.
+//!
+//! This is the one external-process tool backend the server ships: plugin-owned tools are
+//! addressed under the `plugin___` MCP name prefix and dispatched here directly from
+//! `HttpMcpServer::handle_tools_call`, rather than through a second backend routed via a
+//! `Namespace::Plugin` variant and `TclExecutor::ExecuteCustomTool`. The latter would duplicate
+//! this module's subprocess/JSON-RPC plumbing behind a different address scheme for no
+//! behavioral difference a caller could observe, so `namespace::Namespace` stays at `Bin`/`Sbin`/
+//! `Docs`/`User` and custom TCL tools keep going through `TclExecutor` exclusively.
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::tcl_tools::ParameterDefinition;
+
+/// A single tool a plugin advertised in its `describe` reply, reusing `ParameterDefinition` so
+/// `tools/list` can build an `inputSchema` for it the same way it does for custom TCL tools.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginToolDefinition {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub parameters: Vec<ParameterDefinition>,
+}
+
+/// One JSON-RPC 2.0 request sent to a plugin as a single line on its stdin.
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<Value>,
+}
+
+/// One JSON-RPC 2.0 response read back as a single line from a plugin's stdout.
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    #[serde(default)]
+    #[allow(dead_code)]
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DescribeResult {
+    tools: Vec<PluginToolDefinition>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ExecResult {
+    #[serde(default)]
+    output: Option<String>,
+}
+
+/// The MCP-facing namespace prefix for plugin-owned tools, parallel to `bin___`/`sbin___`.
+const PLUGIN_NAME_PREFIX: &str = "plugin___";
+
+/// A running plugin process. Requests are serialized through `io` under a lock since a plugin
+/// speaks one `exec` request/response pair at a time over its pipe.
+struct Plugin {
+    /// The plugin's file stem (e.g. `weather` for `plugins/weather`), used as its display name
+    /// and log tag.
+    name: String,
+    tools: Vec<PluginToolDefinition>,
+    io: Mutex<PluginIo>,
+    next_id: AtomicU64,
+    /// Cleared the moment an `exec` call fails to read a response line: EOF on the plugin's
+    /// stdout (the child crashed or exited), a read error, or a line that isn't valid JSON-RPC.
+    /// Once cleared, further calls fail fast instead of writing to the plugin again.
+    available: AtomicBool,
+}
+
+struct PluginIo {
+    /// Kept alive so the process isn't reaped out from under `stdin`/`stdout`; never read from
+    /// directly after `stdin`/`stdout` are taken at spawn time.
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Plugin {
+    async fn spawn(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("plugin")
+            .to_string();
+
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("failed to launch plugin '{}' ({}): {}", name, path.display(), e))?;
+
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("plugin '{}' has no stdin pipe", name))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("plugin '{}' has no stdout pipe", name))?;
+        let mut io = PluginIo { _child: child, stdin, stdout: BufReader::new(stdout) };
+        let next_id = AtomicU64::new(1);
+
+        let tools = describe(&mut io, &next_id, &name).await?;
+
+        Ok(Self { name, tools, io: Mutex::new(io), next_id, available: AtomicBool::new(true) })
+    }
+
+    async fn call(&self, tool_name: &str, arguments: Value) -> Result<String> {
+        if !self.available.load(Ordering::Acquire) {
+            return Err(anyhow!("plugin '{}' is unavailable (an earlier call got no readable response from it)", self.name));
+        }
+
+        let parameters = self.tools.iter()
+            .find(|t| t.name == tool_name)
+            .map(|t| t.parameters.as_slice())
+            .unwrap_or(&[]);
+        validate_arguments(parameters, &arguments)?;
+
+        let mut io = self.io.lock().await;
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id,
+            method: "exec",
+            params: Some(arguments),
+        };
+        send_request(&mut io, &request, &self.name).await?;
+
+        let response = match read_response(&mut io, &self.name).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.available.store(false, Ordering::Release);
+                return Err(e);
+            }
+        };
+        match response.error {
+            Some(error) => Err(anyhow!("plugin '{}' reported an error for '{}': {}", self.name, tool_name, error.message)),
+            None => {
+                let result: ExecResult = response.result
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .map_err(|e| anyhow!("plugin '{}' returned a malformed result for '{}': {}", self.name, tool_name, e))?
+                    .unwrap_or_default();
+                Ok(result.output.unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Checks a call's arguments against `parameters`' `required`/`type_name` constraints before a
+/// request is forwarded to the plugin, the same shape of check `tcl_executor::bind_params_script`
+/// applies to native TCL tools (minus the TCL-literal binding, since a plugin just gets raw JSON).
+fn validate_arguments(parameters: &[ParameterDefinition], arguments: &Value) -> Result<()> {
+    let provided = arguments.as_object();
+
+    if let Some(provided) = provided {
+        let known: std::collections::HashSet<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+        if let Some(unknown) = provided.keys().find(|name| !known.contains(name.as_str())) {
+            return Err(anyhow!("Unknown parameter: {}", unknown));
+        }
+    }
+
+    for param in parameters {
+        match provided.and_then(|p| p.get(&param.name)) {
+            Some(value) if !type_matches(&param.type_name, value) => {
+                return Err(anyhow!("Parameter '{}' must be of type {}", param.name, param.type_name));
+            }
+            Some(_) => {}
+            None if param.required && param.default.is_none() => {
+                return Err(anyhow!("Missing required parameter: {}", param.name));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether `value` is shaped like `type_name` expects. Mirrors the type-name vocabulary
+/// `server::typed_schema_value`/`tcl_executor::format_tcl_default` already use elsewhere in the
+/// crate; an unrecognized `type_name` is treated as unconstrained rather than rejected.
+fn type_matches(type_name: &str, value: &Value) -> bool {
+    match type_name.to_lowercase().as_str() {
+        "number" | "float" | "double" | "real" | "integer" | "int" | "long" => value.is_number(),
+        "boolean" | "bool" => value.is_boolean(),
+        "string" => value.is_string(),
+        _ => true,
+    }
+}
+
+async fn describe(io: &mut PluginIo, next_id: &AtomicU64, plugin_name: &str) -> Result<Vec<PluginToolDefinition>> {
+    let id = next_id.fetch_add(1, Ordering::SeqCst);
+    let request = JsonRpcRequest { jsonrpc: "2.0", id, method: "describe", params: None };
+    send_request(io, &request, plugin_name).await?;
+
+    let response = read_response(io, plugin_name).await?;
+    if let Some(error) = response.error {
+        return Err(anyhow!("plugin '{}' reported an error for describe: {}", plugin_name, error.message));
+    }
+    let result: DescribeResult = response.result
+        .ok_or_else(|| anyhow!("plugin '{}' returned no result for describe", plugin_name))
+        .and_then(|result| serde_json::from_value(result)
+            .map_err(|e| anyhow!("plugin '{}' returned a malformed describe result: {}", plugin_name, e)))?;
+    Ok(result.tools)
+}
+
+async fn send_request(io: &mut PluginIo, request: &JsonRpcRequest<'_>, plugin_name: &str) -> Result<()> {
+    let mut line = serde_json::to_string(request)
+        .map_err(|e| anyhow!("failed to encode request for plugin '{}': {}", plugin_name, e))?;
+    line.push('\n');
+    io.stdin.write_all(line.as_bytes()).await
+        .map_err(|e| anyhow!("failed to write to plugin '{}': {}", plugin_name, e))?;
+    io.stdin.flush().await
+        .map_err(|e| anyhow!("failed to flush write to plugin '{}': {}", plugin_name, e))
+}
+
+async fn read_response(io: &mut PluginIo, plugin_name: &str) -> Result<JsonRpcResponse> {
+    let mut line = String::new();
+    let n = io.stdout.read_line(&mut line).await
+        .map_err(|e| anyhow!("failed to read from plugin '{}': {}", plugin_name, e))?;
+    if n == 0 {
+        return Err(anyhow!("plugin '{}' closed its stdout pipe", plugin_name));
+    }
+    serde_json::from_str(line.trim())
+        .map_err(|e| anyhow!("plugin '{}' returned malformed response '{}': {}", plugin_name, line.trim(), e))
+}
+
+/// A plugin-owned tool as surfaced to `tools/list`, with its fully-qualified MCP name and the
+/// plugin it came from.
+pub struct PluginListedTool {
+    pub mcp_name: String,
+    pub plugin_name: String,
+    pub definition: PluginToolDefinition,
+}
+
+/// Holds every successfully-loaded plugin for the lifetime of the server and routes `tools/call`
+/// to whichever plugin owns the requested tool name.
+#[derive(Clone, Default)]
+pub struct PluginManager {
+    /// Keyed by MCP tool name (`plugin___<tool>`), so `call` is a single lookup.
+    tool_owners: Arc<HashMap<String, Arc<Plugin>>>,
+}
+
+impl PluginManager {
+    /// Launches every executable directly under `dir` and collects the tools each advertises.
+    /// A plugin that fails to launch or answer `describe` is logged and skipped rather than
+    /// failing the whole server — one broken plugin shouldn't take down the others.
+    pub async fn load(dir: &Path) -> Result<Self> {
+        let mut entries = tokio::fs::read_dir(dir).await
+            .map_err(|e| anyhow!("failed to read plugins directory {}: {}", dir.display(), e))?;
+
+        let mut tool_owners = HashMap::new();
+        while let Some(entry) = entries.next_entry().await
+            .map_err(|e| anyhow!("failed to read plugins directory {}: {}", dir.display(), e))?
+        {
+            let path = entry.path();
+            let is_executable = entry.file_type().await.map(|t| t.is_file()).unwrap_or(false)
+                && is_executable(&path);
+            if !is_executable {
+                continue;
+            }
+
+            match Plugin::spawn(&path).await {
+                Ok(plugin) => {
+                    info!("Loaded plugin '{}' with {} tool(s)", plugin.name, plugin.tools.len());
+                    let plugin = Arc::new(plugin);
+                    for tool in &plugin.tools {
+                        let mcp_name = format!("{}{}", PLUGIN_NAME_PREFIX, tool.name);
+                        if let Some(existing) = tool_owners.insert(mcp_name.clone(), plugin.clone()) {
+                            warn!(
+                                "Plugin '{}' redeclares tool '{}' already owned by '{}'; the later plugin wins",
+                                plugin.name, tool.name, existing.name
+                            );
+                        }
+                    }
+                }
+                Err(e) => warn!("Skipping plugin at {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(Self { tool_owners: Arc::new(tool_owners) })
+    }
+
+    /// True if `mcp_name` is owned by a loaded plugin, i.e. some plugin advertised the tool that
+    /// this `plugin___`-prefixed name refers to. The prefix alone is not enough.
+    pub fn owns(&self, mcp_name: &str) -> bool {
+        self.tool_owners.contains_key(mcp_name)
+    }
+
+    /// Forwards a `tools/call` to whichever plugin owns `mcp_name`. Returns `Ok(None)` if no
+    /// plugin owns that name, so callers can fall through to the built-in dispatch.
+    pub async fn call(&self, mcp_name: &str, arguments: Value) -> Result<Option<String>> {
+        let Some(plugin) = self.tool_owners.get(mcp_name) else {
+            return Ok(None);
+        };
+        let tool_name = mcp_name.strip_prefix(PLUGIN_NAME_PREFIX).unwrap_or(mcp_name);
+        plugin.call(tool_name, arguments).await.map(Some)
+    }
+
+    /// Every plugin-owned tool, for merging into `tools/list`.
+    pub fn list_tools(&self) -> Vec<PluginListedTool> {
+        self.tool_owners
+            .iter()
+            .map(|(mcp_name, plugin)| {
+                let tool_name = mcp_name.strip_prefix(PLUGIN_NAME_PREFIX).unwrap_or(mcp_name);
+                let definition = plugin.tools.iter()
+                    .find(|t| t.name == tool_name)
+                    .cloned()
+                    .unwrap_or_else(|| PluginToolDefinition {
+                        name: tool_name.to_string(),
+                        description: String::new(),
+                        parameters: Vec::new(),
+                    });
+                PluginListedTool {
+                    mcp_name: mcp_name.clone(),
+                    plugin_name: plugin.name.clone(),
+                    definition,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the configured plugins directory, if `TCL_MCP_PLUGINS_DIR` is set.
+pub fn plugins_dir_from_env() -> Option<PathBuf> {
+    std::env::var("TCL_MCP_PLUGINS_DIR").ok().map(PathBuf::from)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Writes a tiny shell-script plugin that answers `describe` with one `echo` tool and `exec`
+    /// by echoing back its `params.text` field, then makes it executable.
+    fn write_echo_plugin(dir: &Path) -> PathBuf {
+        let path = dir.join("echo_plugin");
+        std::fs::write(&path, r#"#!/bin/sh
+while IFS= read -r line; do
+  case "$line" in
+    *'"method":"describe"'*)
+      id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[{\"name\":\"echo\",\"description\":\"Echoes its input\",\"parameters\":[{\"name\":\"text\",\"description\":\"text to echo\",\"required\":true,\"type_name\":\"string\"}]}]}}"
+      ;;
+    *'"method":"exec"'*)
+      id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+      text=$(echo "$line" | sed -n 's/.*"text":"\([^"]*\)".*/\1/p')
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"output\":\"$text\"}}"
+      ;;
+  esac
+done
+"#).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// Writes a plugin that answers `describe` normally but exits immediately on its first `exec`
+    /// without writing a response, so its stdout hits EOF mid-call.
+    fn write_crashing_plugin(dir: &Path) -> PathBuf {
+        let path = dir.join("crashy_plugin");
+        std::fs::write(&path, r#"#!/bin/sh
+while IFS= read -r line; do
+  case "$line" in
+    *'"method":"describe"'*)
+      id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[{\"name\":\"boom\",\"description\":\"Crashes on exec\",\"parameters\":[]}]}}"
+      ;;
+    *'"method":"exec"'*)
+      exit 1
+      ;;
+  esac
+done
+"#).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn write_non_executable_file(dir: &Path) {
+        std::fs::write(dir.join("not_a_plugin.txt"), "just a file").unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_merges_tools_under_plugin_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        write_echo_plugin(dir.path());
+        write_non_executable_file(dir.path());
+
+        let manager = PluginManager::load(dir.path()).await.unwrap();
+
+        assert!(manager.owns("plugin___echo"));
+        assert!(!manager.owns("plugin___nonexistent"));
+        let tools = manager.list_tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].mcp_name, "plugin___echo");
+        assert_eq!(tools[0].plugin_name, "echo_plugin");
+    }
+
+    #[tokio::test]
+    async fn test_call_forwards_to_plugin_and_returns_output() {
+        let dir = tempfile::tempdir().unwrap();
+        write_echo_plugin(dir.path());
+        let manager = PluginManager::load(dir.path()).await.unwrap();
+
+        let result = manager
+            .call("plugin___echo", serde_json::json!({"text": "hello"}))
+            .await
+            .unwrap();
+
+        assert_eq!(result, Some("hello".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_call_returns_none_for_unowned_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = PluginManager::load(dir.path()).await.unwrap();
+
+        let result = manager.call("plugin___missing", serde_json::json!({})).await.unwrap();
+
+        assert_eq!(result, None);
+    }
+
+    #[tokio::test]
+    async fn test_call_rejects_missing_required_argument() {
+        let dir = tempfile::tempdir().unwrap();
+        write_echo_plugin(dir.path());
+        let manager = PluginManager::load(dir.path()).await.unwrap();
+
+        let err = manager.call("plugin___echo", serde_json::json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("Missing required parameter"));
+    }
+
+    #[tokio::test]
+    async fn test_call_rejects_wrong_typed_argument() {
+        let dir = tempfile::tempdir().unwrap();
+        write_echo_plugin(dir.path());
+        let manager = PluginManager::load(dir.path()).await.unwrap();
+
+        let err = manager.call("plugin___echo", serde_json::json!({"text": 5})).await.unwrap_err();
+        assert!(err.to_string().contains("must be of type"));
+    }
+
+    #[tokio::test]
+    async fn test_crashed_plugin_is_marked_unavailable_after_eof() {
+        let dir = tempfile::tempdir().unwrap();
+        write_crashing_plugin(dir.path());
+        let manager = PluginManager::load(dir.path()).await.unwrap();
+
+        let first = manager.call("plugin___boom", serde_json::json!({})).await;
+        assert!(first.is_err());
+
+        let second = manager.call("plugin___boom", serde_json::json!({})).await.unwrap_err();
+        assert!(second.to_string().contains("unavailable"));
+    }
+}
@@ -0,0 +1,154 @@
+//! OS-level process hardening applied once at server startup when running non-privileged: drops
+//! Linux capabilities the TCL sandbox doesn't need, and installs a seccomp syscall filter as a
+//! backstop in case that sandbox is ever bypassed (e.g. the [`crate::tcl_executor`]
+//! `UNSAFE_COMMANDS` denylist, or a capability grant from [`crate::capability_grants`]). Gated
+//! behind `#[cfg(target_os = "linux")]` internally; [`harden_if_restricted`] itself is callable on
+//! every platform and just reports unsupported where that cfg doesn't apply.
+use serde::Serialize;
+
+/// What hardening actually got applied, so a caller can audit the real sandbox through
+/// `tcl/capabilities` rather than trusting the `privileged` flag alone.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HardeningReport {
+    /// False on non-Linux targets or when `privileged` is true, in which case the rest of the
+    /// fields are left at their defaults (nothing attempted).
+    pub platform_supported: bool,
+    pub capabilities_dropped: Vec<String>,
+    pub seccomp_enabled: bool,
+    /// Set if dropping capabilities or installing the seccomp filter failed partway through; the
+    /// server still starts; a restricted process that failed to harden is safer to run (with the
+    /// TCL-layer sandbox still active) than to refuse to start at all.
+    pub error: Option<String>,
+}
+
+/// Applies [`HardeningReport`]-producing hardening once, at server startup, only when running
+/// non-privileged — a privileged server legitimately needs the full OS capability set, since
+/// `--privileged` already means "trust this process with the real TCL interpreter and exec".
+pub fn harden_if_restricted(privileged: bool) -> HardeningReport {
+    if privileged {
+        return HardeningReport::default();
+    }
+    linux::apply()
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::HardeningReport;
+    use caps::CapSet;
+    use seccompiler::{BpfProgram, SeccompAction, SeccompFilter, TargetArch};
+    use std::collections::BTreeMap;
+    use std::convert::TryInto;
+    use tracing::warn;
+
+    /// Syscalls a non-privileged TCL sandbox should never need once it's past startup: spawning
+    /// processes or creating sockets (`socket`/`socketpair` are denied for every socket type, not
+    /// just raw ones). Deliberately does *not* include `open`/`openat` — unlike
+    /// a true one-shot sandboxed worker, this server's persistence layer
+    /// ([`crate::persistence`]) and tool discovery ([`crate::tool_discovery`]) keep doing regular
+    /// file I/O for the rest of the process's life, so blocking those syscalls here would break
+    /// already-shipped features rather than just the TCL script sandbox this backstops. Everything
+    /// not listed keeps its default action (`Allow`), so the rest of the async runtime (epoll,
+    /// futexes, timers, file I/O) keeps working unmodified.
+    const DENIED_SYSCALLS: &[(&str, i64)] = &[
+        ("execve", libc::SYS_execve),
+        ("execveat", libc::SYS_execveat),
+        ("socket", libc::SYS_socket),
+        ("socketpair", libc::SYS_socketpair),
+    ];
+
+    pub fn apply() -> HardeningReport {
+        let mut report = HardeningReport {
+            platform_supported: true,
+            ..Default::default()
+        };
+
+        match drop_all_capabilities() {
+            Ok(dropped) => report.capabilities_dropped = dropped,
+            Err(e) => {
+                warn!("Failed to drop Linux capabilities: {}", e);
+                report.error = Some(format!("failed to drop capabilities: {}", e));
+                return report;
+            }
+        }
+
+        match install_seccomp_filter() {
+            Ok(()) => report.seccomp_enabled = true,
+            Err(e) => {
+                warn!("Failed to install seccomp filter: {}", e);
+                report.error = Some(format!("failed to install seccomp filter: {}", e));
+            }
+        }
+
+        report
+    }
+
+    /// Drops every capability this process might hold from the effective, permitted, inheritable,
+    /// and bounding sets, so a capability can't come back via `exec`-time inheritance or a
+    /// capability-aware binary re-raising from the bounding set.
+    fn drop_all_capabilities() -> Result<Vec<String>, caps::errors::CapsError> {
+        let all = caps::all();
+        // Bounding-set drops need CAP_SETPCAP in the effective set, so they go first.
+        for cap in &all {
+            caps::drop(None, CapSet::Bounding, *cap)?;
+        }
+        caps::clear(None, CapSet::Effective)?;
+        caps::clear(None, CapSet::Permitted)?;
+        caps::clear(None, CapSet::Inheritable)?;
+        Ok(all.iter().map(|c| c.to_string()).collect())
+    }
+
+    /// Builds an allow-by-default seccomp-bpf filter that errors out [`DENIED_SYSCALLS`] with
+    /// `EPERM` instead of running them, and loads it for the current thread and its future
+    /// children. Irreversible for the lifetime of the process, by design.
+    fn install_seccomp_filter() -> anyhow::Result<()> {
+        let mut rules = BTreeMap::new();
+        for (_name, nr) in DENIED_SYSCALLS {
+            rules.insert(*nr, vec![]);
+        }
+
+        let filter = SeccompFilter::new(
+            rules,
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::EPERM as u32),
+            target_arch(),
+        )?;
+
+        let program: BpfProgram = filter.try_into()?;
+        seccompiler::apply_filter(&program)?;
+        Ok(())
+    }
+
+    /// The filter must be built against the architecture it'll actually run on — a filter built
+    /// for the wrong `TargetArch` silently fails its arch check and lets every syscall through.
+    #[cfg(target_arch = "x86_64")]
+    fn target_arch() -> TargetArch {
+        TargetArch::x86_64
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn target_arch() -> TargetArch {
+        TargetArch::aarch64
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod linux {
+    use super::HardeningReport;
+
+    pub fn apply() -> HardeningReport {
+        HardeningReport::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privileged_skips_hardening_entirely() {
+        let report = harden_if_restricted(true);
+        assert!(!report.platform_supported);
+        assert!(report.capabilities_dropped.is_empty());
+        assert!(!report.seccomp_enabled);
+    }
+}
@@ -0,0 +1,357 @@
+//! A TUF-style (The Update Framework) signed registry gating which TCL user-defined function
+//! (UDF) definitions this server will register. A `root` document lists the public keys trusted
+//! to sign metadata and how many of them must agree; a `targets` document maps each UDF name to
+//! the content hash it must match. `tcl_tool_add` consults [`Registry::verify_udf`] before
+//! handing a definition to the executor, and `POST /udf/verify` exposes the same check without
+//! registering anything.
+use anyhow::{anyhow, Result};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A detached signature over a document's canonical (signature-free) bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetachedSignature {
+    pub key_id: String,
+    pub signature_hex: String,
+}
+
+/// A single trusted signing key, identified by an operator-chosen `key_id` and its hex-encoded
+/// Ed25519 public key bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedKey {
+    pub key_id: String,
+    pub public_key_hex: String,
+}
+
+/// The root trust document: which keys are authorized to sign `targets`, how many must agree,
+/// and when this document itself expires. Every version after the first must carry signatures
+/// from at least `threshold` keys of the *previous* root, so rotation can only be performed by
+/// holders of the outgoing key set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootDocument {
+    pub version: u64,
+    pub keys: Vec<TrustedKey>,
+    pub threshold: usize,
+    pub expires_at: u64,
+    #[serde(default)]
+    pub signatures: Vec<DetachedSignature>,
+}
+
+impl RootDocument {
+    fn signable_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "version": self.version,
+            "keys": self.keys,
+            "threshold": self.threshold,
+            "expires_at": self.expires_at,
+        }))
+        .expect("RootDocument fields are always serializable")
+    }
+}
+
+/// A single `targets` entry: the SHA-256 hash (hex) a UDF's script must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetEntry {
+    pub sha256_hex: String,
+}
+
+/// The targets document: one content hash per UDF name, signed by the current root's keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetsDocument {
+    pub version: u64,
+    pub expires_at: u64,
+    pub targets: HashMap<String, TargetEntry>,
+    #[serde(default)]
+    pub signatures: Vec<DetachedSignature>,
+}
+
+impl TargetsDocument {
+    fn signable_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "version": self.version,
+            "expires_at": self.expires_at,
+            "targets": self.targets,
+        }))
+        .expect("TargetsDocument fields are always serializable")
+    }
+}
+
+/// Why a candidate UDF definition (or metadata document) was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationFailure {
+    RootExpired,
+    TargetsExpired,
+    InsufficientSignatures { required: usize, valid: usize },
+    UnknownTarget,
+    HashMismatch,
+}
+
+impl std::fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerificationFailure::RootExpired => write!(f, "root trust document has expired"),
+            VerificationFailure::TargetsExpired => write!(f, "targets document has expired"),
+            VerificationFailure::InsufficientSignatures { required, valid } => write!(
+                f,
+                "targets document has {valid} valid signature(s) from trusted keys, needs {required}"
+            ),
+            VerificationFailure::UnknownTarget => write!(f, "no signed target entry for this UDF name"),
+            VerificationFailure::HashMismatch => write!(f, "UDF body does not match its signed content hash"),
+        }
+    }
+}
+
+/// Counts how many of `signatures` are valid Ed25519 signatures over `message` from a key in
+/// `keys`, ignoring signatures from unknown `key_id`s and malformed hex/signature bytes.
+fn count_valid_signatures(signatures: &[DetachedSignature], keys: &[TrustedKey], message: &[u8]) -> usize {
+    signatures
+        .iter()
+        .filter(|sig| {
+            let Some(key) = keys.iter().find(|k| k.key_id == sig.key_id) else { return false };
+            let Ok(key_bytes) = hex::decode(&key.public_key_hex) else { return false };
+            let Ok(key_bytes) = <[u8; 32]>::try_from(key_bytes.as_slice()) else { return false };
+            let Ok(verifying_key) = VerifyingKey::from_bytes(&key_bytes) else { return false };
+            let Ok(sig_bytes) = hex::decode(&sig.signature_hex) else { return false };
+            let Ok(sig_bytes) = <[u8; 64]>::try_from(sig_bytes.as_slice()) else { return false };
+            verifying_key.verify(message, &Signature::from_bytes(&sig_bytes)).is_ok()
+        })
+        .count()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+struct RegistryState {
+    root: RootDocument,
+    targets: TargetsDocument,
+}
+
+/// Holds the currently-trusted `root`/`targets` pair and verifies candidate UDF definitions
+/// against them. Shared across requests via `Arc` (mirroring `ApiKeyStore`'s `Arc<Mutex<..>>`
+/// shape), since rotation replaces both documents behind a single lock.
+pub struct Registry {
+    state: RwLock<RegistryState>,
+}
+
+impl Registry {
+    /// Validates that `targets` is signed by at least `root.threshold` of `root`'s keys before
+    /// accepting the pair as the initial trusted state.
+    pub fn new(root: RootDocument, targets: TargetsDocument) -> Result<Self> {
+        let valid = count_valid_signatures(&targets.signatures, &root.keys, &targets.signable_bytes());
+        if valid < root.threshold {
+            return Err(anyhow!(
+                "initial targets document has {valid} valid signature(s), needs {}",
+                root.threshold
+            ));
+        }
+        Ok(Self { state: RwLock::new(RegistryState { root, targets }) })
+    }
+
+    /// Loads `root.json` and `targets.json` from `dir`.
+    pub fn load_from_dir(dir: &Path) -> Result<Self> {
+        let root: RootDocument = serde_json::from_str(&std::fs::read_to_string(dir.join("root.json"))?)?;
+        let targets: TargetsDocument = serde_json::from_str(&std::fs::read_to_string(dir.join("targets.json"))?)?;
+        Self::new(root, targets)
+    }
+
+    /// Checks whether `script` would be accepted as the body of UDF `name` under the current
+    /// trust state, without mutating anything.
+    pub fn verify_udf(&self, name: &str, script: &str) -> Result<(), VerificationFailure> {
+        let state = self.state.read().unwrap();
+        let now = now_secs();
+
+        if state.root.expires_at <= now {
+            return Err(VerificationFailure::RootExpired);
+        }
+        if state.targets.expires_at <= now {
+            return Err(VerificationFailure::TargetsExpired);
+        }
+
+        let valid = count_valid_signatures(&state.targets.signatures, &state.root.keys, &state.targets.signable_bytes());
+        if valid < state.root.threshold {
+            return Err(VerificationFailure::InsufficientSignatures { required: state.root.threshold, valid });
+        }
+
+        let Some(entry) = state.targets.targets.get(name) else {
+            return Err(VerificationFailure::UnknownTarget);
+        };
+
+        let actual_hash = hex::encode(Sha256::digest(script.as_bytes()));
+        if !constant_time_eq(&actual_hash, &entry.sha256_hex) {
+            return Err(VerificationFailure::HashMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the trusted `root` with `new_root`, which is only accepted if signed by at least
+    /// the *current* (soon-to-be-previous) root's threshold of keys — so rotation requires
+    /// cooperation from the outgoing key set, not the incoming one.
+    pub fn rotate_root(&self, new_root: RootDocument) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+
+        let valid = count_valid_signatures(&new_root.signatures, &state.root.keys, &new_root.signable_bytes());
+        if valid < state.root.threshold {
+            return Err(anyhow!(
+                "new root document has {valid} valid signature(s) from the previous root's keys, needs {}",
+                state.root.threshold
+            ));
+        }
+
+        state.root = new_root;
+        Ok(())
+    }
+
+    /// Replaces the trusted `targets` document, requiring it to be signed by the threshold of
+    /// the *current* root's keys and not already expired.
+    pub fn update_targets(&self, new_targets: TargetsDocument) -> Result<()> {
+        let mut state = self.state.write().unwrap();
+
+        if new_targets.expires_at <= now_secs() {
+            return Err(anyhow!("replacement targets document is already expired"));
+        }
+
+        let valid = count_valid_signatures(&new_targets.signatures, &state.root.keys, &new_targets.signable_bytes());
+        if valid < state.root.threshold {
+            return Err(anyhow!(
+                "replacement targets document has {valid} valid signature(s), needs {}",
+                state.root.threshold
+            ));
+        }
+
+        state.targets = new_targets;
+        Ok(())
+    }
+}
+
+/// Constant-time string comparison, mirroring `auth::verify_api_key`'s rationale: target hashes
+/// are not secret, but comparing them the same way as everything else avoids timing side
+/// channels becoming a habit to reason about case-by-case.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Resolves the registry directory from `TCL_MCP_UDF_REGISTRY_DIR`. Returns `Ok(None)` when the
+/// feature isn't configured, so UDF loading behaves exactly as before by default.
+pub fn registry_from_env() -> Result<Option<Registry>> {
+    let Some(dir) = std::env::var("TCL_MCP_UDF_REGISTRY_DIR").ok().filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    Registry::load_from_dir(&PathBuf::from(dir)).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const FAR_FUTURE: u64 = 4_102_444_800; // 2100-01-01
+
+    fn test_key(seed: u8, key_id: &str) -> (SigningKey, TrustedKey) {
+        let signing_key = SigningKey::from_bytes(&[seed; 32]);
+        let trusted = TrustedKey {
+            key_id: key_id.to_string(),
+            public_key_hex: hex::encode(signing_key.verifying_key().to_bytes()),
+        };
+        (signing_key, trusted)
+    }
+
+    fn sign(signing_key: &SigningKey, key_id: &str, message: &[u8]) -> DetachedSignature {
+        DetachedSignature { key_id: key_id.to_string(), signature_hex: hex::encode(signing_key.sign(message).to_bytes()) }
+    }
+
+    fn single_key_root(threshold: usize) -> (SigningKey, RootDocument) {
+        let (key, trusted) = test_key(1, "root-key-1");
+        (key, RootDocument { version: 1, keys: vec![trusted], threshold, expires_at: FAR_FUTURE, signatures: Vec::new() })
+    }
+
+    #[test]
+    fn test_registry_accepts_correctly_signed_matching_udf() {
+        let (root_key, root) = single_key_root(1);
+        let mut targets = TargetsDocument {
+            version: 1,
+            expires_at: FAR_FUTURE,
+            targets: HashMap::from([("alice/util".to_string(), TargetEntry { sha256_hex: hex::encode(Sha256::digest(b"puts hi")) })]),
+            signatures: Vec::new(),
+        };
+        targets.signatures = vec![sign(&root_key, "root-key-1", &targets.signable_bytes())];
+
+        let registry = Registry::new(root, targets).unwrap();
+        assert_eq!(registry.verify_udf("alice/util", "puts hi"), Ok(()));
+    }
+
+    #[test]
+    fn test_registry_rejects_hash_mismatch() {
+        let (root_key, root) = single_key_root(1);
+        let mut targets = TargetsDocument {
+            version: 1,
+            expires_at: FAR_FUTURE,
+            targets: HashMap::from([("alice/util".to_string(), TargetEntry { sha256_hex: hex::encode(Sha256::digest(b"puts hi")) })]),
+            signatures: Vec::new(),
+        };
+        targets.signatures = vec![sign(&root_key, "root-key-1", &targets.signable_bytes())];
+
+        let registry = Registry::new(root, targets).unwrap();
+        assert_eq!(registry.verify_udf("alice/util", "puts bye"), Err(VerificationFailure::HashMismatch));
+    }
+
+    #[test]
+    fn test_registry_rejects_unknown_target() {
+        let (root_key, root) = single_key_root(1);
+        let mut targets = TargetsDocument { version: 1, expires_at: FAR_FUTURE, targets: HashMap::new(), signatures: Vec::new() };
+        targets.signatures = vec![sign(&root_key, "root-key-1", &targets.signable_bytes())];
+
+        let registry = Registry::new(root, targets).unwrap();
+        assert_eq!(registry.verify_udf("alice/util", "puts hi"), Err(VerificationFailure::UnknownTarget));
+    }
+
+    #[test]
+    fn test_registry_rejects_insufficient_signatures_for_threshold() {
+        let (key1, trusted1) = test_key(1, "root-key-1");
+        let (_key2, trusted2) = test_key(2, "root-key-2");
+        let root = RootDocument { version: 1, keys: vec![trusted1, trusted2], threshold: 2, expires_at: FAR_FUTURE, signatures: Vec::new() };
+
+        let mut targets = TargetsDocument {
+            version: 1,
+            expires_at: FAR_FUTURE,
+            targets: HashMap::from([("alice/util".to_string(), TargetEntry { sha256_hex: hex::encode(Sha256::digest(b"puts hi")) })]),
+            signatures: Vec::new(),
+        };
+        // Only one of the two required keys signs.
+        targets.signatures = vec![sign(&key1, "root-key-1", &targets.signable_bytes())];
+
+        assert!(Registry::new(root, targets).is_err());
+    }
+
+    #[test]
+    fn test_rotate_root_requires_previous_threshold() {
+        let (old_key, old_root) = single_key_root(1);
+        let mut targets = TargetsDocument {
+            version: 1,
+            expires_at: FAR_FUTURE,
+            targets: HashMap::new(),
+            signatures: Vec::new(),
+        };
+        targets.signatures = vec![sign(&old_key, "root-key-1", &targets.signable_bytes())];
+        let registry = Registry::new(old_root, targets).unwrap();
+
+        let (_new_key, new_trusted) = test_key(9, "root-key-9");
+        let mut new_root = RootDocument { version: 2, keys: vec![new_trusted], threshold: 1, expires_at: FAR_FUTURE, signatures: Vec::new() };
+
+        // Unsigned rotation is rejected...
+        assert!(registry.rotate_root(new_root.clone()).is_err());
+
+        // ...but signed by the outgoing root's key, it succeeds.
+        new_root.signatures = vec![sign(&old_key, "root-key-1", &new_root.signable_bytes())];
+        assert!(registry.rotate_root(new_root).is_ok());
+    }
+}
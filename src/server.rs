@@ -5,9 +5,11 @@ use serde_json::json;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tracing::{info, debug};
 
-use crate::tcl_tools::{TclToolBox, TclExecuteRequest, TclToolAddRequest, TclToolRemoveRequest, TclToolListRequest, TclExecToolRequest};
-use crate::tcl_executor::TclExecutor;
+use crate::tcl_tools::{TclToolBox, TclExecuteRequest, TclToolAddRequest, TclToolRemoveRequest, TclToolListRequest, TclToolReceiptRequest, TclExecToolRequest, TclExecBatchRequest, TclPipelineRequest, TclDiscoverToolsRequest, TclToolTrustRequest, TclToolRevokeRequest, TclToolTestRequest, TclToolCoverageRequest, TclComposeRequest};
+use crate::tcl_executor::{TclExecutorPool, pool_size_from_env};
+use crate::tcl_runtime::RuntimeConfig;
 use crate::namespace::ToolPath;
+use crate::process_hardening::harden_if_restricted;
 
 #[derive(Clone)]
 pub struct TclMcpServer {
@@ -37,6 +39,11 @@ struct McpCallToolParams {
 #[derive(Debug, Serialize, Deserialize)]
 struct McpCallToolResult {
     content: Vec<McpContent>,
+    /// Set when the tool itself failed (as opposed to a protocol-level `jsonrpc_core::Error`),
+    /// so clients can distinguish "the tool ran and reported failure" from "the call couldn't
+    /// be made".
+    #[serde(rename = "isError")]
+    is_error: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,22 +51,121 @@ struct McpCallToolResult {
 enum McpContent {
     #[serde(rename = "text")]
     Text { text: String },
+    #[serde(rename = "json")]
+    Json { data: Value },
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct McpResourceInfo {
+    uri: String,
+    name: String,
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct McpListResourcesResult {
+    resources: Vec<McpResourceInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct McpReadResourceParams {
+    uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct McpResourceContents {
+    uri: String,
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct McpReadResourceResult {
+    contents: Vec<McpResourceContents>,
+}
+
+/// Topic names shared by `docs___molt_book` and the `molt-book://{topic}` resources, so the
+/// tool and the resource listing can't drift apart on what topics exist.
+const MOLT_BOOK_TOPICS: &[&str] = &["overview", "basic_syntax", "commands", "examples", "links"];
+
+/// URI scheme for Molt Book topics exposed as MCP resources (`molt-book://{topic}`).
+const MOLT_BOOK_RESOURCE_SCHEME: &str = "molt-book://";
+
+/// Protocol versions this server understands, newest first. The `initialize` handshake picks
+/// the highest version that also appears in the client's request.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
 impl TclMcpServer {
     pub fn new(privileged: bool) -> Self {
-        // Spawn the TCL executor with privilege settings
-        let executor = TclExecutor::spawn(privileged);
-        let tool_box = TclToolBox::new(executor);
+        // stdio only ever has one client, but tool calls still shouldn't serialize behind a
+        // single interpreter (see `HttpMcpServer::new`); pool size defaults to `num_cpus::get()`
+        // (see `TCL_MCP_POOL_SIZE`/`TCL_MCP_EXECUTOR_POOL_SIZE`).
+        let pool = TclExecutorPool::spawn(privileged, pool_size_from_env());
+        Self::from_tool_box(TclToolBox::with_pool(pool), privileged)
+    }
+
+    /// Same as [`Self::new`], but with an explicit `--runtime`/`--eval-timeout`/`--pool-size`
+    /// configuration instead of just privilege and the env-derived pool size, mirroring
+    /// `HttpMcpServer::new_with_runtime`.
+    pub fn new_with_runtime(privileged: bool, runtime_config: RuntimeConfig) -> Result<Self, String> {
+        let size = runtime_config.executor_pool_size.unwrap_or_else(pool_size_from_env);
+        let pool = TclExecutorPool::spawn_with_runtime(privileged, size, runtime_config)?;
+        Ok(Self::from_tool_box(TclToolBox::with_pool(pool), privileged))
+    }
+
+    /// Builds the MCP method table against an already-constructed `TclToolBox`, shared by
+    /// [`Self::new`] and [`Self::new_with_runtime`] so the two only differ in how the pool behind
+    /// `tool_box` gets built.
+    fn from_tool_box(tool_box: TclToolBox, privileged: bool) -> Self {
+        // Applied once, here at construction (the stdio transport's actual entry point), so
+        // `main.rs` doesn't need to remember to call it itself; see `HttpMcpServer::apply_hardening`.
+        let hardening = harden_if_restricted(privileged);
+        if hardening.platform_supported {
+            if let Some(error) = &hardening.error {
+                tracing::warn!("Process hardening only partially applied: {error}");
+            } else {
+                info!(
+                    "Process hardening applied: dropped {} Linux capabilities, seccomp {}",
+                    hardening.capabilities_dropped.len(),
+                    if hardening.seccomp_enabled { "enabled" } else { "not enabled" }
+                );
+            }
+        } else if !privileged {
+            info!("Process hardening not applied (unsupported platform); relying on the TCL-layer sandbox only");
+        }
+
         let mut handler = IoHandler::new();
-        
-        // Register MCP methods
-        handler.add_sync_method("initialize", move |_params: Params| {
-            info!("MCP initialize called");
+
+        // Register MCP methods. These all run as async methods on the runtime driving
+        // `run_stdio` (see `main.rs`'s `#[tokio::main]`) rather than spawning a thread plus a
+        // fresh `tokio::runtime::Runtime` per call, which used to be the dominant per-request
+        // cost and the only source of the "Thread panic" error path.
+        handler.add_method("initialize", move |params: Params| async move {
+            let requested: Option<Value> = params.parse().ok();
+            let requested_version = requested.as_ref()
+                .and_then(|p| p.get("protocolVersion"))
+                .and_then(|v| v.as_str());
+
+            let negotiated_version = match requested_version {
+                Some(requested) if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) => requested,
+                Some(requested) => {
+                    return Err(jsonrpc_core::Error::invalid_params(format!(
+                        "Unsupported protocolVersion '{}'; server supports: {}",
+                        requested,
+                        SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+                    )));
+                }
+                None => SUPPORTED_PROTOCOL_VERSIONS[0],
+            };
+
+            info!("MCP initialize called (protocolVersion: {})", negotiated_version);
             Ok(json!({
-                "protocolVersion": "2024-11-05",
+                "protocolVersion": negotiated_version,
                 "capabilities": {
-                    "tools": {}
+                    "tools": {},
+                    "resources": {}
                 },
                 "serverInfo": {
                     "name": "tcl-mcp-server",
@@ -67,270 +173,280 @@ impl TclMcpServer {
                 }
             }))
         });
-        
+
+        let is_privileged_for_capabilities = privileged;
+        let hardening_for_capabilities = hardening.clone();
+        let tb = tool_box.clone();
+        handler.add_method("tcl/capabilities", move |_params: Params| {
+            let tb = tb.clone();
+            let hardening = hardening_for_capabilities.clone();
+            async move {
+                let runtime = crate::tcl_runtime::create_runtime();
+                let probes = ["set", "expr", "proc", "foreach", "while", "namespace"];
+                let commands: serde_json::Map<String, Value> = probes
+                    .iter()
+                    .map(|cmd| (cmd.to_string(), json!(runtime.has_command(cmd))))
+                    .collect();
+
+                let sandbox = tb.capabilities().await
+                    .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+
+                Ok(json!({
+                    "protocolVersions": SUPPORTED_PROTOCOL_VERSIONS,
+                    "namespaces": sandbox.namespaces,
+                    "features": sandbox.features,
+                    "privileged": is_privileged_for_capabilities,
+                    "sandbox": sandbox,
+                    "runtime": {
+                        "name": runtime.name(),
+                        "version": runtime.version(),
+                        "commands": commands,
+                    },
+                    "hardening": hardening,
+                }))
+            }
+        });
+
         let tb = tool_box.clone();
         let is_privileged = privileged;
-        handler.add_sync_method("tools/list", move |_params: Params| {
+        handler.add_method("tools/list", move |_params: Params| {
             debug!("MCP tools/list called (privileged: {})", is_privileged);
             let tb = tb.clone();
-            
-            // Don't use async block here since we're in a sync context
-            let mut tools = vec![];
-                
-                // Add system tools with MCP-compatible names
-                let mut system_tools = vec![
-                    (ToolPath::bin("tcl_execute"), "Execute a TCL script and return the result", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {
-                            "script": {
-                                "type": "string",
-                                "description": "TCL script to execute"
-                            }
-                        },
-                        "required": ["script"]
-                    })),
-                    (ToolPath::bin("tcl_tool_list"), "List all available TCL tools", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {
-                            "namespace": {
-                                "type": "string",
-                                "description": "Filter tools by namespace (optional)"
-                            },
-                            "filter": {
-                                "type": "string",
-                                "description": "Filter tools by name pattern (optional)"
-                            }
+            async move {
+                let tools = build_tool_infos(&tb, is_privileged).await;
+                Ok(json!(McpListToolsResult { tools }))
+            }
+        });
+
+        let tb = tool_box.clone();
+        let is_privileged_call = privileged;
+        handler.add_method("tools/call", move |params: Params| {
+            debug!("MCP tools/call called with params: {:?}", params);
+            let tb = tb.clone();
+            async move {
+                let params: McpCallToolParams = params.parse()?;
+                info!("Calling tool: {} (privileged: {})", params.name, is_privileged_call);
+
+                let schema = build_tool_infos(&tb, is_privileged_call).await
+                    .into_iter()
+                    .find(|tool| tool.name == params.name)
+                    .map(|tool| tool.input_schema);
+
+                if let Some(schema) = schema {
+                    if let Err(errors) = validate_arguments_against_schema(&schema, &params.arguments) {
+                        return Err(jsonrpc_core::Error::invalid_params(format!(
+                            "Invalid arguments for '{}': {}",
+                            params.name,
+                            errors.join("; ")
+                        )));
+                    }
+                }
+
+                let result: Result<String> = async move {
+                    // Check if it's a system tool by MCP name
+                    match params.name.as_str() {
+                        "bin___tcl_execute" => {
+                            let request: TclExecuteRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_execute(request).await
                         }
-                    })),
-                    (ToolPath::docs("molt_book"), "Access Molt TCL interpreter documentation and examples", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {
-                            "topic": {
-                                "type": "string",
-                                "description": "Documentation topic: 'overview', 'commands', 'examples', 'links', or 'basic_syntax'",
-                                "enum": ["overview", "commands", "examples", "links", "basic_syntax"]
+                        "sbin___tcl_tool_add" => {
+                            if !is_privileged_call {
+                                return Err(anyhow::anyhow!("Tool management requires --privileged mode"));
                             }
-                        },
-                        "required": ["topic"]
-                    })),
-                    (ToolPath::bin("exec_tool"), "Execute a tool by its path with parameters", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {
-                            "tool_path": {
-                                "type": "string",
-                                "description": "Full path to the tool (e.g., '/bin/list_dir')"
-                            },
-                            "params": {
-                                "type": "object",
-                                "description": "Parameters to pass to the tool",
-                                "default": {}
+                            let request: TclToolAddRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_add(request).await
+                        }
+                        "sbin___tcl_tool_remove" => {
+                            if !is_privileged_call {
+                                return Err(anyhow::anyhow!("Tool management requires --privileged mode"));
                             }
-                        },
-                        "required": ["tool_path"]
-                    })),
-                    (ToolPath::bin("discover_tools"), "Discover and index tools from the filesystem", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {}
-                    })),
-                ];
-                
-                // Add privileged tools only if in privileged mode
-                if is_privileged {
-                    system_tools.push((ToolPath::sbin("tcl_tool_add"), "Add a new TCL tool to the available tools (PRIVILEGED)", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {
-                            "user": {
-                                "type": "string",
-                                "description": "User namespace"
-                            },
-                            "package": {
-                                "type": "string",
-                                "description": "Package name"
-                            },
-                            "name": {
-                                "type": "string",
-                                "description": "Name of the new tool"
-                            },
-                            "version": {
-                                "type": "string",
-                                "description": "Version of the tool (defaults to 'latest')",
-                                "default": "latest"
-                            },
-                            "description": {
-                                "type": "string",
-                                "description": "Description of what the tool does"
-                            },
-                            "script": {
-                                "type": "string",
-                                "description": "TCL script that implements the tool"
-                            },
-                            "parameters": {
-                                "type": "array",
-                                "description": "Parameters that the tool accepts",
-                                "items": {
-                                    "type": "object",
-                                    "properties": {
-                                        "name": { "type": "string" },
-                                        "description": { "type": "string" },
-                                        "required": { "type": "boolean" },
-                                        "type_name": { "type": "string" }
-                                    },
-                                    "required": ["name", "description", "required", "type_name"]
-                                }
+                            let request: TclToolRemoveRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_remove(request).await
+                        }
+                        "sbin___tcl_tool_trust" => {
+                            if !is_privileged_call {
+                                return Err(anyhow::anyhow!("Tool management requires --privileged mode"));
                             }
-                        },
-                        "required": ["user", "package", "name", "description", "script"]
-                    })));
-                    system_tools.push((ToolPath::sbin("tcl_tool_remove"), "Remove a TCL tool from the available tools (PRIVILEGED)", json!({
-                        "$schema": "https://json-schema.org/draft/2020-12/schema",
-                        "type": "object",
-                        "properties": {
-                            "path": {
-                                "type": "string",
-                                "description": "Full tool path (e.g., '/alice/utils/reverse_string:1.0')"
+                            let request: TclToolTrustRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_trust(request).await
+                        }
+                        "sbin___tcl_tool_revoke" => {
+                            if !is_privileged_call {
+                                return Err(anyhow::anyhow!("Tool management requires --privileged mode"));
                             }
-                        },
-                        "required": ["path"]
-                    })));
-                }
-                
-                for (path, description, schema) in system_tools {
-                    tools.push(McpToolInfo {
-                        name: path.to_mcp_name(),
-                        description: Some(format!("{} [{}]", description, path)),
-                        input_schema: schema,
-                    });
-                }
-                
-            // Get custom tools synchronously - this should be fast
-            let custom_tools = match std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(tb.get_tool_definitions())
-            }).join() {
-                Ok(result) => result,
-                Err(_) => {
-                    return Err(jsonrpc_core::Error::internal_error());
-                }
-            };
-            
-            // Add custom tools to the list
-            if let Ok(tool_defs) = custom_tools {
-                for tool_def in tool_defs {
-                    // Build input schema for custom tool
-                    let mut properties = serde_json::Map::new();
-                    let mut required = Vec::new();
-                    
-                    for param in &tool_def.parameters {
-                        // Validate and normalize JSON Schema type
-                        let json_type = match param.type_name.to_lowercase().as_str() {
-                            "string" | "str" | "text" => "string",
-                            "number" | "float" | "double" | "real" => "number",
-                            "integer" | "int" | "long" => "integer", 
-                            "boolean" | "bool" => "boolean",
-                            "array" | "list" => "array",
-                            "object" | "dict" | "map" => "object",
-                            "null" | "nil" | "none" => "null",
-                            // Default to string for unknown types to maintain compatibility
-                            _ => "string"
-                        };
-                        
-                        properties.insert(
-                            param.name.clone(),
-                            json!({
-                                "type": json_type,
-                                "description": param.description,
-                            }),
-                        );
-                        
-                        if param.required {
-                            required.push(param.name.clone());
+                            let request: TclToolRevokeRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_revoke(request).await
+                        }
+                        "bin___tcl_tool_list" => {
+                            let request: TclToolListRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_list(request).await
+                        }
+                        "bin___tcl_tool_receipt" => {
+                            let request: TclToolReceiptRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_receipt(request).await
+                        }
+                        "bin___exec_tool" => {
+                            let request: TclExecToolRequest = serde_json::from_value(params.arguments)?;
+                            tb.exec_tool(request).await
+                        }
+                        "bin___tcl_tool_test" => {
+                            let request: TclToolTestRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_test(request).await
+                        }
+                        "bin___tcl_tool_coverage" => {
+                            let request: TclToolCoverageRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_coverage(request).await
+                        }
+                        "bin___tcl_tool_compose" => {
+                            let request: TclComposeRequest = serde_json::from_value(params.arguments)?;
+                            tb.tcl_tool_compose(request).await
+                        }
+                        "bin___discover_tools" => {
+                            let request: TclDiscoverToolsRequest = serde_json::from_value(params.arguments)?;
+                            tb.discover_tools(request).await
+                        }
+                        "bin___exec_batch" => {
+                            let request: TclExecBatchRequest = serde_json::from_value(params.arguments)?;
+                            tb.exec_batch(request).await
+                        }
+                        "bin___pipeline" => {
+                            let request: TclPipelineRequest = serde_json::from_value(params.arguments)?;
+                            tb.pipeline(request).await
+                        }
+                        "docs___molt_book" => {
+                            let topic = params.arguments.get("topic")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("overview");
+
+                            molt_book_topic_content(topic).ok_or_else(|| anyhow::anyhow!(
+                                "Unknown documentation topic: {}. Available topics: {}",
+                                topic, MOLT_BOOK_TOPICS.join(", ")
+                            ))
+                        }
+                        mcp_name => {
+                            // Try to execute as a custom tool
+                            tb.execute_custom_tool(mcp_name, params.arguments).await
                         }
                     }
-                    
-                    // Build the schema object, only including "required" if it's not empty
-                    let mut schema_obj = serde_json::Map::new();
-                    schema_obj.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
-                    schema_obj.insert("type".to_string(), json!("object"));
-                    schema_obj.insert("properties".to_string(), json!(properties));
-                    
-                    // Only add "required" array if there are required parameters
-                    if !required.is_empty() {
-                        schema_obj.insert("required".to_string(), json!(required));
+                }.await;
+
+                match result {
+                    Ok(text) => Ok(json!(McpCallToolResult {
+                        content: vec![McpContent::Text { text }],
+                        is_error: false,
+                    })),
+                    Err(e) => {
+                        // Report tool-level failures as a successful call carrying an error-flagged
+                        // result rather than a JSON-RPC error, so the client keeps whatever partial
+                        // output the tool produced (e.to_string()) alongside a machine-readable error.
+                        Ok(json!(McpCallToolResult {
+                            content: vec![
+                                McpContent::Text { text: e.to_string() },
+                                McpContent::Json { data: json!({ "error": e.to_string() }) },
+                            ],
+                            is_error: true,
+                        }))
                     }
-                    
-                    let input_schema = serde_json::Value::Object(schema_obj);
-                    
-                    tools.push(McpToolInfo {
-                        name: tool_def.path.to_mcp_name(),
-                        description: Some(format!("{} [{}]", tool_def.description, tool_def.path)),
-                        input_schema,
-                    });
                 }
             }
-            
-            Ok(json!(McpListToolsResult { tools }))
         });
+
+        handler.add_method("resources/list", move |_params: Params| async move {
+            debug!("MCP resources/list called");
+            let resources = MOLT_BOOK_TOPICS.iter().map(|topic| McpResourceInfo {
+                uri: format!("{}{}", MOLT_BOOK_RESOURCE_SCHEME, topic),
+                name: format!("Molt Book: {}", topic),
+                mime_type: "text/markdown".to_string(),
+            }).collect();
+
+            Ok(json!(McpListResourcesResult { resources }))
+        });
+
+        handler.add_method("resources/read", move |params: Params| async move {
+            let params: McpReadResourceParams = params.parse()?;
+            debug!("MCP resources/read called with uri: {}", params.uri);
+
+            let topic = params.uri.strip_prefix(MOLT_BOOK_RESOURCE_SCHEME)
+                .ok_or_else(|| jsonrpc_core::Error::invalid_params(format!(
+                    "Unsupported resource URI '{}'; expected a '{}' URI", params.uri, MOLT_BOOK_RESOURCE_SCHEME
+                )))?;
+
+            let text = molt_book_topic_content(topic).ok_or_else(|| jsonrpc_core::Error::invalid_params(format!(
+                "Unknown documentation topic: {}. Available topics: {}", topic, MOLT_BOOK_TOPICS.join(", ")
+            )))?;
+
+            Ok(json!(McpReadResourceResult {
+                contents: vec![McpResourceContents {
+                    uri: params.uri,
+                    mime_type: "text/markdown".to_string(),
+                    text,
+                }],
+            }))
+        });
+
+        Self { tool_box, handler }
+    }
+
+    /// Initialize persistence for tool storage
+    pub async fn initialize_persistence(&self) -> Result<()> {
+        match self.tool_box.initialize_persistence().await {
+            Ok(message) => {
+                info!("{}", message);
+                Ok(())
+            }
+            Err(e) => {
+                tracing::warn!("Failed to initialize persistence: {}", e);
+                Err(e)
+            }
+        }
+    }
+    
+    pub async fn run_stdio(self) -> Result<()> {
+        info!("Starting TCL MCP server on stdio");
         
-        let tb = tool_box.clone();
-        let is_privileged_call = privileged;
-        handler.add_sync_method("tools/call", move |params: Params| {
-            debug!("MCP tools/call called with params: {:?}", params);
-            let tb = tb.clone();
+        let stdin = tokio::io::stdin();
+        let mut stdout = tokio::io::stdout();
+        let mut reader = BufReader::new(stdin);
+        let mut line = String::new();
+        
+        loop {
+            line.clear();
+            let n = reader.read_line(&mut line).await?;
+            if n == 0 {
+                break; // EOF
+            }
+            
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
             
-            let params: McpCallToolParams = params.parse()?;
-            info!("Calling tool: {} (privileged: {})", params.name, is_privileged_call);
+            debug!("Received request: {}", trimmed);
             
-            let result = std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async move {
-                // Check if it's a system tool by MCP name
-                match params.name.as_str() {
-                    "bin___tcl_execute" => {
-                        let request: TclExecuteRequest = serde_json::from_value(params.arguments)?;
-                        tb.tcl_execute(request).await
-                    }
-                    "sbin___tcl_tool_add" => {
-                        if !is_privileged_call {
-                            return Err(anyhow::anyhow!("Tool management requires --privileged mode"));
-                        }
-                        let request: TclToolAddRequest = serde_json::from_value(params.arguments)?;
-                        tb.tcl_tool_add(request).await
-                    }
-                    "sbin___tcl_tool_remove" => {
-                        if !is_privileged_call {
-                            return Err(anyhow::anyhow!("Tool management requires --privileged mode"));
-                        }
-                        let request: TclToolRemoveRequest = serde_json::from_value(params.arguments)?;
-                        tb.tcl_tool_remove(request).await
-                    }
-                    "bin___tcl_tool_list" => {
-                        let request: TclToolListRequest = serde_json::from_value(params.arguments)?;
-                        tb.tcl_tool_list(request).await
-                    }
-                    "bin___exec_tool" => {
-                        let request: TclExecToolRequest = serde_json::from_value(params.arguments)?;
-                        tb.exec_tool(request).await
-                    }
-                    "bin___discover_tools" => {
-                        tb.discover_tools().await
-                    }
-                    "docs___molt_book" => {
-                        // Handle documentation request
-                        let topic = params.arguments.get("topic")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("overview");
-                        
-                        match topic {
-                            "overview" => Ok(format!(r#"# Molt TCL Interpreter Overview
+            // Process the request
+            let response = self.handler.handle_request(trimmed).await;
+            
+            if let Some(response) = response {
+                debug!("Sending response: {}", response);
+                stdout.write_all(response.as_bytes()).await?;
+                stdout.write_all(b"\n").await?;
+                stdout.flush().await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Markdown content for one `docs___molt_book` topic / `molt-book://{topic}` resource,
+/// shared by both so the two can't drift apart on what a topic actually contains.
+/// `None` for an unrecognized topic.
+fn molt_book_topic_content(topic: &str) -> Option<String> {
+        match topic {
+            "overview" => Some(format!(r#"# Molt TCL Interpreter Overview
 
 ## What is Molt?
-Molt is a TCL (Tool Command Language) interpreter implemented in Rust. It provides a memory-safe, 
+Molt is a TCL (Tool Command Language) interpreter implemented in Rust. It provides a memory-safe,
 embeddable scripting language with familiar TCL syntax.
 
 ## Key Features
@@ -346,7 +462,7 @@ embeddable scripting language with familiar TCL syntax.
 - Source Documentation: https://github.com/wduquette/molt/tree/master/molt-book/src
 
 Use 'basic_syntax', 'commands', 'examples', or 'links' for more specific information."#)),
-                            "basic_syntax" => Ok(format!(r#"# TCL Basic Syntax
+            "basic_syntax" => Some(format!(r#"# TCL Basic Syntax
 
 ## Variables
 ```tcl
@@ -391,7 +507,7 @@ proc greet {{name}} {{
 set message [greet "World"]
 puts $message
 ```"#)),
-                            "commands" => Ok(format!(r#"# Common TCL Commands in Molt
+            "commands" => Some(format!(r#"# Common TCL Commands in Molt
 
 ## String Operations
 - `string length $str` - Get string length
@@ -426,7 +542,7 @@ puts $message
 - `set varName $value` - Set variable
 - `unset varName` - Delete variable
 - `global varName` - Access global variable"#)),
-                            "examples" => Ok(format!(r#"# TCL Examples
+            "examples" => Some(format!(r#"# TCL Examples
 
 ## Example 1: Calculator
 ```tcl
@@ -435,11 +551,11 @@ proc calculate {{op a b}} {{
         "+" {{ return [expr {{$a + $b}}] }}
         "-" {{ return [expr {{$a - $b}}] }}
         "*" {{ return [expr {{$a * $b}}] }}
-        "/" {{ 
+        "/" {{
             if {{$b == 0}} {{
                 error "Division by zero"
             }}
-            return [expr {{$a / $b}}] 
+            return [expr {{$a / $b}}]
         }}
         default {{ error "Unknown operation: $op" }}
     }}
@@ -489,15 +605,15 @@ proc reverse_string {{str}} {{
 puts [word_count "Hello world from TCL"]  ;# 4
 puts [reverse_string "hello"]              ;# olleh
 ```"#)),
-                            "links" => Ok(format!(r#"# Molt TCL Documentation Links
+            "links" => Some(format!(r#"# Molt TCL Documentation Links
 
 ## Official Documentation
 - **Molt Book**: https://wduquette.github.io/molt/
   Complete guide to the Molt TCL interpreter
-  
+
 - **GitHub Repository**: https://github.com/wduquette/molt
   Source code, examples, and issue tracking
-  
+
 - **Book Source**: https://github.com/wduquette/molt/tree/master/molt-book/src
   Markdown source files for the Molt Book
 
@@ -518,84 +634,454 @@ puts [reverse_string "hello"]              ;# olleh
 
 Note: Molt implements a subset of full TCL but covers the core language features.
 For Molt-specific capabilities and limitations, refer to the Molt Book."#)),
-                            _ => Err(anyhow::anyhow!("Unknown documentation topic: {}. Available topics: overview, basic_syntax, commands, examples, links", topic))
-                        }
+            _ => None,
+        }
+}
+
+/// Converts a `ParameterDefinition`'s textual `default`/`enum_values` entries into a JSON value
+/// typed per the parameter's declared `type_name`, so the emitted schema's `default`/`enum` look
+/// like client-supplied values (e.g. `5`, not `"5"`) rather than always a string.
+fn typed_schema_value(type_name: &str, raw: &str) -> Value {
+    match type_name.to_lowercase().as_str() {
+        "number" | "float" | "double" | "real" => raw.parse::<f64>().map(|n| json!(n)).unwrap_or_else(|_| json!(raw)),
+        "integer" | "int" | "long" => raw.parse::<i64>().map(|n| json!(n)).unwrap_or_else(|_| json!(raw)),
+        "boolean" | "bool" => raw.parse::<bool>().map(|b| json!(b)).unwrap_or_else(|_| json!(raw)),
+        _ => json!(raw),
+    }
+}
+
+/// Builds the full `tools/list` entry set: the fixed system tools (plus `sbin` tools when
+/// `is_privileged`), followed by any custom tools registered in `tool_box`. Shared by the
+/// `tools/list` handler and by `tools/call`'s pre-dispatch schema validation so both see the
+/// same tool set.
+async fn build_tool_infos(tool_box: &TclToolBox, is_privileged: bool) -> Vec<McpToolInfo> {
+    let mut tools = vec![];
+
+    // Add system tools with MCP-compatible names
+    let mut system_tools = vec![
+        (ToolPath::bin("tcl_execute"), "Execute a TCL script and return the result", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "script": {
+                    "type": "string",
+                    "description": "TCL script to execute"
+                }
+            },
+            "required": ["script"]
+        })),
+        (ToolPath::bin("tcl_tool_list"), "List all available TCL tools", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "namespace": {
+                    "type": "string",
+                    "description": "Filter tools by namespace (optional)"
+                },
+                "filter": {
+                    "type": "string",
+                    "description": "Filter tools by name pattern (optional)"
+                }
+            }
+        })),
+        (ToolPath::bin("tcl_tool_receipt"), "Get a persisted tool's receipt (timestamps, checksum, origin, schema version)", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Full tool path (e.g., '/alice/utils/reverse_string:1.0')"
+                }
+            },
+            "required": ["path"]
+        })),
+        (ToolPath::docs("molt_book"), "Access Molt TCL interpreter documentation and examples", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "topic": {
+                    "type": "string",
+                    "description": "Documentation topic: 'overview', 'commands', 'examples', 'links', or 'basic_syntax'",
+                    "enum": ["overview", "commands", "examples", "links", "basic_syntax"]
+                }
+            },
+            "required": ["topic"]
+        })),
+        (ToolPath::bin("exec_tool"), "Execute a tool by its path with parameters", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "tool_path": {
+                    "type": "string",
+                    "description": "Full path to the tool (e.g., '/bin/list_dir')"
+                },
+                "params": {
+                    "type": "object",
+                    "description": "Parameters to pass to the tool",
+                    "default": {}
+                }
+            },
+            "required": ["tool_path"]
+        })),
+        (ToolPath::bin("tcl_tool_test"), "Run a custom tool's attached test cases and report pass/fail per case", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Full tool path whose attached test cases should be run (e.g., '/alice/utils/reverse:1.0')"
+                },
+                "filter": {
+                    "type": "string",
+                    "description": "Only run cases whose name contains this substring (optional)"
+                }
+            },
+            "required": ["path"]
+        })),
+        (ToolPath::bin("tcl_tool_coverage"), "Run a custom tool and report which lines of its script executed", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "tool_path": {
+                    "type": "string",
+                    "description": "Tool path to run with coverage instrumentation (e.g., '/alice/utils/reverse:1.0')"
+                },
+                "params": {
+                    "type": "object",
+                    "description": "Parameters to pass to the tool",
+                    "default": {}
+                }
+            },
+            "required": ["tool_path"]
+        })),
+        (ToolPath::bin("tcl_tool_compose"), "Run a named sequence of tools, interpolating earlier steps' bound output into later steps' params", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "steps": {
+                    "type": "array",
+                    "description": "Ordered steps to run; each runs through the normal tool-call path with its own parameter validation",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "tool_path": { "type": "string" },
+                            "params": {
+                                "type": "object",
+                                "description": "Parameters for this step; a string value containing '${name}' is replaced with the output bound to 'name' by an earlier step",
+                                "default": {}
+                            },
+                            "bind": {
+                                "type": "string",
+                                "description": "Name this step's output is bound to, for interpolation into a later step's params (optional)"
+                            }
+                        },
+                        "required": ["tool_path"]
                     }
-                    mcp_name => {
-                        // Try to execute as a custom tool
-                        tb.execute_custom_tool(mcp_name, params.arguments).await
+                }
+            },
+            "required": ["steps"]
+        })),
+        (ToolPath::bin("discover_tools"), "Discover and index tools from the filesystem", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {}
+        })),
+        (ToolPath::bin("exec_batch"), "Execute multiple tools concurrently on a bounded worker pool", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "entries": {
+                    "type": "array",
+                    "description": "Tools to invoke concurrently, in order",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "tool_path": { "type": "string" },
+                            "params": { "type": "object", "default": {} }
+                        },
+                        "required": ["tool_path"]
                     }
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Per-entry timeout in milliseconds (default 30000)",
+                    "default": 30000
                 }
-                })
-            }).join();
-            
-            match result {
-                Ok(Ok(text)) => Ok(json!(McpCallToolResult {
-                    content: vec![McpContent::Text { text }],
-                })),
-                Ok(Err(e)) => Err(jsonrpc_core::Error {
-                    code: jsonrpc_core::ErrorCode::InternalError,
-                    message: e.to_string(),
-                    data: None,
-                }),
-                Err(_) => Err(jsonrpc_core::Error {
-                    code: jsonrpc_core::ErrorCode::InternalError,
-                    message: "Thread panic".to_string(),
-                    data: None,
-                }),
-            }
+            },
+            "required": ["entries"]
+        })),
+        (ToolPath::bin("pipeline"), "Compose tools into a data pipeline, threading each stage's output into the next", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "input": {
+                    "type": "string",
+                    "description": "Initial input fed to the first stage (or split element-wise in separate mode)",
+                    "default": ""
+                },
+                "stages": {
+                    "type": "array",
+                    "description": "Ordered stages to run the input through",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "tool_path": { "type": "string" },
+                            "params": { "type": "object", "default": {} },
+                            "input_param": {
+                                "type": "string",
+                                "description": "Name of the parameter that receives the previous stage's output"
+                            }
+                        },
+                        "required": ["tool_path", "input_param"]
+                    }
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["buffer", "separate"],
+                    "description": "buffer threads the whole output through as one value; separate splits it into TCL list elements run independently",
+                    "default": "buffer"
+                },
+                "init": {
+                    "type": "string",
+                    "description": "Script run once, before the first stage, to set up shared state"
+                }
+            },
+            "required": ["stages"]
+        })),
+    ];
+
+    // Add privileged tools only if in privileged mode
+    if is_privileged {
+        system_tools.push((ToolPath::sbin("tcl_tool_add"), "Add a new TCL tool to the available tools (PRIVILEGED)", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "user": {
+                    "type": "string",
+                    "description": "User namespace"
+                },
+                "package": {
+                    "type": "string",
+                    "description": "Package name"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Name of the new tool"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Version of the tool (defaults to 'latest')",
+                    "default": "latest"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Description of what the tool does"
+                },
+                "script": {
+                    "type": "string",
+                    "description": "TCL script that implements the tool"
+                },
+                "parameters": {
+                    "type": "array",
+                    "description": "Parameters that the tool accepts",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "description": { "type": "string" },
+                            "required": { "type": "boolean" },
+                            "type_name": { "type": "string" },
+                            "default": { "description": "Value injected when this optional parameter is omitted" },
+                            "enum": { "type": "array", "description": "Allowed values; a provided argument outside this set is rejected" },
+                            "min": { "type": "number", "description": "Inclusive lower bound; a provided value that is not a number is rejected" },
+                            "max": { "type": "number", "description": "Inclusive upper bound; a provided value that is not a number is rejected" },
+                            "validate": { "type": "string", "description": "Regex a provided string value must match" }
+                        },
+                        "required": ["name", "description", "required", "type_name"]
+                    }
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "If a tool already exists at this path, replace it instead of failing (default false)",
+                    "default": false
+                },
+                "test_cases": {
+                    "type": "array",
+                    "description": "Test cases to attach, runnable later via tcl_tool_test",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "params": { "type": "object", "default": {} },
+                            "expect_output": { "type": "string", "description": "Expected to equal the tool's returned output exactly, if given" },
+                            "expect_error": { "type": "string", "description": "Expected to be a substring of the error message, if the call is expected to fail" }
+                        },
+                        "required": ["name"]
+                    }
+                }
+            },
+            "required": ["user", "package", "name", "description", "script"]
+        })));
+        system_tools.push((ToolPath::sbin("tcl_tool_remove"), "Remove a TCL tool from the available tools (PRIVILEGED)", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Full tool path (e.g., '/alice/utils/reverse_string:1.0')"
+                }
+            },
+            "required": ["path"]
+        })));
+        system_tools.push((ToolPath::sbin("tcl_tool_trust"), "Approve a filesystem-discovered tool at its current content hash (PRIVILEGED)", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Full tool path of a filesystem-discovered tool (e.g., '/alice/utils/reverse_string:1.0')"
+                }
+            },
+            "required": ["path"]
+        })));
+        system_tools.push((ToolPath::sbin("tcl_tool_revoke"), "Withdraw a tool's trust approval (PRIVILEGED)", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Full tool path whose trust approval should be withdrawn"
+                }
+            },
+            "required": ["path"]
+        })));
+    }
+
+    for (path, description, schema) in system_tools {
+        tools.push(McpToolInfo {
+            name: path.to_mcp_name(),
+            description: Some(format!("{} [{}]", description, path)),
+            input_schema: schema,
         });
-        
-        Self { tool_box, handler }
     }
-    
-    /// Initialize persistence for tool storage
-    pub async fn initialize_persistence(&self) -> Result<()> {
-        match self.tool_box.initialize_persistence().await {
-            Ok(message) => {
-                info!("{}", message);
-                Ok(())
+
+    // Add custom tools to the list
+    if let Ok(tool_defs) = tool_box.get_tool_definitions().await {
+        for tool_def in tool_defs {
+            // Build input schema for custom tool
+            let mut properties = serde_json::Map::new();
+            let mut required = Vec::new();
+
+            for param in &tool_def.parameters {
+                // Validate and normalize JSON Schema type
+                let json_type = match param.type_name.to_lowercase().as_str() {
+                    "string" | "str" | "text" => "string",
+                    "number" | "float" | "double" | "real" => "number",
+                    "integer" | "int" | "long" => "integer",
+                    "boolean" | "bool" => "boolean",
+                    "array" | "list" => "array",
+                    "object" | "dict" | "map" => "object",
+                    "null" | "nil" | "none" => "null",
+                    // Default to string for unknown types to maintain compatibility
+                    _ => "string"
+                };
+
+                let mut property = json!({
+                    "type": json_type,
+                    "description": param.description,
+                });
+                let property_obj = property.as_object_mut().expect("object literal");
+                if let Some(default) = &param.default {
+                    property_obj.insert("default".to_string(), typed_schema_value(&param.type_name, default));
+                }
+                if let Some(enum_values) = &param.enum_values {
+                    property_obj.insert("enum".to_string(), json!(enum_values.iter()
+                        .map(|v| typed_schema_value(&param.type_name, v))
+                        .collect::<Vec<_>>()));
+                }
+                if let Some(min) = param.min {
+                    property_obj.insert("minimum".to_string(), json!(min));
+                }
+                if let Some(max) = param.max {
+                    property_obj.insert("maximum".to_string(), json!(max));
+                }
+                if let Some(pattern) = &param.validate {
+                    property_obj.insert("pattern".to_string(), json!(pattern));
+                }
+
+                properties.insert(param.name.clone(), property);
+
+                if param.required {
+                    required.push(param.name.clone());
+                }
             }
-            Err(e) => {
-                tracing::warn!("Failed to initialize persistence: {}", e);
-                Err(e)
+
+            // Build the schema object, only including "required" if it's not empty
+            let mut schema_obj = serde_json::Map::new();
+            schema_obj.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+            schema_obj.insert("type".to_string(), json!("object"));
+            schema_obj.insert("properties".to_string(), json!(properties));
+
+            // Only add "required" array if there are required parameters
+            if !required.is_empty() {
+                schema_obj.insert("required".to_string(), json!(required));
             }
+
+            let input_schema = serde_json::Value::Object(schema_obj);
+
+            tools.push(McpToolInfo {
+                name: tool_def.path.to_mcp_name(),
+                description: Some(format!("{} [{}]", tool_def.description, tool_def.path)),
+                input_schema,
+            });
         }
     }
-    
-    pub async fn run_stdio(self) -> Result<()> {
-        info!("Starting TCL MCP server on stdio");
-        
-        let stdin = tokio::io::stdin();
-        let mut stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
-        let mut line = String::new();
-        
-        loop {
-            line.clear();
-            let n = reader.read_line(&mut line).await?;
-            if n == 0 {
-                break; // EOF
-            }
-            
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
+
+    tools
+}
+
+/// Minimal structural JSON Schema check: confirms `required` properties are present and that
+/// each property present in `properties` matches its declared `type`. This doesn't attempt full
+/// JSON Schema (nested `$ref`s, composition, formats) — just enough to catch the missing-field
+/// and wrong-type mistakes `tools/call` used to let through to the tool itself.
+fn validate_arguments_against_schema(schema: &Value, arguments: &Value) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
+        for field in required {
+            if let Some(name) = field.as_str() {
+                if arguments.get(name).is_none() {
+                    errors.push(format!("missing required property '{}'", name));
+                }
             }
-            
-            debug!("Received request: {}", trimmed);
-            
-            // Process the request
-            let response = self.handler.handle_request(trimmed).await;
-            
-            if let Some(response) = response {
-                debug!("Sending response: {}", response);
-                stdout.write_all(response.as_bytes()).await?;
-                stdout.write_all(b"\n").await?;
-                stdout.flush().await?;
+        }
+    }
+
+    if let Some(properties) = schema.get("properties").and_then(|v| v.as_object()) {
+        if let Some(provided) = arguments.as_object() {
+            for (name, value) in provided {
+                let Some(expected_type) = properties.get(name).and_then(|p| p.get("type")).and_then(|t| t.as_str()) else {
+                    continue;
+                };
+                if !json_value_matches_type(value, expected_type) {
+                    errors.push(format!("property '{}' should be of type '{}'", name, expected_type));
+                }
             }
         }
-        
-        Ok(())
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+fn json_value_matches_type(value: &Value, expected_type: &str) -> bool {
+    match expected_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
     }
 }
\ No newline at end of file
@@ -1,13 +1,155 @@
 use anyhow::{Result, anyhow};
-use molt::Interp;
-use tokio::sync::{mpsc, oneshot};
+use molt::{Interp, Value};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
-use crate::tcl_tools::{ToolDefinition, ParameterDefinition};
+use crate::tcl_tools::{ToolDefinition, ParameterDefinition, ToolTestCase, encode_test_cases, decode_test_cases, CoverageReport, PipelineStep, PipelineStepResult, PipelineExecutionResult};
 use crate::namespace::{ToolPath, Namespace};
-use crate::persistence::FilePersistence;
+use crate::persistence::{FilePersistence, ToolMetadata, ToolOrigin, UpsertOutcome};
 use crate::tool_discovery::{ToolDiscovery, DiscoveredTool};
+use crate::tool_watcher::{ChangeKind, ToolChange};
+use crate::tool_filter::ToolPathMatcher;
+use crate::tcl_runtime::RuntimeConfig;
+use crate::trust::TrustStore;
+use crate::version_resolver;
+
+/// Conservative floor used to turn an `eval_timeout` wall-clock limit into a Molt command-count
+/// budget. See `MoltRuntime::eval_bounded` in `tcl_runtime/molt_runtime.rs` for the same
+/// conversion applied to the `Evaluator` trait's interpreters; `TclExecutor` talks to `molt::Interp`
+/// directly rather than through that trait, so it duplicates the small calculation here.
+const COMMANDS_PER_MILLISECOND: u64 = 50_000;
+
+/// Default maximum depth of nested `call_tool` invocations reachable from a single top-level
+/// `Execute`/`ExecuteCustomTool`/`ExecTool` command before we abort, so a tool that calls itself
+/// (directly, or via a cycle through other tools) can't hang the interpreter thread. Overridable
+/// per executor via `RuntimeConfig::max_call_depth` (see `TclExecutor::spawn_with_runtime`).
+const MAX_CALL_DEPTH: usize = 16;
+
+/// Commands capable of touching the filesystem, spawning processes, or reaching the network.
+/// Shadowed with [`DisabledCommand`] on every non-privileged `TclExecutor`'s interpreter, turning
+/// `privileged` from a stored-but-unused flag into an actual restricted command surface.
+const UNSAFE_COMMANDS: &[&str] = &["exec", "file", "open", "socket", "source", "glob", "cd", "exit", "load", "pid"];
+
+/// Installed under each name in [`UNSAFE_COMMANDS`] on a non-privileged interpreter, replacing
+/// whatever Molt would otherwise provide (or providing a stub where Molt has none) so the error a
+/// script gets is a clear permission message rather than "command not found" or, worse, the real
+/// thing running.
+struct DisabledCommand {
+    name: &'static str,
+}
+
+impl molt::Command for DisabledCommand {
+    fn execute(&self, _interp: &mut Interp, _argv: &[Value]) -> molt::MoltResult {
+        Err(molt::Exception::molt_err(
+            format!("command '{}' not permitted in non-privileged mode", self.name).as_bytes(),
+        ))
+    }
+}
+
+/// Unsets every global variable currently set on `interp`, so a pooled `TclExecutor` worker
+/// starts each top-level script with a clean global namespace instead of leaking variables a
+/// prior, unrelated caller's script happened to `set`. Wrapped in `catch` so a variable that's
+/// somehow already gone (or a name `unset` balks at) can't fail the reset itself; best-effort
+/// cleanup is the point, not a strict guarantee.
+fn reset_global_vars(interp: &mut Interp) {
+    let _ = interp.eval("foreach __tcl_mcp_reset_var [info vars] { catch { unset $__tcl_mcp_reset_var } }");
+}
+
+/// Returns the name of the first `proc` in `script` that tries to redefine one of
+/// [`UNSAFE_COMMANDS`], so `execute_script` can reject the attempt up front instead of letting a
+/// redefined `proc` shadow the [`DisabledCommand`] stub. Checks the first two words of each line
+/// against the [`UNSAFE_COMMANDS`] denylist, a leading-word heuristic like
+/// `RoutingRuntime::leading_command` (see `tcl_runtime/routing_runtime.rs`) rather than a full
+/// parse — good enough to catch the straightforward case this guards against.
+fn find_unsafe_proc_redefinition(script: &str) -> Option<&'static str> {
+    for line in script.lines() {
+        let mut words = line.trim_start().split_whitespace();
+        if words.next() != Some("proc") {
+            continue;
+        }
+        if let Some(name) = words.next() {
+            if let Some(&unsafe_name) = UNSAFE_COMMANDS.iter().find(|&&u| u == name) {
+                return Some(unsafe_name);
+            }
+        }
+    }
+    None
+}
+
+/// The structured form of a failing script's diagnostics, built from the interpreter's
+/// [`molt::Exception`] instead of the flat `{:?}`-formatted string `execute_script` used to
+/// return. Serialized to JSON and carried inside the `anyhow::Error` message, so every existing
+/// `Result<String>` call site (and the MCP-facing methods built on top of them) keeps working
+/// unchanged, but a caller that parses the error message back out of it gets `errorInfo` (as
+/// `stack_trace`, one frame per line) and `errorCode` rather than just the top-level message.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ToolError {
+    pub message: String,
+    pub error_code: Option<String>,
+    pub stack_trace: Vec<String>,
+    pub tool_path: Option<String>,
+}
+
+impl ToolError {
+    /// Builds a `ToolError` from a failed `Interp::eval`. `tool_path` is the tool whose script
+    /// was running, if this came from `execute_custom_tool`/`exec_tool` rather than a bare
+    /// `tcl_execute` invocation.
+    fn from_exception(exception: &molt::Exception, tool_path: Option<&ToolPath>) -> Self {
+        let message = exception.value().to_string();
+        let stack_trace: Vec<String> = exception
+            .error_data()
+            .map(|data| {
+                data.error_info()
+                    .to_string()
+                    .lines()
+                    .map(|line| line.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let error_code = exception.error_data().map(|data| data.error_code().to_string()).filter(|code| code != "NONE");
+
+        Self {
+            message,
+            error_code,
+            stack_trace,
+            tool_path: tool_path.map(|p| p.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).unwrap_or_else(|_| self.message.clone()))
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+/// The effective capability set of a `TclExecutor`, queried via `TclCommand::GetCapabilities` so
+/// callers (and the MCP layer's `tcl/capabilities`) can advertise which commands and tools are
+/// actually runnable rather than assuming every build is privileged.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EffectiveCapabilities {
+    pub privileged: bool,
+    /// Commands shadowed by [`DisabledCommand`] in this executor; empty when `privileged`.
+    pub disabled_commands: Vec<String>,
+    /// This build's crate version (`CARGO_PKG_VERSION`), for clients comparing against a known-good
+    /// server version rather than just a protocol version.
+    pub server_version: String,
+    /// Namespaces a tool path can resolve into on this executor; `Sbin` only appears when `privileged`.
+    pub namespaces: Vec<Namespace>,
+    /// Optional capabilities a client can probe for before relying on them, e.g. whether tools
+    /// survive a restart (`"persistence"`) or `/sbin` is reachable at all (`"privileged_sbin"`).
+    /// Always includes `"filesystem_discovery"` and `"semver"`, which every executor supports.
+    pub features: Vec<String>,
+}
 
 pub enum TclCommand {
     Execute {
@@ -19,6 +161,11 @@ pub enum TclCommand {
         description: String,
         script: String,
         parameters: Vec<ParameterDefinition>,
+        /// When a tool already exists at `path`: `false` fails outright (the historical
+        /// behavior); `true` replaces it and bumps its receipt, but only if the script actually
+        /// changed (see `FilePersistence::upsert_tool`).
+        overwrite: bool,
+        test_cases: Vec<ToolTestCase>,
         response: oneshot::Sender<Result<String>>,
     },
     RemoveTool {
@@ -35,72 +182,739 @@ pub enum TclCommand {
         params: serde_json::Value,
         response: oneshot::Sender<Result<String>>,
     },
+    /// Backs `TclToolBox::tcl_tool_test`: looks up `path`'s attached test cases, narrowed by
+    /// `filter` (a plain substring of the case name, like `ListTools`'s filter). Returns only the
+    /// cases to run; actually running them happens in `TclToolBox`, which fans each one out
+    /// through `ExecuteCustomTool` concurrently via `tokio::spawn`.
+    TestTool {
+        path: ToolPath,
+        filter: Option<String>,
+        response: oneshot::Sender<Result<Vec<ToolTestCase>>>,
+    },
+    /// Backs `TclToolBox::tcl_tool_coverage`: runs `path` like `ExecuteCustomTool` does, but with
+    /// `instrument_for_coverage` injected into its script so the response also carries a
+    /// `CoverageReport` of which lines ran. A pre-execution failure (tool not found, bad
+    /// parameters) is a plain `Err`; a TCL error raised by the script itself is folded into the
+    /// returned output text instead, so the `CoverageReport` gathered up to that point isn't lost.
+    ExecuteCustomToolWithCoverage {
+        path: ToolPath,
+        params: serde_json::Value,
+        response: oneshot::Sender<Result<(String, CoverageReport)>>,
+    },
+    /// Backs `TclToolBox::tcl_tool_compose`: a declarative multi-step composition of other tools
+    /// (see `PipelineStep`), run in order through `execute_custom_tool` with later steps able to
+    /// reference earlier ones' bound outputs. Always responds `Ok`; a step-level failure is
+    /// represented inside `PipelineExecutionResult` (`failed_step`/`error`) rather than as an
+    /// `Err`, so `partial_results` from the steps that did run isn't discarded.
+    ExecutePipeline {
+        steps: Vec<PipelineStep>,
+        response: oneshot::Sender<Result<PipelineExecutionResult>>,
+    },
     GetToolDefinitions {
         response: oneshot::Sender<Vec<ToolDefinition>>,
     },
     InitializePersistence {
         response: oneshot::Sender<Result<String>>,
     },
+    /// Backs `HttpMcpServer::watch_tools`'s returned `ToolWatchGuard`: drops this worker's
+    /// `tool_changes` receiver, so externally-edited tool files stop being picked up. Nothing
+    /// restarts the watcher afterwards: `start_watching` only runs when persistence is first set
+    /// up, and neither `InitializePersistence` nor `add_tool` repeats that on a worker that already
+    /// has it. A no-op if this worker was never watching.
+    StopWatchingTools {
+        response: oneshot::Sender<Result<String>>,
+    },
     ExecTool {
         tool_path: String,
         params: serde_json::Value,
         response: oneshot::Sender<Result<String>>,
     },
     DiscoverTools {
+        /// Bypasses `ToolDiscovery`'s persisted mtime cache, re-reading every tool file.
+        force: bool,
+        response: oneshot::Sender<Result<String>>,
+    },
+    GetToolReceipt {
+        path: ToolPath,
+        response: oneshot::Sender<Result<Option<ToolMetadata>>>,
+    },
+    GetCapabilities {
+        response: oneshot::Sender<EffectiveCapabilities>,
+    },
+    SplitList {
+        value: String,
+        response: oneshot::Sender<Result<Vec<String>>>,
+    },
+    JoinList {
+        values: Vec<String>,
+        response: oneshot::Sender<Result<String>>,
+    },
+    TrustTool {
+        path: ToolPath,
+        response: oneshot::Sender<Result<String>>,
+    },
+    RevokeTool {
+        path: ToolPath,
         response: oneshot::Sender<Result<String>>,
     },
 }
 
+/// The hardcoded paths for tools that are always available, independent of what's been added or
+/// discovered. Shared by `TclExecutor::list_tools` and `call_tool`'s built-in fallback so the two
+/// don't drift apart.
+fn system_tool_paths() -> Vec<ToolPath> {
+    vec![
+        ToolPath::bin("tcl_execute"),
+        ToolPath::sbin("tcl_tool_add"),
+        ToolPath::sbin("tcl_tool_remove"),
+        ToolPath::bin("tcl_tool_list"),
+        ToolPath::bin("tcl_tool_receipt"),
+        ToolPath::bin("exec_tool"),
+        ToolPath::bin("tcl_tool_test"),
+        ToolPath::bin("tcl_tool_coverage"),
+        ToolPath::bin("tcl_tool_compose"),
+        ToolPath::bin("discover_tools"),
+        ToolPath::bin("pipeline"),
+        ToolPath::sbin("tcl_tool_trust"),
+        ToolPath::sbin("tcl_tool_revoke"),
+        ToolPath::docs("molt_book"),
+    ]
+}
+
+/// Builds the `set name value` preamble that binds `params` to TCL variables per `parameters`,
+/// in the declarative style tcllib's `parse_args`/argparse provides. Shared by every place that
+/// runs a tool script (`execute_custom_tool`, `exec_tool`, and the native `call_tool` command) so
+/// the quoting and validation rules stay in one place:
+/// - an argument not named in `parameters` is rejected outright;
+/// - a missing required parameter (with no `default`) is an error;
+/// - a missing optional parameter with a `default` gets that default injected;
+/// - a provided value is checked against the parameter's `enum_values`/`min`/`max`/`validate`
+///   before being bound, naming the offending parameter on failure.
+fn bind_params_script(parameters: &[ParameterDefinition], params: &serde_json::Value) -> Result<String> {
+    let provided = params.as_object();
+
+    if let Some(provided) = provided {
+        let known: std::collections::HashSet<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+        if let Some(unknown) = provided.keys().find(|name| !known.contains(name.as_str())) {
+            return Err(anyhow!("Unknown parameter: {}", unknown));
+        }
+    }
+
+    let mut script = String::new();
+    for param_def in parameters {
+        match provided.and_then(|p| p.get(&param_def.name)) {
+            Some(value) => {
+                validate_param_value(param_def, value)?;
+                script.push_str(&format!("set {} {}\n", param_def.name, format_tcl_value(value)));
+            }
+            None => {
+                if let Some(default) = &param_def.default {
+                    script.push_str(&format!("set {} {}\n", param_def.name, format_tcl_default(param_def, default)));
+                } else if param_def.required {
+                    return Err(anyhow!("Missing required parameter: {}", param_def.name));
+                }
+            }
+        }
+    }
+    Ok(script)
+}
+
+/// Rewrites a tool's script for `execute_custom_tool_with_coverage`, prefixing each of its own
+/// top-level statement lines with a call to `__cov_hit` recording that line's number, and returns
+/// the instrumented script alongside the number of such lines (`CoverageReport::total_lines`).
+/// "Top-level" is tracked with a crude running brace count rather than a real TCL parse: a line is
+/// only instrumented while no multi-line block (`if {...} {`, `proc ... {`, etc.) is still open, so
+/// the injected calls never land inside an open brace and corrupt the block they're part of. Blank
+/// lines and bare `#`-comments don't count either way. This is a heuristic, not a real TCL
+/// tokenizer — braces inside strings or comments can throw the count off — but it's adequate for
+/// the straight-line and simple-control-flow scripts tools are typically written as.
+fn instrument_for_coverage(script: &str) -> (String, usize) {
+    let mut instrumented = String::with_capacity(script.len() * 2);
+    let mut total_lines = 0usize;
+    let mut brace_depth: i32 = 0;
+
+    for (i, line) in script.lines().enumerate() {
+        let lineno = (i + 1) as u32;
+        let trimmed = line.trim_start();
+        let executable = brace_depth == 0 && !trimmed.is_empty() && !trimmed.starts_with('#');
+
+        if executable {
+            total_lines += 1;
+            instrumented.push_str(&format!("__cov_hit {}\n", lineno));
+        }
+        instrumented.push_str(line);
+        instrumented.push('\n');
+
+        brace_depth += line.matches('{').count() as i32;
+        brace_depth -= line.matches('}').count() as i32;
+    }
+
+    (instrumented, total_lines)
+}
+
+/// Substitutes `"${name}"` inside every string value of `params` (recursively, through objects and
+/// arrays) with `bindings[name]`, ahead of `execute_composition`'s normal `execute_custom_tool`
+/// call for the step. A `${name}` with no matching binding is left as-is rather than erroring here
+/// — the step's own `ParameterDefinition` validation will surface the problem (e.g. a required
+/// parameter left looking like a literal `"${typo}"`) with more context than this function has.
+fn interpolate_step_params(params: &serde_json::Value, bindings: &HashMap<String, String>) -> serde_json::Value {
+    match params {
+        serde_json::Value::String(s) => {
+            let mut result = s.clone();
+            for (name, value) in bindings {
+                result = result.replace(&format!("${{{}}}", name), value);
+            }
+            serde_json::Value::String(result)
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|v| interpolate_step_params(v, bindings)).collect())
+        }
+        serde_json::Value::Object(map) => {
+            serde_json::Value::Object(map.iter().map(|(k, v)| (k.clone(), interpolate_step_params(v, bindings))).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// Renders a provided argument as a TCL literal: a quoted, escaped string for JSON strings, or
+/// the value's plain `Display` form (e.g. `5`, `true`) otherwise.
+fn format_tcl_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => format!("\"{}\"", s.replace('"', "\\\"")),
+        _ => value.to_string(),
+    }
+}
+
+/// Renders a `ParameterDefinition`'s textual `default` as a TCL literal, quoting it unless the
+/// parameter's `type_name` is clearly numeric or boolean.
+fn format_tcl_default(param_def: &ParameterDefinition, default: &str) -> String {
+    match param_def.type_name.to_lowercase().as_str() {
+        "number" | "float" | "double" | "real" | "integer" | "int" | "long" | "boolean" | "bool" => default.to_string(),
+        _ => format!("\"{}\"", default.replace('"', "\\\"")),
+    }
+}
+
+/// Checks a provided argument against `param_def`'s `enum_values`/`min`/`max`/`validate`
+/// constraints, erroring with the offending parameter's name on the first violation.
+fn validate_param_value(param_def: &ParameterDefinition, value: &serde_json::Value) -> Result<()> {
+    if let Some(enum_values) = &param_def.enum_values {
+        let provided = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if !enum_values.iter().any(|allowed| allowed == &provided) {
+            return Err(anyhow!(
+                "Parameter '{}' must be one of [{}], got '{}'",
+                param_def.name, enum_values.join(", "), provided
+            ));
+        }
+    }
+
+    if param_def.min.is_some() || param_def.max.is_some() {
+        let number = value.as_f64().ok_or_else(|| anyhow!(
+            "Parameter '{}' must be numeric to enforce its min/max bounds", param_def.name
+        ))?;
+        if let Some(min) = param_def.min {
+            if number < min {
+                return Err(anyhow!("Parameter '{}' must be >= {}, got {}", param_def.name, min, number));
+            }
+        }
+        if let Some(max) = param_def.max {
+            if number > max {
+                return Err(anyhow!("Parameter '{}' must be <= {}, got {}", param_def.name, max, number));
+            }
+        }
+    }
+
+    if let Some(pattern) = &param_def.validate {
+        let text = value.as_str().ok_or_else(|| anyhow!(
+            "Parameter '{}' must be a string to enforce its 'validate' pattern", param_def.name
+        ))?;
+        let regex = regex::Regex::new(pattern)
+            .map_err(|e| anyhow!("Parameter '{}' has an invalid 'validate' pattern: {}", param_def.name, e))?;
+        if !regex.is_match(text) {
+            return Err(anyhow!(
+                "Parameter '{}' value '{}' does not match pattern '{}'", param_def.name, text, pattern
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves `path`'s version against the registry when it's a semver requirement (`^1.2`,
+/// `~1.0`, `>=1.0,<2.0`, or `latest`) rather than a version already registered under this exact
+/// path, picking the highest version registered under the same namespace/package/name that
+/// satisfies it (see `version_resolver`). Returns `path` unchanged when it's already an exact
+/// match, or when `path.version` doesn't parse as a requirement at all (a literal, non-semver
+/// tag like `"experimental"` can still be registered and looked up exactly — it just can't be
+/// resolved as a range). Errors, naming the versions actually installed, when `path.version`
+/// *is* a requirement but nothing registered satisfies it.
+fn resolve_tool_version(
+    custom_tools: &HashMap<ToolPath, ToolDefinition>,
+    discovered_tools: &HashMap<ToolPath, DiscoveredTool>,
+    path: &ToolPath,
+) -> Result<ToolPath> {
+    if custom_tools.contains_key(path) || discovered_tools.contains_key(path) {
+        return Ok(path.clone());
+    }
+
+    let Ok(spec) = version_resolver::parse(&path.version) else {
+        return Ok(path.clone());
+    };
+
+    let candidates: Vec<&ToolPath> = custom_tools.keys().chain(discovered_tools.keys())
+        .filter(|candidate| {
+            candidate.namespace == path.namespace && candidate.package == path.package && candidate.name == path.name
+        })
+        .collect();
+
+    match version_resolver::resolve(candidates.iter().copied(), &spec) {
+        Some(resolved) => Ok(resolved.clone()),
+        None => {
+            let mut installed: Vec<&str> = candidates.iter().map(|c| c.version.as_str()).collect();
+            installed.sort_unstable();
+            Err(anyhow!(
+                "no installed version of '{}' satisfies '{}' (installed: {})",
+                path,
+                path.version,
+                if installed.is_empty() { "none".to_string() } else { installed.join(", ") }
+            ))
+        }
+    }
+}
+
+/// Resolves `path` to a runnable script via the same custom → discovered precedence `exec_tool`
+/// uses, returning `Ok(None)` if neither registry has it (the caller then falls back to built-ins).
+fn resolve_tool_script(
+    custom_tools: &HashMap<ToolPath, ToolDefinition>,
+    discovered_tools: &HashMap<ToolPath, DiscoveredTool>,
+    path: &ToolPath,
+    params: &serde_json::Value,
+) -> Result<Option<String>> {
+    if let Some(tool) = custom_tools.get(path) {
+        let mut script = bind_params_script(&tool.parameters, params)?;
+        script.push_str(&tool.script);
+        return Ok(Some(script));
+    }
+
+    if let Some(tool) = discovered_tools.get(path) {
+        let content = std::fs::read_to_string(&tool.file_path)
+            .map_err(|e| anyhow!("Failed to read tool file for '{}': {}", path, e))?;
+        let mut script = bind_params_script(&tool.parameters, params)?;
+        script.push_str(&content);
+        return Ok(Some(script));
+    }
+
+    Ok(None)
+}
+
+/// Lists every tool path known across the system, custom, and discovered registries, filtered by
+/// `namespace`/`filter` the same way `TclExecutor::list_tools` does. Free function so the native
+/// `call_tool` command's `/bin/tcl_tool_list` fallback can call it without a `&TclExecutor`.
+fn list_tool_paths(
+    custom_tools: &HashMap<ToolPath, ToolDefinition>,
+    discovered_tools: &HashMap<ToolPath, DiscoveredTool>,
+    namespace: Option<String>,
+    filter: Option<String>,
+) -> Vec<String> {
+    let mut tools = Vec::new();
+
+    for tool in system_tool_paths() {
+        if let Some(ref ns) = namespace {
+            let matches = match (&tool.namespace, ns.as_str()) {
+                (Namespace::Bin, "bin") => true,
+                (Namespace::Sbin, "sbin") => true,
+                (Namespace::User(user_ns), filter_ns) if user_ns == filter_ns => true,
+                _ => false,
+            };
+            if !matches {
+                continue;
+            }
+        }
+
+        let path_str = tool.to_string();
+        if filter.as_ref().map(|f| path_str.contains(f)).unwrap_or(true) {
+            tools.push(path_str);
+        }
+    }
+
+    for path in custom_tools.keys() {
+        if let Some(ref ns) = namespace {
+            let matches = match (&path.namespace, ns.as_str()) {
+                (Namespace::User(user_ns), filter_ns) if user_ns == filter_ns => true,
+                _ => false,
+            };
+            if !matches {
+                continue;
+            }
+        }
+
+        let path_str = path.to_string();
+        if filter.as_ref().map(|f| path_str.contains(f)).unwrap_or(true) {
+            tools.push(path_str);
+        }
+    }
+
+    for path in discovered_tools.keys() {
+        if let Some(ref ns) = namespace {
+            let matches = match (&path.namespace, ns.as_str()) {
+                (Namespace::Bin, "bin") => true,
+                (Namespace::Sbin, "sbin") => true,
+                (Namespace::Docs, "docs") => true,
+                (Namespace::User(user_ns), filter_ns) if user_ns == filter_ns => true,
+                _ => false,
+            };
+            if !matches {
+                continue;
+            }
+        }
+
+        let path_str = path.to_string();
+        if filter.as_ref().map(|f| path_str.contains(f)).unwrap_or(true) {
+            tools.push(path_str);
+        }
+    }
+
+    tools.sort();
+    tools
+}
+
+/// Native Molt command backing `call_tool /path/to/tool {json-params}`, registered on
+/// `TclExecutor`'s interpreter so a tool script can invoke another tool mid-script. Holds `Rc`
+/// clones of the same registries `TclExecutor` owns (see `TclExecutor::new`) rather than a
+/// reference to the executor itself, since Molt only ever hands a command `&mut Interp` — the
+/// callee's script is evaluated directly on that same interpreter, which is what makes nesting
+/// work without needing to reach back into `TclExecutor::execute_script`.
+struct CallToolCommand {
+    custom_tools: Rc<RefCell<HashMap<ToolPath, ToolDefinition>>>,
+    discovered_tools: Rc<RefCell<HashMap<ToolPath, DiscoveredTool>>>,
+    call_stack: Rc<RefCell<Vec<ToolPath>>>,
+    /// Shared with `TclExecutor::max_call_depth` so `RuntimeConfig::max_call_depth` can override
+    /// it after construction, the same way `eval_timeout` is applied in `spawn_internal`.
+    max_call_depth: Rc<Cell<usize>>,
+}
+
+impl CallToolCommand {
+    fn run(&self, interp: &mut Interp, path: ToolPath, params: serde_json::Value) -> Result<String> {
+        {
+            let stack = self.call_stack.borrow();
+            if stack.contains(&path) {
+                return Err(anyhow!(
+                    "call_tool: '{}' is already on the call stack ({} -> {}); refusing a recursive or cyclic call",
+                    path,
+                    stack.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(" -> "),
+                    path
+                ));
+            }
+            let max_call_depth = self.max_call_depth.get();
+            if stack.len() >= max_call_depth {
+                return Err(anyhow!(
+                    "call_tool: exceeded maximum call depth of {} while calling '{}'",
+                    max_call_depth,
+                    path
+                ));
+            }
+        }
+
+        let script = {
+            let custom = self.custom_tools.borrow();
+            let discovered = self.discovered_tools.borrow();
+            resolve_tool_script(&custom, &discovered, &path, &params)?
+        };
+
+        let script = match script {
+            Some(script) => script,
+            None => match path.to_string().as_str() {
+                "/bin/tcl_execute" => params
+                    .get("script")
+                    .and_then(|s| s.as_str())
+                    .map(String::from)
+                    .ok_or_else(|| anyhow!("Missing required parameter: script"))?,
+                "/bin/tcl_tool_list" => {
+                    let namespace = params.get("namespace").and_then(|s| s.as_str()).map(String::from);
+                    let filter = params.get("filter").and_then(|s| s.as_str()).map(String::from);
+                    let tools = list_tool_paths(
+                        &self.custom_tools.borrow(),
+                        &self.discovered_tools.borrow(),
+                        namespace,
+                        filter,
+                    );
+                    return Ok(tools.join("\n"));
+                }
+                _ => return Err(anyhow!("Tool '{}' not found", path)),
+            },
+        };
+
+        self.call_stack.borrow_mut().push(path.clone());
+        let result = interp.eval(&script);
+        self.call_stack.borrow_mut().pop();
+
+        match result {
+            Ok(value) => Ok(value.to_string()),
+            Err(error) => Err(ToolError::from_exception(&error, Some(&path)).into()),
+        }
+    }
+}
+
+/// Registered as `__cov_hit` (only while `execute_custom_tool_with_coverage` is running) and
+/// called once per instrumented line by `instrument_for_coverage`'s injected script; just records
+/// the line number it's passed into the shared set `execute_custom_tool_with_coverage` reads back
+/// afterward.
+struct CoverageHitCommand {
+    hits: Rc<RefCell<std::collections::BTreeSet<u32>>>,
+}
+
+impl molt::Command for CoverageHitCommand {
+    fn execute(&self, _interp: &mut Interp, argv: &[Value]) -> molt::MoltResult {
+        molt::check_args(1, argv, 2, 2, "line")?;
+
+        let line: u32 = argv[1].to_string().parse()
+            .map_err(|_| molt::Exception::molt_err(format!("__cov_hit: invalid line number '{}'", argv[1]).as_bytes()))?;
+        self.hits.borrow_mut().insert(line);
+
+        Ok(Value::from(""))
+    }
+}
+
+impl molt::Command for CallToolCommand {
+    fn execute(&self, interp: &mut Interp, argv: &[Value]) -> molt::MoltResult {
+        molt::check_args(1, argv, 2, 3, "path ?params?")?;
+
+        let path_str = argv[1].to_string();
+        let path = ToolPath::parse(&path_str)
+            .map_err(|e| molt::Exception::molt_err(format!("call_tool: invalid tool path '{}': {}", path_str, e).as_bytes()))?;
+
+        let params: serde_json::Value = if argv.len() == 3 {
+            serde_json::from_str(&argv[2].to_string())
+                .map_err(|e| molt::Exception::molt_err(format!("call_tool: invalid params '{}': {}", argv[2], e).as_bytes()))?
+        } else {
+            serde_json::Value::Object(Default::default())
+        };
+
+        self.run(interp, path, params)
+            .map(Value::from)
+            .map_err(|e| molt::Exception::molt_err(e.to_string().as_bytes()))
+    }
+}
+
 pub struct TclExecutor {
     interp: Interp,
-    custom_tools: HashMap<ToolPath, ToolDefinition>,
-    discovered_tools: HashMap<ToolPath, DiscoveredTool>,
+    custom_tools: Rc<RefCell<HashMap<ToolPath, ToolDefinition>>>,
+    discovered_tools: Rc<RefCell<HashMap<ToolPath, DiscoveredTool>>>,
+    /// Tool paths currently being executed via `call_tool`, innermost last. Reset at the top of
+    /// every top-level entry point (`execute_script`, `execute_custom_tool`, `exec_tool`) and
+    /// pushed/popped by `CallToolCommand::run` as it recurses, so depth and cycles are tracked
+    /// across an entire chain of `call_tool` invocations rather than per call.
+    call_stack: Rc<RefCell<Vec<ToolPath>>>,
     tool_discovery: ToolDiscovery,
     persistence: Option<FilePersistence>,
+    /// Debounced out-of-band storage-directory changes, started the moment `persistence` is
+    /// first initialized (see `start_watching`). `None` before then, or if starting the watcher
+    /// failed — in which case custom tools simply require a restart to pick up external edits,
+    /// same as before this field existed.
+    tool_changes: Option<broadcast::Receiver<ToolChange>>,
+    /// Wall-clock budget applied to every `execute_script` call, converted to a Molt command-count
+    /// budget (see `COMMANDS_PER_MILLISECOND`). `None` means unbounded. Set via
+    /// `TclExecutor::spawn_with_runtime`'s `RuntimeConfig::eval_timeout`.
+    eval_timeout: Option<Duration>,
+    /// Whether this executor's interpreter runs with the full command set. `false` shadows
+    /// every name in `UNSAFE_COMMANDS` with a `DisabledCommand` (see `new`) and refuses to run
+    /// discovered tools marked `requires_privileged`.
+    privileged: bool,
+    /// Shared with the registered `call_tool` command (see `new`); defaults to `MAX_CALL_DEPTH`
+    /// and overridden via `RuntimeConfig::max_call_depth` in `spawn_internal`.
+    max_call_depth: Rc<Cell<usize>>,
+    /// Gate consulted by `exec_tool` before running a filesystem-discovered tool; see
+    /// `crate::trust`. Starts empty and is lazily populated from disk by
+    /// `ensure_trust_store_loaded` the first time it's needed, the same way `persistence` is
+    /// lazily initialized by `add_tool`.
+    trust_store: TrustStore,
+    trust_store_loaded: bool,
+    /// Shared with the registered `__cov_hit` command (see `new`); cleared at the top of every
+    /// `execute_custom_tool_with_coverage` call and read back once the script returns.
+    coverage_hits: Rc<RefCell<std::collections::BTreeSet<u32>>>,
 }
 
 impl TclExecutor {
     pub fn new(privileged: bool) -> Self {
-        let interp = Interp::new();
-        
-        // In non-privileged mode, we could disable certain commands here
-        // For now, we'll just store the flag and use it during execution
+        let mut interp = Interp::new();
+
         if !privileged {
-            // TODO: Consider filtering dangerous commands like 'exec', 'file', etc.
-            // For now, we rely on Molt's default safety features
+            for &name in UNSAFE_COMMANDS {
+                interp.add_command_object(name, Rc::new(DisabledCommand { name }));
+            }
         }
-        
+
+        let custom_tools = Rc::new(RefCell::new(HashMap::new()));
+        let discovered_tools = Rc::new(RefCell::new(HashMap::new()));
+        let call_stack = Rc::new(RefCell::new(Vec::new()));
+        let max_call_depth = Rc::new(Cell::new(MAX_CALL_DEPTH));
+
+        interp.add_command_object(
+            "call_tool",
+            Rc::new(CallToolCommand {
+                custom_tools: Rc::clone(&custom_tools),
+                discovered_tools: Rc::clone(&discovered_tools),
+                call_stack: Rc::clone(&call_stack),
+                max_call_depth: Rc::clone(&max_call_depth),
+            }),
+        );
+
+        let coverage_hits = Rc::new(RefCell::new(std::collections::BTreeSet::new()));
+        interp.add_command_object(
+            "__cov_hit",
+            Rc::new(CoverageHitCommand { hits: Rc::clone(&coverage_hits) }),
+        );
+
         Self {
             interp,
-            custom_tools: HashMap::new(),
-            discovered_tools: HashMap::new(),
+            custom_tools,
+            discovered_tools,
+            call_stack,
             tool_discovery: ToolDiscovery::new(),
             persistence: None,
+            tool_changes: None,
+            eval_timeout: None,
+            privileged,
+            max_call_depth,
+            trust_store: TrustStore::new(),
+            trust_store_loaded: false,
+            coverage_hits,
+        }
+    }
+
+    /// Starts watching `persistence`'s storage directory, if not already doing so. Called right
+    /// after `persistence` is set, from both `add_tool`'s lazy-init branch and
+    /// `initialize_persistence`, so hot-reload comes up however persistence itself did.
+    fn start_watching(&mut self) {
+        if self.tool_changes.is_some() {
+            return;
+        }
+
+        let Some(ref persistence) = self.persistence else { return };
+        match persistence.watch() {
+            Ok(rx) => self.tool_changes = Some(rx),
+            Err(e) => tracing::warn!("Failed to start tool storage watcher: {}", e),
+        }
+    }
+
+    /// Applies a debounced [`ToolChange`] from `tool_changes` to `custom_tools`, mirroring
+    /// `add_tool`/`remove_tool`'s in-memory bookkeeping. Only user-namespace tools are tracked in
+    /// `custom_tools` (system tools are hardcoded), so changes to anything else are ignored. A
+    /// reloaded script is `validate_script`-checked before it replaces the live one: a bad edit
+    /// (e.g. an unbalanced brace from a half-written save) is rejected and logged, and the
+    /// previous version keeps serving calls rather than being torn out.
+    async fn apply_tool_change(&mut self, change: ToolChange) {
+        if !matches!(change.path.namespace, Namespace::User(_)) {
+            return;
+        }
+
+        match change.kind {
+            ChangeKind::Created | ChangeKind::Modified => {
+                let loaded = match self.persistence {
+                    Some(ref persistence) => persistence.load_tool(&change.path).await,
+                    None => return,
+                };
+                match loaded {
+                    Ok(Some(tool)) => {
+                        if let Err(e) = self.validate_script(&tool.script) {
+                            tracing::warn!(
+                                "Rejected reloaded tool '{}': script does not validate ({}); keeping previous version",
+                                change.path, e
+                            );
+                            return;
+                        }
+                        tracing::info!("Reloaded externally-changed tool '{}'", change.path);
+                        self.custom_tools.borrow_mut().insert(change.path, tool);
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to reload changed tool '{}': {}", change.path, e),
+                }
+            }
+            ChangeKind::Deleted => {
+                if self.custom_tools.borrow_mut().remove(&change.path).is_some() {
+                    tracing::info!("Removed externally-deleted tool '{}'", change.path);
+                }
+            }
         }
     }
-    
+
+    /// Best-effort syntax check for a hot-reloaded tool script: defining it as a scratch proc
+    /// makes Molt's own parser reject unbalanced braces/brackets the same way `proc` would for a
+    /// tool added directly through `add_tool`, without running any of the script's side effects.
+    /// Deliberately not a full correctness check — a script that calls an undefined command still
+    /// "validates" here and only fails at call time, same as a tool added via `add_tool` always has.
+    fn validate_script(&mut self, script: &str) -> Result<()> {
+        const PROBE_NAME: &str = "__hot_reload_validate__";
+        let result = self.interp.eval(&format!("proc {} {{}} {{{}}}", PROBE_NAME, script));
+        let _ = self.interp.eval(&format!("catch {{rename {} {{}}}}", PROBE_NAME));
+        result.map(|_| ()).map_err(|e| anyhow!(e.value().to_string()))
+    }
+
     pub fn spawn(privileged: bool) -> mpsc::Sender<TclCommand> {
+        Self::spawn_internal(privileged, None, None)
+    }
+
+    /// Like `spawn`, but also applies `runtime_config.eval_timeout` and
+    /// `runtime_config.max_call_depth` to every script evaluation this executor runs, so a
+    /// runaway `tools/call` can't hang its worker thread indefinitely, and a cyclic `call_tool`
+    /// chain can't either.
+    pub fn spawn_with_runtime(privileged: bool, runtime_config: RuntimeConfig) -> Result<mpsc::Sender<TclCommand>, String> {
+        Ok(Self::spawn_internal(privileged, runtime_config.eval_timeout, runtime_config.max_call_depth))
+    }
+
+    fn spawn_internal(privileged: bool, eval_timeout: Option<Duration>, max_call_depth: Option<usize>) -> mpsc::Sender<TclCommand> {
         let (tx, mut rx) = mpsc::channel::<TclCommand>(100);
-        
+
         // Spawn a dedicated thread for the TCL interpreter
         thread::spawn(move || {
             let mut executor = TclExecutor::new(privileged);
-            
+            executor.eval_timeout = eval_timeout;
+            if let Some(max_call_depth) = max_call_depth {
+                executor.max_call_depth.set(max_call_depth);
+            }
+
             // Create a single-threaded runtime for this thread
             let runtime = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()
                 .expect("Failed to create Tokio runtime");
-                
+
             runtime.block_on(async move {
-                while let Some(cmd) = rx.recv().await {
+                loop {
+                    // `tool_changes` only exists once persistence has been lazily initialized
+                    // (see `start_watching`), so it's selected on conditionally rather than
+                    // unconditionally awaited alongside `rx`.
+                    let cmd = if let Some(ref mut changes) = executor.tool_changes {
+                        tokio::select! {
+                            cmd = rx.recv() => cmd,
+                            change = changes.recv() => {
+                                match change {
+                                    Ok(change) => executor.apply_tool_change(change).await,
+                                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                                        tracing::warn!("Tool storage watcher lagged, some changes were dropped");
+                                    }
+                                    Err(broadcast::error::RecvError::Closed) => {
+                                        executor.tool_changes = None;
+                                    }
+                                }
+                                continue;
+                            }
+                        }
+                    } else {
+                        rx.recv().await
+                    };
+
+                    let Some(cmd) = cmd else { break };
+
                     match cmd {
                         TclCommand::Execute { script, response } => {
-                            let result = executor.execute_script(&script);
+                            let result = executor.execute_script(&script, None);
                             let _ = response.send(result);
                         }
-                        TclCommand::AddTool { path, description, script, parameters, response } => {
-                            let result = executor.add_tool(path, description, script, parameters).await;
+                        TclCommand::AddTool { path, description, script, parameters, overwrite, test_cases, response } => {
+                            let result = executor.add_tool(path, description, script, parameters, overwrite, test_cases).await;
                             let _ = response.send(result);
                         }
                         TclCommand::RemoveTool { path, response } => {
@@ -115,6 +929,18 @@ impl TclExecutor {
                             let result = executor.execute_custom_tool(&path, params);
                             let _ = response.send(result);
                         }
+                        TclCommand::TestTool { path, filter, response } => {
+                            let result = executor.test_tool_cases(&path, filter);
+                            let _ = response.send(result);
+                        }
+                        TclCommand::ExecuteCustomToolWithCoverage { path, params, response } => {
+                            let result = executor.execute_custom_tool_with_coverage(&path, params);
+                            let _ = response.send(result);
+                        }
+                        TclCommand::ExecutePipeline { steps, response } => {
+                            let result = executor.execute_composition(steps);
+                            let _ = response.send(Ok(result));
+                        }
                         TclCommand::GetToolDefinitions { response } => {
                             let tools = executor.get_tool_definitions();
                             let _ = response.send(tools);
@@ -123,354 +949,477 @@ impl TclExecutor {
                             let result = executor.initialize_persistence().await;
                             let _ = response.send(result);
                         }
+                        TclCommand::StopWatchingTools { response } => {
+                            let was_watching = executor.tool_changes.take().is_some();
+                            let _ = response.send(Ok(if was_watching {
+                                "Stopped watching tool storage directory".to_string()
+                            } else {
+                                "Was not watching tool storage directory".to_string()
+                            }));
+                        }
                         TclCommand::ExecTool { tool_path, params, response } => {
                             let result = executor.exec_tool(&tool_path, params).await;
                             let _ = response.send(result);
                         }
-                        TclCommand::DiscoverTools { response } => {
-                            let result = executor.discover_tools().await;
+                        TclCommand::DiscoverTools { force, response } => {
+                            let result = executor.discover_tools(force).await;
+                            let _ = response.send(result);
+                        }
+                        TclCommand::GetToolReceipt { path, response } => {
+                            let result = executor.get_tool_receipt(&path).await;
+                            let _ = response.send(result);
+                        }
+                        TclCommand::GetCapabilities { response } => {
+                            let _ = response.send(executor.capabilities());
+                        }
+                        TclCommand::SplitList { value, response } => {
+                            let result = executor.split_list(&value);
+                            let _ = response.send(result);
+                        }
+                        TclCommand::JoinList { values, response } => {
+                            let result = executor.join_list(&values);
+                            let _ = response.send(result);
+                        }
+                        TclCommand::TrustTool { path, response } => {
+                            let result = executor.trust_tool(&path).await;
+                            let _ = response.send(result);
+                        }
+                        TclCommand::RevokeTool { path, response } => {
+                            let result = executor.revoke_tool(&path).await;
                             let _ = response.send(result);
                         }
                     }
                 }
             });
         });
-        
+
         tx
     }
-    
-    fn execute_script(&mut self, script: &str) -> Result<String> {
-        match self.interp.eval(script) {
+
+    /// Top-level script evaluation (backs `TclCommand::Execute`). Resets the `call_tool` call
+    /// stack and any global variables left behind by whatever script this worker last ran, since
+    /// this is an entry point rather than a nested call — a pooled worker round-robins across
+    /// unrelated top-level requests (see `TclExecutorPool`), so leftover state from one caller's
+    /// script must not leak into the next caller's. `tool_path` is attached to the resulting
+    /// `ToolError` on failure; pass `None` for a bare `tcl_execute` call with no tool behind it.
+    fn execute_script(&mut self, script: &str, tool_path: Option<&ToolPath>) -> Result<String> {
+        self.call_stack.borrow_mut().clear();
+        reset_global_vars(&mut self.interp);
+
+        if !self.privileged {
+            if let Some(name) = find_unsafe_proc_redefinition(script) {
+                return Err(anyhow!("command '{}' not permitted in non-privileged mode", name));
+            }
+        }
+
+        if let Some(limit) = self.eval_timeout {
+            let budget = (limit.as_millis() as u64)
+                .saturating_mul(COMMANDS_PER_MILLISECOND)
+                .max(1);
+            self.interp.set_command_limit(Some(budget));
+        }
+
+        let result = self.interp.eval(script);
+
+        if self.eval_timeout.is_some() {
+            self.interp.set_command_limit(None);
+        }
+
+        match result {
             Ok(value) => Ok(value.to_string()),
-            Err(error) => Err(anyhow!("TCL execution error: {:?}", error)),
+            Err(error) => Err(ToolError::from_exception(&error, tool_path).into()),
         }
     }
-    
-    async fn add_tool(&mut self, path: ToolPath, description: String, script: String, parameters: Vec<ParameterDefinition>) -> Result<String> {
+
+    async fn add_tool(&mut self, path: ToolPath, description: String, script: String, parameters: Vec<ParameterDefinition>, overwrite: bool, test_cases: Vec<ToolTestCase>) -> Result<String> {
         // Only allow adding tools to user namespace
         if !matches!(path.namespace, Namespace::User(_)) {
             return Err(anyhow!("Can only add tools to user namespace, not {}", path));
         }
-        
-        if self.custom_tools.contains_key(&path) {
+
+        if self.custom_tools.borrow().contains_key(&path) && !overwrite {
             return Err(anyhow!("Tool '{}' already exists", path));
         }
-        
+
         // Initialize persistence if not already initialized
         if self.persistence.is_none() {
             match FilePersistence::new().await {
                 Ok(persistence) => {
                     // Load existing tools from storage
-                    match persistence.list_tools(None).await {
+                    match persistence.list_tools(&ToolPathMatcher::all()).await {
                         Ok(stored_tools) => {
+                            let mut custom_tools = self.custom_tools.borrow_mut();
                             for tool in stored_tools {
                                 if matches!(tool.path.namespace, Namespace::User(_)) {
-                                    self.custom_tools.insert(tool.path.clone(), tool);
+                                    custom_tools.insert(tool.path.clone(), tool);
                                 }
                             }
-                            tracing::info!("Initialized persistence and loaded {} existing tools", self.custom_tools.len());
+                            tracing::info!("Initialized persistence and loaded {} existing tools", custom_tools.len());
                         }
                         Err(e) => {
                             tracing::warn!("Failed to load existing tools: {}", e);
                         }
                     }
                     self.persistence = Some(persistence);
+                    self.start_watching();
                 }
                 Err(e) => {
                     tracing::warn!("Failed to initialize persistence: {}", e);
                 }
             }
         }
-        
+
         let tool_def = ToolDefinition {
             path: path.clone(),
             description,
             script,
             parameters,
+            test_cases: encode_test_cases(&test_cases),
         };
-        
+
         // Save to persistence if available
-        let persisted = if let Some(ref mut persistence) = self.persistence {
-            match persistence.save_tool(&tool_def).await {
-                Ok(_) => true,
+        let outcome = if let Some(ref mut persistence) = self.persistence {
+            match persistence.upsert_tool(&tool_def, ToolOrigin::UserAdded, overwrite).await {
+                Ok(outcome) => Some(outcome),
                 Err(e) => {
                     tracing::warn!("Failed to persist tool: {}", e);
-                    false
+                    None
                 }
             }
         } else {
-            false
+            None
         };
-        
+
+        if outcome == Some(UpsertOutcome::Unchanged) {
+            return Ok(format!("Tool '{}' unchanged (script identical to stored version)", path));
+        }
+
         // Add to in-memory cache
-        self.custom_tools.insert(path.clone(), tool_def);
-        
-        if persisted {
-            Ok(format!("Tool '{}' added successfully and persisted", path))
-        } else {
-            Ok(format!("Tool '{}' added to memory (persistence unavailable)", path))
+        self.custom_tools.borrow_mut().insert(path.clone(), tool_def);
+
+        match outcome {
+            Some(UpsertOutcome::Created) => Ok(format!("Tool '{}' added successfully and persisted", path)),
+            Some(UpsertOutcome::Upgraded) => Ok(format!("Tool '{}' overwritten and persisted", path)),
+            Some(UpsertOutcome::Unchanged) => unreachable!(),
+            None => Ok(format!("Tool '{}' added to memory (persistence unavailable)", path)),
         }
     }
-    
+
+    /// Backs `TclCommand::GetToolReceipt`: returns the persisted metadata for `path` (creation
+    /// and update timestamps, checksum, origin, schema version) without loading the script
+    /// itself. `None` if the tool was never persisted (e.g. in-memory only because persistence
+    /// was unavailable when it was added).
+    async fn get_tool_receipt(&self, path: &ToolPath) -> Result<Option<ToolMetadata>> {
+        match self.persistence {
+            Some(ref persistence) => persistence.get_receipt(path).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Loads `trust_store` from disk the first time it's needed, mirroring `add_tool`'s lazy
+    /// persistence init. A failure to determine the trust store's path just leaves it empty
+    /// in memory for this process — every `users/*` tool stays gated rather than the executor
+    /// refusing to start.
+    async fn ensure_trust_store_loaded(&mut self) {
+        if self.trust_store_loaded {
+            return;
+        }
+        self.trust_store_loaded = true;
+
+        match crate::trust::default_trust_store_path() {
+            Ok(path) => self.trust_store = TrustStore::load(&path).await,
+            Err(e) => tracing::warn!("Failed to locate trust store, starting from no approvals: {}", e),
+        }
+    }
+
+    /// Backs `TclCommand::TrustTool`: approves `path` at its currently-discovered content hash.
+    /// Errors if `path` isn't a filesystem-discovered tool (custom tools added via
+    /// `tcl_tool_add` aren't gated by the trust store at all).
+    async fn trust_tool(&mut self, path: &ToolPath) -> Result<String> {
+        self.ensure_trust_store_loaded().await;
+
+        let tool = self.discovered_tools.borrow().get(path).cloned()
+            .ok_or_else(|| anyhow!("'{}' is not a filesystem-discovered tool", path))?;
+
+        self.trust_store.trust(&tool);
+        if let Ok(store_path) = crate::trust::default_trust_store_path() {
+            self.trust_store.save(&store_path).await;
+        }
+
+        Ok(format!("Tool '{}' trusted at its current content hash", path))
+    }
+
+    /// Backs `TclCommand::RevokeTool`: withdraws `path`'s trust approval, if any.
+    async fn revoke_tool(&mut self, path: &ToolPath) -> Result<String> {
+        self.ensure_trust_store_loaded().await;
+
+        self.trust_store.revoke(path)?;
+        if let Ok(store_path) = crate::trust::default_trust_store_path() {
+            self.trust_store.save(&store_path).await;
+        }
+
+        Ok(format!("Trust approval for '{}' revoked", path))
+    }
+
+    /// Backs `TclCommand::GetCapabilities`.
+    fn capabilities(&self) -> EffectiveCapabilities {
+        let mut namespaces = vec![Namespace::Bin, Namespace::Docs];
+        if self.privileged {
+            namespaces.push(Namespace::Sbin);
+        }
+
+        let mut features = vec!["filesystem_discovery".to_string(), "semver".to_string()];
+        if self.persistence.is_some() {
+            features.push("persistence".to_string());
+        }
+        if self.privileged {
+            features.push("privileged_sbin".to_string());
+        }
+
+        EffectiveCapabilities {
+            privileged: self.privileged,
+            disabled_commands: if self.privileged {
+                Vec::new()
+            } else {
+                UNSAFE_COMMANDS.iter().map(|s| s.to_string()).collect()
+            },
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            namespaces,
+            features,
+        }
+    }
+
     async fn remove_tool(&mut self, path: &ToolPath) -> Result<String> {
         // Cannot remove system tools
         if path.is_system() {
             return Err(anyhow!("Cannot remove system tool '{}'", path));
         }
-        
+
         // Remove from in-memory cache first
-        let removed_from_memory = self.custom_tools.remove(path).is_some();
-        
+        let removed_from_memory = self.custom_tools.borrow_mut().remove(path).is_some();
+
         // Remove from persistent storage
         let removed_from_storage = self.remove_tool_from_storage(path).await?;
-        
+
         if removed_from_memory || removed_from_storage {
             Ok(format!("Tool '{}' removed successfully", path))
         } else {
             Err(anyhow!("Tool '{}' not found", path))
         }
     }
-    
+
     fn list_tools(&self, namespace: Option<String>, filter: Option<String>) -> Vec<String> {
-        let mut tools = Vec::new();
-        
-        // Add system tools
-        let system_tools = vec![
-            ToolPath::bin("tcl_execute"),
-            ToolPath::sbin("tcl_tool_add"),
-            ToolPath::sbin("tcl_tool_remove"),
-            ToolPath::bin("tcl_tool_list"),
-            ToolPath::bin("exec_tool"),
-            ToolPath::bin("discover_tools"),
-            ToolPath::docs("molt_book"),
-        ];
-        
-        for tool in system_tools {
-            if let Some(ref ns) = namespace {
-                let matches = match (&tool.namespace, ns.as_str()) {
-                    (Namespace::Bin, "bin") => true,
-                    (Namespace::Sbin, "sbin") => true,
-                    (Namespace::User(user_ns), filter_ns) if user_ns == filter_ns => true,
-                    _ => false,
-                };
-                if !matches {
-                    continue;
-                }
-            }
-            
-            let path_str = tool.to_string();
-            if filter.as_ref().map(|f| path_str.contains(f)).unwrap_or(true) {
-                tools.push(path_str);
-            }
-        }
-        
-        // Add custom tools
-        for path in self.custom_tools.keys() {
-            if let Some(ref ns) = namespace {
-                let matches = match (&path.namespace, ns.as_str()) {
-                    (Namespace::User(user_ns), filter_ns) if user_ns == filter_ns => true,
-                    _ => false,
-                };
-                if !matches {
-                    continue;
-                }
-            }
-            
-            let path_str = path.to_string();
-            if filter.as_ref().map(|f| path_str.contains(f)).unwrap_or(true) {
-                tools.push(path_str);
-            }
-        }
-        
-        // Add discovered tools
-        for path in self.discovered_tools.keys() {
-            if let Some(ref ns) = namespace {
-                let matches = match (&path.namespace, ns.as_str()) {
-                    (Namespace::Bin, "bin") => true,
-                    (Namespace::Sbin, "sbin") => true,
-                    (Namespace::Docs, "docs") => true,
-                    (Namespace::User(user_ns), filter_ns) if user_ns == filter_ns => true,
-                    _ => false,
-                };
-                if !matches {
-                    continue;
-                }
-            }
-            
-            let path_str = path.to_string();
-            if filter.as_ref().map(|f| path_str.contains(f)).unwrap_or(true) {
-                tools.push(path_str);
-            }
-        }
-        
-        tools.sort();
-        tools
+        list_tool_paths(&self.custom_tools.borrow(), &self.discovered_tools.borrow(), namespace, filter)
     }
-    
+
+    /// Top-level script evaluation for a registered custom tool (backs
+    /// `TclCommand::ExecuteCustomTool`). Resets the `call_tool` call stack, since this is an
+    /// entry point rather than a nested call.
     fn execute_custom_tool(&mut self, path: &ToolPath, params: serde_json::Value) -> Result<String> {
-        let tool = self.custom_tools.get(path)
+        let path = resolve_tool_version(&self.custom_tools.borrow(), &self.discovered_tools.borrow(), path)?;
+
+        let tool = self.custom_tools.borrow().get(&path)
+            .ok_or_else(|| anyhow!("Tool '{}' not found", path))?
+            .clone();
+
+        let mut script = bind_params_script(&tool.parameters, &params)?;
+        script.push_str(&tool.script);
+
+        self.execute_script(&script, Some(&path))
+    }
+
+    /// Backs `TclCommand::ExecuteCustomToolWithCoverage`: runs `path` exactly like
+    /// `execute_custom_tool`, except the tool's own script (not the parameter-binding preamble) is
+    /// rewritten by `instrument_for_coverage` first, and `__cov_hit`'s accumulated line numbers are
+    /// read back into a `CoverageReport` once the script returns. A TCL error from the script is
+    /// folded into the returned output text rather than short-circuiting, so the coverage gathered
+    /// up to the failure point is still reported.
+    fn execute_custom_tool_with_coverage(&mut self, path: &ToolPath, params: serde_json::Value) -> Result<(String, CoverageReport)> {
+        let path = resolve_tool_version(&self.custom_tools.borrow(), &self.discovered_tools.borrow(), path)?;
+
+        let tool = self.custom_tools.borrow().get(&path)
             .ok_or_else(|| anyhow!("Tool '{}' not found", path))?
             .clone();
-        
-        let mut script = String::new();
-        
-        // Set parameters as TCL variables
-        if let Some(params_obj) = params.as_object() {
-            for param_def in &tool.parameters {
-                if let Some(value) = params_obj.get(&param_def.name) {
-                    let tcl_value = match value {
-                        serde_json::Value::String(s) => format!("\"{}\"", s.replace("\"", "\\\"")),
-                        _ => value.to_string(),
+
+        let mut script = bind_params_script(&tool.parameters, &params)?;
+        let (instrumented, total_lines) = instrument_for_coverage(&tool.script);
+        script.push_str(&instrumented);
+
+        self.coverage_hits.borrow_mut().clear();
+        let output = match self.execute_script(&script, Some(&path)) {
+            Ok(output) => output,
+            Err(e) => e.to_string(),
+        };
+        let covered_lines = self.coverage_hits.borrow().clone();
+        let percent = if total_lines == 0 {
+            100.0
+        } else {
+            (covered_lines.len() as f64 / total_lines as f64) * 100.0
+        };
+
+        Ok((output, CoverageReport { total_lines, covered_lines, percent }))
+    }
+
+    /// Backs `TclCommand::ExecutePipeline`: runs `steps` in order, interpolating each step's
+    /// `params` against every prior step's bound output (`interpolate_step_params`) before routing
+    /// it through `execute_custom_tool` — so a step's own `ParameterDefinition`s still validate
+    /// exactly as they would for a standalone call. Stops at the first step that errors;
+    /// `partial_results` keeps whatever ran before that, so the caller can see how far it got.
+    fn execute_composition(&mut self, steps: Vec<PipelineStep>) -> PipelineExecutionResult {
+        let mut bindings: HashMap<String, String> = HashMap::new();
+        let mut partial_results = Vec::with_capacity(steps.len());
+
+        for (index, step) in steps.into_iter().enumerate() {
+            let params = interpolate_step_params(&step.params, &bindings);
+            match self.execute_custom_tool(&step.path, params) {
+                Ok(output) => {
+                    if let Some(bind) = &step.bind {
+                        bindings.insert(bind.clone(), output.clone());
+                    }
+                    partial_results.push(PipelineStepResult { bind: step.bind, output });
+                }
+                Err(e) => {
+                    return PipelineExecutionResult {
+                        partial_results,
+                        failed_step: Some(index),
+                        error: Some(e.to_string()),
                     };
-                    script.push_str(&format!("set {} {}\n", param_def.name, tcl_value));
-                } else if param_def.required {
-                    return Err(anyhow!("Missing required parameter: {}", param_def.name));
                 }
             }
         }
-        
-        // Append the tool script
-        script.push_str(&tool.script);
-        
-        self.execute_script(&script)
+
+        PipelineExecutionResult { partial_results, failed_step: None, error: None }
+    }
+
+    /// Backs `TclCommand::TestTool`: the test cases attached to `path` (only custom tools carry
+    /// any — a filesystem-discovered tool's `ToolDefinition` is synthesized on the fly and never
+    /// has test cases of its own), narrowed to those whose name contains `filter`.
+    fn test_tool_cases(&self, path: &ToolPath, filter: Option<String>) -> Result<Vec<ToolTestCase>> {
+        let path = resolve_tool_version(&self.custom_tools.borrow(), &self.discovered_tools.borrow(), path)?;
+
+        let tool = self.custom_tools.borrow().get(&path)
+            .ok_or_else(|| anyhow!("Tool '{}' not found", path))?
+            .clone();
+
+        let cases = decode_test_cases(&tool.test_cases);
+        Ok(match filter {
+            Some(filter) => cases.into_iter().filter(|c| c.name.contains(&filter)).collect(),
+            None => cases,
+        })
     }
-    
+
     fn get_tool_definitions(&self) -> Vec<ToolDefinition> {
         let mut tools = Vec::new();
-        
+
         // Add custom tools
-        tools.extend(self.custom_tools.values().cloned());
-        
+        tools.extend(self.custom_tools.borrow().values().cloned());
+
         // Convert discovered tools to ToolDefinition format
-        for discovered in self.discovered_tools.values() {
+        for discovered in self.discovered_tools.borrow().values() {
             let tool_def = ToolDefinition {
                 path: discovered.path.clone(),
                 description: discovered.description.clone(),
                 script: format!("# Tool loaded from: {}", discovered.file_path.display()),
                 parameters: discovered.parameters.clone(),
+                test_cases: String::new(),
             };
             tools.push(tool_def);
         }
-        
+
         tools
     }
-    
+
     /// Initialize persistence and load existing tools
     async fn initialize_persistence(&mut self) -> Result<String> {
         if self.persistence.is_some() {
             return Ok("Persistence already initialized".to_string());
         }
-        
+
         let persistence = FilePersistence::new().await?;
-        
+
         // Load existing tools from storage
-        let stored_tools = persistence.list_tools(None).await?;
+        let stored_tools = persistence.list_tools(&ToolPathMatcher::all()).await?;
         let loaded_count = stored_tools.len();
-        
+
         // Add stored tools to in-memory cache
-        for tool in stored_tools {
-            // Only load user tools, system tools are hardcoded
-            if matches!(tool.path.namespace, Namespace::User(_)) {
-                self.custom_tools.insert(tool.path.clone(), tool);
+        {
+            let mut custom_tools = self.custom_tools.borrow_mut();
+            for tool in stored_tools {
+                // Only load user tools, system tools are hardcoded
+                if matches!(tool.path.namespace, Namespace::User(_)) {
+                    custom_tools.insert(tool.path.clone(), tool);
+                }
             }
         }
-        
+
         self.persistence = Some(persistence);
-        
+        self.start_watching();
+
         Ok(format!("Persistence initialized. Loaded {} tools from storage.", loaded_count))
     }
-    
-    
-    /// Remove tool from persistent storage
+
+
+    /// Remove tool from persistent storage. Archived revisions are kept (`purge_history: false`)
+    /// in case the tool is re-added later and its history is still wanted.
     async fn remove_tool_from_storage(&mut self, path: &ToolPath) -> Result<bool> {
         if let Some(ref mut persistence) = self.persistence {
-            return persistence.delete_tool(path).await;
+            return persistence.delete_tool(path, false).await;
         }
         Ok(false)
     }
-    
-    /// Execute a tool from the filesystem or custom tools
+
+    /// Execute a tool from the filesystem or custom tools. Top-level entry point (backs
+    /// `TclCommand::ExecTool`), so it resets the `call_tool` call stack before running anything.
     async fn exec_tool(&mut self, tool_path: &str, params: serde_json::Value) -> Result<String> {
-        // Parse the tool path
-        let path = ToolPath::parse(tool_path)?;
-        
-        // Check custom tools first (added via tcl_tool_add)
-        if let Some(custom_tool) = self.custom_tools.get(&path) {
-            // Create a script with parameter bindings
-            let mut full_script = String::new();
-            
-            // Set parameters as TCL variables
-            if let Some(params_obj) = params.as_object() {
-                for param_def in &custom_tool.parameters {
-                    if let Some(value) = params_obj.get(&param_def.name) {
-                        let tcl_value = match value {
-                            serde_json::Value::String(s) => format!("\"{}\"", s.replace("\"", "\\\"")),
-                            _ => value.to_string(),
-                        };
-                        full_script.push_str(&format!("set {} {}\n", param_def.name, tcl_value));
-                    } else if param_def.required {
-                        return Err(anyhow!("Missing required parameter: {}", param_def.name));
-                    }
-                }
-            }
-            
-            // Make params available as an array for the script
-            full_script.push_str("array set params {}\n");
-            if let Some(params_obj) = params.as_object() {
-                for (key, value) in params_obj {
-                    let tcl_value = match value {
-                        serde_json::Value::String(s) => format!("\"{}\"", s.replace("\"", "\\\"")),
-                        _ => value.to_string(),
-                    };
-                    full_script.push_str(&format!("set params({}) {}\n", key, tcl_value));
+        self.call_stack.borrow_mut().clear();
+
+        // Parse the tool path, then resolve a semver requirement (e.g. "^1.2", "~1.0",
+        // ">=1.0,<2.0", or "latest" with no literal "latest" registration) against whatever
+        // versions of this tool path are actually registered.
+        let requested = ToolPath::parse(tool_path)?;
+        let path = resolve_tool_version(&self.custom_tools.borrow(), &self.discovered_tools.borrow(), &requested)?;
+        if path.version != requested.version {
+            tracing::info!("exec_tool: resolved version constraint '{}' on '{}' to {}", requested.version, requested, path);
+        }
+
+        if !self.privileged {
+            if let Some(tool) = self.discovered_tools.borrow().get(&path) {
+                if tool.requires_privileged {
+                    return Err(anyhow!("Tool '{}' requires privileged mode", path));
                 }
             }
-            
-            // Append the tool script
-            full_script.push_str(&custom_tool.script);
-            
-            return self.execute_script(&full_script);
-        }
-        
-        // Check if it's a discovered tool
-        if let Some(discovered_tool) = self.discovered_tools.get(&path) {
-            // Read and execute the tool file
-            let script_content = tokio::fs::read_to_string(&discovered_tool.file_path).await?;
-            
-            // Create a script with parameter bindings
-            let mut full_script = String::new();
-            
-            // Set parameters as TCL variables
-            if let Some(params_obj) = params.as_object() {
-                for param_def in &discovered_tool.parameters {
-                    if let Some(value) = params_obj.get(&param_def.name) {
-                        let tcl_value = match value {
-                            serde_json::Value::String(s) => format!("\"{}\"", s.replace("\"", "\\\"")),
-                            _ => value.to_string(),
-                        };
-                        full_script.push_str(&format!("set {} {}\n", param_def.name, tcl_value));
-                    } else if param_def.required {
-                        return Err(anyhow!("Missing required parameter: {}", param_def.name));
-                    }
-                }
+        }
+
+        // Filesystem-discovered tools need an explicit trust approval before they can run (see
+        // `crate::trust`); tools added via `tcl_tool_add` live in `custom_tools` instead and
+        // aren't affected.
+        let discovered_tool = self.discovered_tools.borrow().get(&path).cloned();
+        if let Some(tool) = discovered_tool {
+            self.ensure_trust_store_loaded().await;
+            if !self.trust_store.is_trusted(&tool) {
+                return Err(anyhow!(
+                    "Tool '{}' is not trusted; approve it with tcl_tool_trust before running it",
+                    path
+                ));
             }
-            
-            // Append the tool script
-            full_script.push_str(&script_content);
-            
-            return self.execute_script(&full_script);
-        }
-        
-        // Check if it's a custom tool
-        if let Some(_custom_tool) = self.custom_tools.get(&path) {
-            return self.execute_custom_tool(&path, params);
-        }
-        
+        }
+
+        // Check custom tools first (added via tcl_tool_add), then discovered tools
+        let resolved = {
+            let custom = self.custom_tools.borrow();
+            let discovered = self.discovered_tools.borrow();
+            resolve_tool_script(&custom, &discovered, &path, &params)?
+        };
+
+        if let Some(script) = resolved {
+            return self.execute_script(&script, Some(&path));
+        }
+
         // Check if it's a built-in system tool
         match tool_path {
             "/bin/tcl_execute" => {
                 if let Some(script) = params.get("script").and_then(|s| s.as_str()) {
-                    self.execute_script(script)
+                    self.execute_script(script, Some(&path))
                 } else {
                     Err(anyhow!("Missing required parameter: script"))
                 }
@@ -484,22 +1433,342 @@ impl TclExecutor {
             _ => Err(anyhow!("Tool '{}' not found", tool_path))
         }
     }
-    
-    /// Discover and index tools from the filesystem
-    async fn discover_tools(&mut self) -> Result<String> {
+
+    /// Splits a TCL list string into its elements via the interpreter's own `llength`/`lindex`,
+    /// so list-quoting rules match whatever produced the string rather than reimplementing TCL
+    /// list parsing here. Backs `bin___pipeline`'s separate mode.
+    fn split_list(&mut self, value: &str) -> Result<Vec<String>> {
+        self.interp.set_scalar("__tcl_mcp_pipeline_list", Value::from(value))
+            .map_err(|e| anyhow!("TCL execution error: {:?}", e))?;
+
+        let len: usize = self.interp.eval("llength $__tcl_mcp_pipeline_list")
+            .map_err(|e| anyhow!("TCL execution error: {:?}", e))?
+            .to_string()
+            .parse()
+            .map_err(|e| anyhow!("llength returned a non-numeric value: {}", e))?;
+
+        let mut elements = Vec::with_capacity(len);
+        for i in 0..len {
+            let element = self.interp.eval(&format!("lindex $__tcl_mcp_pipeline_list {}", i))
+                .map_err(|e| anyhow!("TCL execution error: {:?}", e))?;
+            elements.push(element.to_string());
+        }
+
+        let _ = self.interp.eval("unset __tcl_mcp_pipeline_list");
+        Ok(elements)
+    }
+
+    /// Joins `values` back into a single TCL list string via `lappend`, so elements containing
+    /// spaces or braces get the same quoting a real TCL list would apply. Backs `bin___pipeline`'s
+    /// re-collection of separate-mode results.
+    fn join_list(&mut self, values: &[String]) -> Result<String> {
+        let _ = self.interp.eval("set __tcl_mcp_pipeline_result [list]");
+        for value in values {
+            self.interp.set_scalar("__tcl_mcp_pipeline_elem", Value::from(value.as_str()))
+                .map_err(|e| anyhow!("TCL execution error: {:?}", e))?;
+            self.interp.eval("lappend __tcl_mcp_pipeline_result $__tcl_mcp_pipeline_elem")
+                .map_err(|e| anyhow!("TCL execution error: {:?}", e))?;
+        }
+
+        let result = self.interp.eval("set __tcl_mcp_pipeline_result")
+            .map_err(|e| anyhow!("TCL execution error: {:?}", e))?
+            .to_string();
+
+        let _ = self.interp.eval("unset __tcl_mcp_pipeline_result __tcl_mcp_pipeline_elem");
+        Ok(result)
+    }
+
+    /// Discover and index tools from the filesystem. `force` bypasses `ToolDiscovery`'s
+    /// persisted mtime cache, re-reading every tool file even if its mtime hasn't changed. A bad
+    /// file or directory doesn't abort the scan (see `DiscoveryReport`); any such errors are
+    /// logged and folded into the summary returned here instead.
+    async fn discover_tools(&mut self, force: bool) -> Result<String> {
         // Discover tools from the filesystem
-        let discovered = self.tool_discovery.discover_tools().await?;
-        let count = discovered.len();
-        
+        let report = self.tool_discovery.discover_tools(force).await?;
+        let count = report.tools.len();
+
         // Add discovered tools to our cache
-        for tool in discovered {
-            self.discovered_tools.insert(tool.path.clone(), tool);
+        {
+            let mut discovered_tools = self.discovered_tools.borrow_mut();
+            for tool in report.tools {
+                discovered_tools.insert(tool.path.clone(), tool);
+            }
         }
-        
+
         // Register discovered tools as available for execution
         // Note: We don't add them as TCL commands directly since that would require
-        // complex callback handling. Instead, they can be executed via exec_tool.
-        
-        Ok(format!("Discovered {} tools from filesystem", count))
+        // complex callback handling. Instead, they can be executed via exec_tool or call_tool.
+
+        if report.errors.is_empty() {
+            return Ok(format!("Discovered {} tools from filesystem", count));
+        }
+
+        for error in &report.errors {
+            tracing::warn!("Tool discovery error: {}", error);
+        }
+        Ok(format!(
+            "Discovered {} tools from filesystem ({} error{} encountered; see logs)",
+            count,
+            report.errors.len(),
+            if report.errors.len() == 1 { "" } else { "s" }
+        ))
+    }
+}
+
+/// Returns the configured executor pool size, defaulting to the number of logical CPUs.
+/// `TCL_MCP_POOL_SIZE` is the preferred name (mirroring `--pool-size`); `TCL_MCP_EXECUTOR_POOL_SIZE`
+/// is still read as a fallback for existing deployments that already set it.
+pub fn pool_size_from_env() -> usize {
+    std::env::var("TCL_MCP_POOL_SIZE")
+        .ok()
+        .or_else(|| std::env::var("TCL_MCP_EXECUTOR_POOL_SIZE").ok())
+        .and_then(|v| v.parse().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or_else(num_cpus::get)
+}
+
+/// A pool of independent `TclExecutor` worker threads, each with its own interpreter, so
+/// concurrent `tools/call` invocations aren't serialized through a single interpreter.
+///
+/// Read/execute commands (`Execute`, `ExecTool`, `ExecuteCustomTool`, `ListTools`,
+/// `GetToolDefinitions`) are routed round-robin to a free-ish worker.
+/// Commands that mutate the shared tool registry (`AddTool`, `RemoveTool`,
+/// `InitializePersistence`, `DiscoverTools`) must go through `TclToolBox::broadcast` (in
+/// `tcl_tools.rs`), which sends them on every channel from [`TclExecutorPool::senders`] so
+/// every worker's registry stays consistent.
+///
+/// Because each worker owns its own interpreter, a tool's script must be side-effect-free across
+/// workers: it can't assume a global TCL variable set by one call is still there on the next, or
+/// that two concurrent calls to the same tool share any state beyond what `call_tool`'s params and
+/// return value carry. Any state a tool needs to persist belongs in `FilePersistence`, which every
+/// worker already reads from the same on-disk store, not in interpreter-local variables.
+#[derive(Clone)]
+pub struct TclExecutorPool {
+    workers: Arc<Vec<mpsc::Sender<TclCommand>>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl TclExecutorPool {
+    /// Spawns `size` independent `TclExecutor` workers, each on its own thread.
+    pub fn spawn(privileged: bool, size: usize) -> Self {
+        let size = size.max(1);
+        let workers = (0..size).map(|_| TclExecutor::spawn(privileged)).collect();
+
+        Self {
+            workers: Arc::new(workers),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Like `spawn`, but every worker also applies `runtime_config.eval_timeout` to the scripts
+    /// it runs (see `TclExecutor::spawn_with_runtime`). Used by `HttpMcpServer::new_with_runtime`
+    /// so picking a `--runtime`/`--eval-timeout` flag doesn't fall back to a single-threaded
+    /// executor and serialize every `tools/call` behind it.
+    pub fn spawn_with_runtime(privileged: bool, size: usize, runtime_config: RuntimeConfig) -> Result<Self, String> {
+        let size = size.max(1);
+        let workers = (0..size)
+            .map(|_| TclExecutor::spawn_with_runtime(privileged, runtime_config.clone()))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            workers: Arc::new(workers),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Wraps a single, already-spawned executor as a pool of one (used by `TclToolBox::new`,
+    /// which is handed one channel).
+    pub fn from_single(sender: mpsc::Sender<TclCommand>) -> Self {
+        Self {
+            workers: Arc::new(vec![sender]),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Picks the next worker in round-robin order.
+    pub fn next_sender(&self) -> mpsc::Sender<TclCommand> {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        self.workers[idx].clone()
+    }
+
+    /// All worker channels, for commands that must be applied to every interpreter's registry.
+    pub fn senders(&self) -> impl Iterator<Item = &mpsc::Sender<TclCommand>> {
+        self.workers.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fans `n` `Execute` calls out across `pool` concurrently via `tokio::spawn`, each landing on
+    /// whichever worker `next_sender`'s round-robin hands it, and returns every result in
+    /// submission order once all have completed.
+    async fn run_concurrent_executes(pool: &TclExecutorPool, n: usize) -> Vec<Result<String>> {
+        let handles: Vec<_> = (0..n).map(|i| {
+            let sender = pool.next_sender();
+            tokio::spawn(async move {
+                let (tx, rx) = oneshot::channel();
+                sender.send(TclCommand::Execute {
+                    script: format!("expr {{{} * 2}}", i),
+                    response: tx,
+                }).await.unwrap();
+                rx.await.unwrap()
+            })
+        }).collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        results
+    }
+
+    /// Stress test backing chunk12-4: hundreds of concurrent executions fanned out across a
+    /// multi-worker `TclExecutorPool` (each worker its own thread and interpreter), the scenario
+    /// `test_bin_exec_tool_concurrent_execution`-style callers would otherwise queue behind one
+    /// single-actor `TclExecutor`. Every call still has to see correct, isolated output, since the
+    /// whole point of per-worker interpreters is that tools stay side-effect-free across workers.
+    #[tokio::test]
+    async fn test_pool_scales_concurrent_execution_across_workers() {
+        let pool = TclExecutorPool::spawn(false, 4);
+        assert_eq!(pool.len(), 4);
+
+        let results = run_concurrent_executes(&pool, 300).await;
+        assert_eq!(results.len(), 300);
+        for (i, result) in results.into_iter().enumerate() {
+            assert_eq!(result.unwrap(), (i * 2).to_string());
+        }
+    }
+
+    /// A pool of one worker is the single-actor baseline this request compares against: still
+    /// correct under concurrent submission, just serialized behind the one interpreter.
+    #[tokio::test]
+    async fn test_pool_of_one_matches_single_actor_baseline() {
+        let pool = TclExecutorPool::spawn(false, 1);
+        assert_eq!(pool.len(), 1);
+
+        let results = run_concurrent_executes(&pool, 50).await;
+        for (i, result) in results.into_iter().enumerate() {
+            assert_eq!(result.unwrap(), (i * 2).to_string());
+        }
     }
-}
\ No newline at end of file
+
+    fn param(name: &str, type_name: &str) -> ParameterDefinition {
+        ParameterDefinition {
+            name: name.to_string(),
+            description: String::new(),
+            required: true,
+            type_name: type_name.to_string(),
+            default: None,
+            enum_values: None,
+            min: None,
+            max: None,
+            validate: None,
+        }
+    }
+
+    /// Registers a tool straight into `custom_tools`, bypassing `add_tool`'s `FilePersistence`
+    /// setup (which would otherwise touch the real user's local data directory) — these tests only
+    /// need `execute_composition`'s in-memory control flow, not persistence.
+    fn register_tool(executor: &TclExecutor, path: ToolPath, script: &str, parameters: Vec<ParameterDefinition>) {
+        executor.custom_tools.borrow_mut().insert(path.clone(), ToolDefinition {
+            path,
+            description: String::new(),
+            script: script.to_string(),
+            parameters,
+            test_cases: String::new(),
+        });
+    }
+
+    /// Covers chunk12-5's "data flow between steps": the first step's bound output is interpolated
+    /// into the second step's params via `"${name}"`, the same way two independent calls never could.
+    #[test]
+    fn test_execute_composition_threads_bound_output_into_later_step() {
+        let mut executor = TclExecutor::new(true);
+        let double_path = ToolPath::user("alice", "math", "double", "1.0");
+        let greet_path = ToolPath::user("alice", "text", "greet", "1.0");
+        register_tool(&executor, double_path.clone(), "return [expr {$n * 2}]", vec![param("n", "number")]);
+        register_tool(&executor, greet_path.clone(), "return \"hello, $name\"", vec![param("name", "string")]);
+
+        let result = executor.execute_composition(vec![
+            PipelineStep { path: double_path, params: serde_json::json!({"n": 5}), bind: Some("doubled".to_string()) },
+            PipelineStep { path: greet_path, params: serde_json::json!({"name": "${doubled}"}), bind: None },
+        ]);
+
+        assert_eq!(result.failed_step, None);
+        assert_eq!(result.error, None);
+        assert_eq!(result.partial_results.len(), 2);
+        assert_eq!(result.partial_results[0].output, "10");
+        assert_eq!(result.partial_results[1].output, "hello, 10");
+    }
+
+    /// Covers chunk12-5's "error propagation mid-pipeline": a step missing a required parameter
+    /// fails validation, `execute_composition` stops there, and the step after it never runs —
+    /// `partial_results` holds only what actually completed, and `failed_step`/`error` say why.
+    #[test]
+    fn test_execute_composition_stops_at_first_failing_step() {
+        let mut executor = TclExecutor::new(true);
+        let double_path = ToolPath::user("alice", "math", "double", "1.0");
+        let greet_path = ToolPath::user("alice", "text", "greet", "1.0");
+        register_tool(&executor, double_path.clone(), "return [expr {$n * 2}]", vec![param("n", "number")]);
+        register_tool(&executor, greet_path.clone(), "return \"hello, $name\"", vec![param("name", "string")]);
+
+        let result = executor.execute_composition(vec![
+            PipelineStep { path: double_path, params: serde_json::json!({"n": 5}), bind: Some("doubled".to_string()) },
+            PipelineStep { path: greet_path, params: serde_json::json!({}), bind: Some("greeting".to_string()) },
+            PipelineStep { path: ToolPath::user("alice", "math", "double", "1.0"), params: serde_json::json!({"n": "${greeting}"}), bind: None },
+        ]);
+
+        assert_eq!(result.failed_step, Some(1));
+        assert!(result.error.as_ref().unwrap().contains("name"));
+        assert_eq!(result.partial_results.len(), 1);
+        assert_eq!(result.partial_results[0].output, "10");
+    }
+
+    #[test]
+    fn test_validate_script_accepts_well_formed_script() {
+        let mut executor = TclExecutor::new(true);
+        assert!(executor.validate_script("set x 1\nreturn $x").is_ok());
+    }
+
+    #[test]
+    fn test_validate_script_rejects_unbalanced_braces() {
+        let mut executor = TclExecutor::new(true);
+        assert!(executor.validate_script("if {1} { return ok").is_err());
+    }
+
+    /// Covers chunk12-6: an externally-edited tool file whose new script doesn't validate is
+    /// rejected by `apply_tool_change`, and the previously-loaded version keeps serving calls.
+    #[tokio::test]
+    async fn test_apply_tool_change_keeps_previous_version_on_invalid_reload() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let mut persistence = FilePersistence::with_directory(temp_dir.path().to_path_buf()).await?;
+        let path = ToolPath::user("alice", "utils", "greet", "1.0");
+        let good = ToolDefinition {
+            path: path.clone(),
+            description: String::new(),
+            script: "return \"hello\"".to_string(),
+            parameters: vec![],
+            test_cases: String::new(),
+        };
+        persistence.save_tool(&good).await?;
+
+        let mut executor = TclExecutor::new(true);
+        executor.custom_tools.borrow_mut().insert(path.clone(), good.clone());
+        executor.persistence = Some(persistence);
+
+        let bad = ToolDefinition { script: "if {1} { return broken".to_string(), ..good.clone() };
+        executor.persistence.as_mut().unwrap().save_tool(&bad).await?;
+
+        executor.apply_tool_change(ToolChange { path: path.clone(), kind: ChangeKind::Modified }).await;
+
+        let stored = executor.custom_tools.borrow().get(&path).unwrap().clone();
+        assert_eq!(stored.script, good.script);
+        Ok(())
+    }
+}
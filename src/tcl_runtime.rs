@@ -1,5 +1,7 @@
 use anyhow::{Result, anyhow};
 use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum RuntimeType {
@@ -40,6 +42,31 @@ impl RuntimeType {
 pub struct RuntimeConfig {
     pub runtime_type: Option<RuntimeType>,
     pub fallback_enabled: bool,
+    /// Overrides the executor pool size (otherwise `num_cpus::get()`). See `--pool-size` /
+    /// `TCL_MCP_POOL_SIZE` (or the older `TCL_MCP_EXECUTOR_POOL_SIZE` name).
+    pub executor_pool_size: Option<usize>,
+    /// Whether `FilePersistence` should maintain an rkyv-backed binary cache of persisted tool
+    /// definitions to avoid re-parsing every tool's JSON file on startup. See
+    /// `TCL_MCP_TOOL_CACHE_ENABLED`.
+    pub tool_cache_enabled: bool,
+    /// Overrides the tool definition cache's file path (otherwise `<storage_dir>/tools.cache.rkyv`).
+    /// See `TCL_MCP_TOOL_CACHE_PATH`.
+    pub tool_cache_path: Option<PathBuf>,
+    /// Wall-clock budget applied to every script a `TclExecutor` evaluates, converted to a Molt
+    /// command budget (see `COMMANDS_PER_MILLISECOND` in `tcl_executor.rs`), so a
+    /// runaway script (`while 1 {}`) can't hang a request thread indefinitely. `None` means
+    /// unbounded. See `--eval-timeout` / `TCL_MCP_EVAL_TIMEOUT_MS`.
+    pub eval_timeout: Option<Duration>,
+    /// Command names that should be evaluated by the full TCL interpreter instead of Molt (see
+    /// `RoutingRuntime`), so a privileged operator can whitelist a few trusted commands (e.g.
+    /// `file`, `exec`) without giving up Molt's sandboxing for everything else. Empty means no
+    /// routing — the single-runtime selection below applies as normal. Requires both the `molt`
+    /// and `tcl` features. See `TCL_MCP_ROUTED_COMMANDS` (comma-separated).
+    pub routed_commands: Vec<String>,
+    /// Overrides how deep a chain of nested `call_tool` invocations (see `tcl_executor`'s
+    /// `CallToolCommand`) may go before it's aborted. `None` keeps the executor's built-in
+    /// default. See `TCL_MCP_MAX_CALL_DEPTH`.
+    pub max_call_depth: Option<usize>,
 }
 
 impl Default for RuntimeConfig {
@@ -47,40 +74,308 @@ impl Default for RuntimeConfig {
         Self {
             runtime_type: None,
             fallback_enabled: true,
+            executor_pool_size: None,
+            tool_cache_enabled: false,
+            tool_cache_path: None,
+            eval_timeout: None,
+            routed_commands: Vec::new(),
+            max_call_depth: None,
         }
     }
 }
 
-/// Trait defining the interface for TCL runtime implementations
-pub trait TclRuntime {
-    /// Create a new instance of the TCL runtime
-    fn new() -> Self where Self: Sized;
-    
+/// A TCL value carrying enough type information to round-trip as a JSON number/boolean/array
+/// instead of a plain string, for backends that can actually tell the difference (see
+/// [`TypedValues`]). Every variant still has a canonical TCL string form — TCL itself has no
+/// distinct types, just strings with a type-appropriate internal representation — so a `TclValue`
+/// can always be written back into any runtime via [`TclValue::to_tcl_string`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TclValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    List(Vec<TclValue>),
+}
+
+impl TclValue {
+    /// Renders this value the way TCL itself would expect to read it back: integers and floats in
+    /// their usual decimal form, a bool as TCL's own `1`/`0` (TCL has no separate boolean literal),
+    /// a string as-is, and a list as a brace-quoted TCL list so embedded whitespace round-trips as
+    /// one element rather than splitting apart.
+    pub fn to_tcl_string(&self) -> String {
+        match self {
+            TclValue::Int(i) => i.to_string(),
+            TclValue::Float(f) => f.to_string(),
+            TclValue::Bool(b) => if *b { "1".to_string() } else { "0".to_string() },
+            TclValue::Str(s) => s.clone(),
+            TclValue::List(items) => items
+                .iter()
+                .map(|item| {
+                    let rendered = item.to_tcl_string();
+                    if rendered.is_empty() || rendered.contains(char::is_whitespace) {
+                        format!("{{{rendered}}}")
+                    } else {
+                        rendered
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Best-effort guess at a plain TCL string's type, for backends (Molt) whose values don't
+    /// carry a type tag a caller can inspect directly — tries an integer, then a float, and falls
+    /// back to [`TclValue::Str`]. Never produces [`TclValue::Bool`] or [`TclValue::List`]: nothing
+    /// distinguishes a boolean-looking string or a multi-word list from an ordinary string at this
+    /// level, so guessing either would be more confident than the input warrants.
+    pub fn from_tcl_string(s: &str) -> TclValue {
+        if let Ok(i) = s.parse::<i64>() {
+            return TclValue::Int(i);
+        }
+        if let Ok(f) = s.parse::<f64>() {
+            return TclValue::Float(f);
+        }
+        TclValue::Str(s.to_string())
+    }
+}
+
+/// Evaluates TCL scripts against whatever interpreter state the implementor holds.
+pub trait Evaluator {
     /// Evaluate a TCL script and return the result
     fn eval(&mut self, script: &str) -> Result<String>;
-    
+
+    /// Evaluate `script`, but give up after `limit` if it hasn't finished.
+    ///
+    /// The default implementation has no way to interrupt an uncooperative backend mid-script, so
+    /// it hands the call to a dedicated thread and simply stops waiting on it once `limit` elapses
+    /// — the thread is abandoned, not killed. **A runtime instance must be discarded, never reused,
+    /// after one of its `eval_bounded` calls times out**: the abandoned thread may still be
+    /// mutating interpreter state that the caller no longer has a way to observe finishing.
+    ///
+    /// Backends that can check their own time budget between steps (see
+    /// `MoltRuntime::eval_bounded`'s use of Molt's command limit) should override this to cancel
+    /// cooperatively instead, which has no such caveat — `TclExecutor` relies on that for the Molt
+    /// interpreter it holds directly, so it never needs to discard and recreate it on a timeout.
+    fn eval_bounded(&mut self, script: &str, limit: Duration) -> Result<String>
+    where
+        Self: Send + Sized + 'static,
+    {
+        // SAFETY: `SendPtr` only crosses the thread boundary to call `eval` once. The caller is
+        // relied on (see doc comment above) to drop/replace `self` rather than touch it again if
+        // the receive below times out, so no aliased access to `*self` can occur afterward.
+        struct SendPtr(*mut (dyn Evaluator + Send));
+        unsafe impl Send for SendPtr {}
+
+        let ptr = SendPtr(self as *mut Self as *mut (dyn Evaluator + Send));
+        let script = script.to_string();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let SendPtr(raw) = ptr;
+            // SAFETY: valid for the duration of this closure per the caller contract above.
+            let result = unsafe { (*raw).eval(&script) };
+            let _ = tx.send(result);
+        });
+
+        rx.recv_timeout(limit)
+            .unwrap_or_else(|_| Err(anyhow!("script exceeded eval_bounded timeout of {:?}", limit)))
+    }
+}
+
+/// Reads and writes interpreter-visible variables.
+///
+/// Split out from [`Evaluator`] so a backend's evaluator can be paired with a `VariableStore`
+/// backed by something else entirely, e.g. the `persistence` module.
+pub trait VariableStore {
     /// Set a variable in the TCL runtime
     fn set_var(&mut self, name: &str, value: &str) -> Result<()>;
-    
+
     /// Get a variable from the TCL runtime
     fn get_var(&self, name: &str) -> Result<String>;
-    
+}
+
+/// Typed variable/eval access layered on top of [`VariableStore`]/[`Evaluator`]'s string-only
+/// interface, so a caller that already knows it wants a number or boolean back doesn't have to
+/// re-parse a TCL string itself (see [`TclValue`]).
+///
+/// The default implementations here fall back to parsing `get_var`/`eval`'s string result with
+/// [`TclValue::from_tcl_string`] — all Molt can offer, since its `Value` doesn't expose a type tag
+/// a caller can inspect. `TclInterpreter` overrides these to use the `tcl` crate's `Obj`
+/// conversions directly, which preserve the interpreter's own internal representation instead of
+/// guessing from a string.
+pub trait TypedValues: VariableStore + Evaluator {
+    /// Set a variable from a typed value, rendered via [`TclValue::to_tcl_string`].
+    fn set_var_typed(&mut self, name: &str, value: TclValue) -> Result<()> {
+        self.set_var(name, &value.to_tcl_string())
+    }
+
+    /// Get a variable, guessing its type from the string `get_var` returns.
+    fn get_var_typed(&self, name: &str) -> Result<TclValue> {
+        self.get_var(name).map(|s| TclValue::from_tcl_string(&s))
+    }
+
+    /// Evaluate a script, guessing the result's type from the string `eval` returns.
+    fn eval_typed(&mut self, script: &str) -> Result<TclValue> {
+        self.eval(script).map(|s| TclValue::from_tcl_string(&s))
+    }
+}
+
+impl<T: VariableStore + Evaluator> TypedValues for T {}
+
+/// Answers questions about what a runtime can do, without being able to run anything.
+pub trait Introspector {
     /// Check if the runtime supports a specific command
     fn has_command(&self, command: &str) -> bool;
-    
+}
+
+/// Static metadata about a runtime, independent of any particular interpreter instance.
+pub trait RuntimeInfo {
     /// Get runtime name for logging/debugging
     fn name(&self) -> &'static str;
-    
+
     /// Get runtime version
     fn version(&self) -> &'static str;
-    
+
     /// Get runtime features/capabilities
     fn features(&self) -> Vec<String>;
-    
+
     /// Check if runtime is safe/sandboxed
     fn is_safe(&self) -> bool;
 }
 
+/// Full interface expected of a TCL runtime implementation.
+///
+/// This is deliberately just the union of the five capability traits above, via a blanket impl,
+/// so existing code that works with `Box<dyn TclRuntime>` keeps working unchanged whether it's
+/// handed a single monolithic backend or a [`CompoundRuntime`] assembled from separate parts.
+/// `TypedValues` itself is blanket-implemented for anything with `Evaluator + VariableStore`, so
+/// no backend needs to opt in explicitly to satisfy this bound — only `TclInterpreter` overrides
+/// its default (string-guessing) behavior.
+pub trait TclRuntime: Evaluator + VariableStore + Introspector + RuntimeInfo + TypedValues {}
+
+impl<T: Evaluator + VariableStore + Introspector + RuntimeInfo + TypedValues> TclRuntime for T {}
+
+/// A runtime assembled from independently-replaceable parts, one per capability trait.
+///
+/// Modeled on the "runtime built from replacement parts" approach in Arti's `tor-rtcompat`
+/// `CompoundRuntime`: each generic parameter owns exactly one capability, so e.g. Molt's
+/// sandboxed [`Evaluator`] can be paired with a [`VariableStore`] backed by the `persistence`
+/// module, or any [`Evaluator`] can be wrapped with an [`Introspector`] that records command
+/// usage, all without touching the underlying backends.
+///
+/// When a single backend needs to provide more than one capability (the common case — Molt and
+/// the official TCL interpreter each hold one mutable interpreter that evaluates scripts, stores
+/// variables, *and* answers introspection queries), share it across parts with
+/// `Rc<RefCell<Backend>>`: the blanket impls below let any `Rc<RefCell<T>>` stand in for `T`.
+pub struct CompoundRuntime<E, V, I, M> {
+    evaluator: E,
+    variables: V,
+    introspector: I,
+    info: M,
+}
+
+impl<E, V, I, M> CompoundRuntime<E, V, I, M> {
+    /// Assemble a runtime from its constituent parts.
+    pub fn new(evaluator: E, variables: V, introspector: I, info: M) -> Self {
+        Self { evaluator, variables, introspector, info }
+    }
+}
+
+impl<E: Evaluator, V, I, M> Evaluator for CompoundRuntime<E, V, I, M> {
+    fn eval(&mut self, script: &str) -> Result<String> {
+        self.evaluator.eval(script)
+    }
+}
+
+impl<E, V: VariableStore, I, M> VariableStore for CompoundRuntime<E, V, I, M> {
+    fn set_var(&mut self, name: &str, value: &str) -> Result<()> {
+        self.variables.set_var(name, value)
+    }
+
+    fn get_var(&self, name: &str) -> Result<String> {
+        self.variables.get_var(name)
+    }
+}
+
+impl<E, V, I: Introspector, M> Introspector for CompoundRuntime<E, V, I, M> {
+    fn has_command(&self, command: &str) -> bool {
+        self.introspector.has_command(command)
+    }
+}
+
+impl<E, V, I, M: RuntimeInfo> RuntimeInfo for CompoundRuntime<E, V, I, M> {
+    fn name(&self) -> &'static str {
+        self.info.name()
+    }
+
+    fn version(&self) -> &'static str {
+        self.info.version()
+    }
+
+    fn features(&self) -> Vec<String> {
+        self.info.features()
+    }
+
+    fn is_safe(&self) -> bool {
+        self.info.is_safe()
+    }
+}
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+impl<T: Evaluator> Evaluator for Rc<RefCell<T>> {
+    fn eval(&mut self, script: &str) -> Result<String> {
+        self.borrow_mut().eval(script)
+    }
+}
+
+impl<T: VariableStore> VariableStore for Rc<RefCell<T>> {
+    fn set_var(&mut self, name: &str, value: &str) -> Result<()> {
+        self.borrow_mut().set_var(name, value)
+    }
+
+    fn get_var(&self, name: &str) -> Result<String> {
+        self.borrow().get_var(name)
+    }
+}
+
+impl<T: Introspector> Introspector for Rc<RefCell<T>> {
+    fn has_command(&self, command: &str) -> bool {
+        self.borrow().has_command(command)
+    }
+}
+
+impl<T: RuntimeInfo> RuntimeInfo for Rc<RefCell<T>> {
+    fn name(&self) -> &'static str {
+        self.borrow().name()
+    }
+
+    fn version(&self) -> &'static str {
+        self.borrow().version()
+    }
+
+    fn features(&self) -> Vec<String> {
+        self.borrow().features()
+    }
+
+    fn is_safe(&self) -> bool {
+        self.borrow().is_safe()
+    }
+}
+
+/// Wrap a single backend that provides all four capabilities itself into a `CompoundRuntime`
+/// whose parts all share one `Rc<RefCell<_>>` handle to that backend.
+#[cfg(any(feature = "molt", feature = "tcl"))]
+fn share_as_compound<T>(backend: T) -> CompoundRuntime<Rc<RefCell<T>>, Rc<RefCell<T>>, Rc<RefCell<T>>, Rc<RefCell<T>>>
+where
+    T: Evaluator + VariableStore + Introspector + RuntimeInfo,
+{
+    let shared = Rc::new(RefCell::new(backend));
+    CompoundRuntime::new(shared.clone(), shared.clone(), shared.clone(), shared)
+}
+
 #[cfg(feature = "molt")]
 mod molt_runtime;
 #[cfg(feature = "molt")]
@@ -91,6 +386,8 @@ mod tcl_interpreter;
 #[cfg(feature = "tcl")]
 pub use tcl_interpreter::TclInterpreter;
 
+mod routing_runtime;
+pub use routing_runtime::RoutingRuntime;
 
 /// Check if a runtime type is available at compile time
 pub fn is_runtime_available(runtime_type: RuntimeType) -> bool {
@@ -109,15 +406,92 @@ pub fn available_runtimes() -> Vec<RuntimeType> {
     runtimes
 }
 
+/// Outcome of [`probe`] for one runtime type: whether it was compiled in at all, and — if so —
+/// whether a freshly constructed instance actually answers a smoke script and a basic
+/// introspection query, not just whether `cfg!(feature = ...)` says it should.
+#[derive(Debug, Clone)]
+pub struct RuntimeProbeStatus {
+    pub runtime_type: RuntimeType,
+    /// Mirrors `RuntimeType::is_available()` — reflects compiled-in features only.
+    pub compiled: bool,
+    /// `true` only once a constructed instance evaluated `expr {1 + 1}` as `"2"` and reported
+    /// `has_command("set")`. Always `false` when `compiled` is `false`.
+    pub probed_ok: bool,
+    /// Why `probed_ok` is `false`, when `compiled` is `true` (construction failed, or the smoke
+    /// script/command check came back wrong — e.g. missing system Tcl library, broken linkage).
+    pub error: Option<String>,
+}
+
+/// Smoke-tests a just-constructed runtime: evaluates `expr {1 + 1}` and confirms `set` is a known
+/// command. A runtime compiled in can still be unusable at construction time or miswired, so this
+/// is checked in addition to (not instead of) `RuntimeType::is_available()`.
+fn smoke_test(runtime: &mut dyn TclRuntime) -> Result<()> {
+    let result = runtime.eval("expr {1 + 1}")?;
+    if result != "2" {
+        return Err(anyhow!("smoke script 'expr {{1 + 1}}' returned '{}', expected '2'", result));
+    }
+    if !runtime.has_command("set") {
+        return Err(anyhow!("has_command(\"set\") returned false"));
+    }
+    Ok(())
+}
+
+/// Functionally probes a runtime type: constructs it (if compiled in) and runs [`smoke_test`]
+/// against the fresh instance, rather than trusting `cfg!(feature = ...)` alone.
+pub fn probe(runtime_type: RuntimeType) -> RuntimeProbeStatus {
+    if !runtime_type.is_available() {
+        return RuntimeProbeStatus {
+            runtime_type,
+            compiled: false,
+            probed_ok: false,
+            error: Some("not compiled in".to_string()),
+        };
+    }
+
+    match create_specific_runtime(runtime_type.clone()) {
+        Ok(mut runtime) => match smoke_test(runtime.as_mut()) {
+            Ok(()) => RuntimeProbeStatus { runtime_type, compiled: true, probed_ok: true, error: None },
+            Err(e) => RuntimeProbeStatus { runtime_type, compiled: true, probed_ok: false, error: Some(e.to_string()) },
+        },
+        Err(e) => RuntimeProbeStatus { runtime_type, compiled: true, probed_ok: false, error: Some(e.to_string()) },
+    }
+}
+
 /// Create runtime with specific configuration
 pub fn create_runtime_with_config(config: RuntimeConfig) -> Result<Box<dyn TclRuntime>> {
+    #[cfg(all(feature = "molt", feature = "tcl"))]
+    if !config.routed_commands.is_empty() {
+        tracing::info!(
+            routed_commands = %config.routed_commands.join(","),
+            "Routing allowlisted commands to the full TCL interpreter; everything else runs on Molt"
+        );
+        return Ok(Box::new(RoutingRuntime::new(
+            MoltRuntime::new(),
+            Some(TclInterpreter::new()),
+            config.routed_commands,
+        )));
+    }
+
     if let Some(requested_type) = config.runtime_type {
         // Try to create the requested runtime
         match create_specific_runtime(requested_type.clone()) {
-            Ok(runtime) => {
-                tracing::info!("Using {} TCL runtime", requested_type.as_str());
-                return Ok(runtime);
-            }
+            Ok(mut runtime) => match smoke_test(runtime.as_mut()) {
+                Ok(()) => {
+                    tracing::info!("Using {} TCL runtime", requested_type.as_str());
+                    return Ok(runtime);
+                }
+                Err(e) if config.fallback_enabled => {
+                    tracing::warn!(
+                        runtime = requested_type.as_str(),
+                        error = %e,
+                        "Requested runtime compiled but failed its startup probe; falling back to auto-selection"
+                    );
+                    // Fall through to auto-selection
+                }
+                Err(e) => {
+                    return Err(anyhow!("{} TCL runtime failed its startup probe: {}", requested_type.as_str(), e));
+                }
+            },
             Err(e) if config.fallback_enabled => {
                 tracing::warn!("Failed to create requested runtime {:?}: {}. Trying fallback.", requested_type, e);
                 // Fall through to auto-selection
@@ -125,19 +499,19 @@ pub fn create_runtime_with_config(config: RuntimeConfig) -> Result<Box<dyn TclRu
             Err(e) => return Err(e),
         }
     }
-    
+
     // Auto-select based on available features (prefer Molt for safety)
     #[cfg(feature = "molt")]
     {
         tracing::info!("Auto-selecting Molt TCL runtime");
-        return Ok(Box::new(MoltRuntime::new()));
+        return Ok(Box::new(share_as_compound(MoltRuntime::new())));
     }
-    
-    
+
+
     #[cfg(all(feature = "tcl", not(feature = "molt")))]
     {
         tracing::info!("Auto-selecting official TCL runtime");
-        return Ok(Box::new(TclInterpreter::new()));
+        return Ok(Box::new(share_as_compound(TclInterpreter::new())));
     }
     
     #[cfg(all(not(feature = "molt"), not(feature = "tcl")))]
@@ -152,7 +526,7 @@ fn create_specific_runtime(runtime_type: RuntimeType) -> Result<Box<dyn TclRunti
         RuntimeType::Molt => {
             #[cfg(feature = "molt")]
             {
-                Ok(Box::new(MoltRuntime::new()))
+                Ok(Box::new(share_as_compound(MoltRuntime::new())))
             }
             #[cfg(not(feature = "molt"))]
             {
@@ -162,7 +536,7 @@ fn create_specific_runtime(runtime_type: RuntimeType) -> Result<Box<dyn TclRunti
         RuntimeType::Tcl => {
             #[cfg(feature = "tcl")]
             {
-                Ok(Box::new(TclInterpreter::new()))
+                Ok(Box::new(share_as_compound(TclInterpreter::new())))
             }
             #[cfg(not(feature = "tcl"))]
             {
@@ -191,19 +565,62 @@ impl RuntimeConfig {
     pub fn from_args_and_env(
         cli_runtime: Option<&str>,
         env_runtime: Option<&str>, // Environment variable value
+        cli_eval_timeout_ms: Option<u64>,
+    ) -> Result<Self> {
+        Self::from_args_and_env_with_pool_size(cli_runtime, env_runtime, cli_eval_timeout_ms, None)
+    }
+
+    /// Same as [`Self::from_args_and_env`], but also accepts a `--pool-size` CLI value with the
+    /// same precedence as `--runtime`/`TCL_MCP_RUNTIME`: CLI overrides `TCL_MCP_POOL_SIZE`, which
+    /// overrides the older `TCL_MCP_EXECUTOR_POOL_SIZE` name, which falls back to `num_cpus::get()`
+    /// (applied later, by `pool_size_from_env`, when `executor_pool_size` is still `None`).
+    pub fn from_args_and_env_with_pool_size(
+        cli_runtime: Option<&str>,
+        env_runtime: Option<&str>, // Environment variable value
+        cli_eval_timeout_ms: Option<u64>,
+        cli_pool_size: Option<usize>,
     ) -> Result<Self> {
         let mut config = RuntimeConfig::default();
-        
+
         // Check environment variable first
         if let Some(env_runtime) = env_runtime {
             config.runtime_type = Some(env_runtime.parse()?);
         }
-        
-        // CLI argument overrides environment  
+
+        // CLI argument overrides environment
         if let Some(cli_runtime) = cli_runtime {
             config.runtime_type = Some(cli_runtime.parse()?);
         }
-        
+
+        config.executor_pool_size = cli_pool_size.filter(|n| *n > 0).or_else(|| {
+            env::var("TCL_MCP_POOL_SIZE")
+                .ok()
+                .or_else(|| env::var("TCL_MCP_EXECUTOR_POOL_SIZE").ok())
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+        });
+
+        config.tool_cache_enabled = env::var("TCL_MCP_TOOL_CACHE_ENABLED")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        config.tool_cache_path = env::var("TCL_MCP_TOOL_CACHE_PATH").ok().map(PathBuf::from);
+
+        // CLI argument overrides TCL_MCP_EVAL_TIMEOUT_MS, same precedence as runtime selection
+        config.eval_timeout = cli_eval_timeout_ms
+            .or_else(|| env::var("TCL_MCP_EVAL_TIMEOUT_MS").ok().and_then(|v| v.parse().ok()))
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis);
+
+        config.routed_commands = env::var("TCL_MCP_ROUTED_COMMANDS")
+            .ok()
+            .map(|v| v.split(',').map(|cmd| cmd.trim().to_string()).filter(|cmd| !cmd.is_empty()).collect())
+            .unwrap_or_default();
+
+        config.max_call_depth = env::var("TCL_MCP_MAX_CALL_DEPTH")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|n| *n > 0);
+
         Ok(config)
     }
     
@@ -211,6 +628,73 @@ impl RuntimeConfig {
     pub fn available_runtimes() -> Vec<RuntimeType> {
         get_available_runtimes()
     }
+
+    /// Functionally probes every runtime type (see [`probe`]), regardless of which is currently
+    /// selected, so callers (e.g. the HTTP server's `/capabilities` endpoint) can report actual —
+    /// not just theoretical, compile-time — availability.
+    pub fn diagnose() -> Vec<RuntimeProbeStatus> {
+        vec![probe(RuntimeType::Molt), probe(RuntimeType::Tcl)]
+    }
+
+    /// Machine-readable capability report for every runtime type this binary knows about
+    /// (whether or not it's compiled in), backing `--list-runtimes`. Unlike [`Self::diagnose`]
+    /// this also surfaces each available runtime's [`RuntimeInfo::version`]/`features`/`is_safe`,
+    /// so a caller can make a capability-based choice (e.g. "only use a runtime advertising
+    /// `no_file_io`") instead of hard-coding a runtime name.
+    pub fn capability_report() -> Vec<RuntimeCapabilityReport> {
+        [RuntimeType::Molt, RuntimeType::Tcl]
+            .into_iter()
+            .map(RuntimeCapabilityReport::for_type)
+            .collect()
+    }
+}
+
+/// One runtime's entry in the `--list-runtimes` report.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RuntimeCapabilityReport {
+    pub name: &'static str,
+    pub available: bool,
+    pub version: Option<&'static str>,
+    pub safe: Option<bool>,
+    pub features: Vec<String>,
+    /// Set when `available` is true but constructing the runtime still failed (mirrors
+    /// [`RuntimeProbeStatus::error`]); `features`/`version`/`safe` are left at their defaults
+    /// in that case.
+    pub error: Option<String>,
+}
+
+impl RuntimeCapabilityReport {
+    fn for_type(runtime_type: RuntimeType) -> Self {
+        if !runtime_type.is_available() {
+            return Self {
+                name: runtime_type.as_str(),
+                available: false,
+                version: None,
+                safe: None,
+                features: Vec::new(),
+                error: Some("not compiled in".to_string()),
+            };
+        }
+
+        match create_specific_runtime(runtime_type.clone()) {
+            Ok(runtime) => Self {
+                name: runtime.name(),
+                available: true,
+                version: Some(runtime.version()),
+                safe: Some(runtime.is_safe()),
+                features: runtime.features(),
+                error: None,
+            },
+            Err(e) => Self {
+                name: runtime_type.as_str(),
+                available: true,
+                version: None,
+                safe: None,
+                features: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        }
+    }
 }
 
 /// Create runtime from environment and CLI arguments
@@ -1,52 +1,85 @@
 use anyhow::{Result, anyhow};
 use molt::Interp;
-use super::TclRuntime;
+use std::time::Duration;
+use super::{Evaluator, VariableStore, Introspector, RuntimeInfo, TypedValues};
 
 /// Molt TCL interpreter implementation
 pub struct MoltRuntime {
     interp: Interp,
 }
 
-impl TclRuntime for MoltRuntime {
-    fn new() -> Self {
+impl MoltRuntime {
+    pub fn new() -> Self {
         Self {
             interp: Interp::new(),
         }
     }
-    
+}
+
+/// Conservative floor used to turn an `eval_bounded` wall-clock limit into a Molt command-count
+/// budget (see `MoltRuntime::eval_bounded`). Molt checks this counter between every command it
+/// executes regardless of how deeply nested, so `while 1 {}` trips it just as reliably as a tight
+/// recursive `proc`.
+const COMMANDS_PER_MILLISECOND: u64 = 50_000;
+
+impl Evaluator for MoltRuntime {
     fn eval(&mut self, script: &str) -> Result<String> {
         match self.interp.eval(script) {
             Ok(value) => Ok(value.to_string()),
             Err(error) => Err(anyhow!("Molt execution error: {:?}", error)),
         }
     }
-    
+
+    /// Cooperative override: Molt aborts evaluation on its own once `set_command_limit` is
+    /// exceeded, so — unlike the default `Evaluator::eval_bounded`, which abandons a thread and
+    /// requires discarding the runtime afterward — `self` stays safe to reuse whether or not this
+    /// call times out. `limit` is converted to a command count with `COMMANDS_PER_MILLISECOND`,
+    /// which trades wall-clock precision for that guarantee.
+    fn eval_bounded(&mut self, script: &str, limit: Duration) -> Result<String> {
+        let budget = (limit.as_millis() as u64)
+            .saturating_mul(COMMANDS_PER_MILLISECOND)
+            .max(1);
+        self.interp.set_command_limit(Some(budget));
+        let result = self.interp.eval(script);
+        self.interp.set_command_limit(None);
+        match result {
+            Ok(value) => Ok(value.to_string()),
+            Err(error) => Err(anyhow!("Molt execution error: {:?}", error)),
+        }
+    }
+}
+
+impl VariableStore for MoltRuntime {
     fn set_var(&mut self, name: &str, value: &str) -> Result<()> {
         match self.interp.set_scalar(name, molt::Value::from(value)) {
             Ok(_) => Ok(()),
             Err(error) => Err(anyhow!("Failed to set variable '{}': {:?}", name, error)),
         }
     }
-    
+
     fn get_var(&self, name: &str) -> Result<String> {
         match self.interp.scalar(name) {
             Ok(value) => Ok(value.to_string()),
             Err(error) => Err(anyhow!("Failed to get variable '{}': {:?}", name, error)),
         }
     }
-    
+}
+
+impl Introspector for MoltRuntime {
     fn has_command(&self, command: &str) -> bool {
         self.interp.has_command(command)
     }
-    
+}
+
+impl RuntimeInfo for MoltRuntime {
     fn name(&self) -> &'static str {
         "Molt"
     }
-    
+
     fn version(&self) -> &'static str {
         "0.3.1" // Molt version
     }
-    
+
     fn features(&self) -> Vec<String> {
         vec![
             "safe_subset".to_string(),
@@ -59,7 +92,7 @@ impl TclRuntime for MoltRuntime {
             "procedures".to_string(),
         ]
     }
-    
+
     fn is_safe(&self) -> bool {
         true
     }
@@ -99,4 +132,46 @@ mod tests {
         let result = runtime.eval("string length $text").unwrap();
         assert_eq!(result, "5");
     }
+
+    #[test]
+    fn test_molt_runtime_eval_bounded_under_budget() {
+        let mut runtime = MoltRuntime::new();
+        let result = runtime.eval_bounded("expr {2 + 2}", std::time::Duration::from_millis(100)).unwrap();
+        assert_eq!(result, "4");
+    }
+
+    #[test]
+    fn test_molt_runtime_eval_bounded_aborts_runaway_script() {
+        let mut runtime = MoltRuntime::new();
+        let result = runtime.eval_bounded(
+            "for {set i 0} {1} {incr i} {}",
+            std::time::Duration::from_millis(1),
+        );
+        assert!(result.is_err());
+
+        // The interpreter itself gave up, so it's still safe to reuse.
+        let result = runtime.eval_bounded("expr {1 + 1}", std::time::Duration::from_millis(100)).unwrap();
+        assert_eq!(result, "2");
+    }
+
+    #[test]
+    fn test_molt_runtime_eval_typed_guesses_an_int_from_the_string_result() {
+        use super::super::TclValue;
+
+        let mut runtime = MoltRuntime::new();
+        // Molt's `Value` carries no type tag of its own, so `eval_typed`'s default impl falls
+        // back to parsing the string `eval` returns.
+        let result = runtime.eval_typed("expr {2 + 2}").unwrap();
+        assert_eq!(result, TclValue::Int(4));
+    }
+
+    #[test]
+    fn test_molt_runtime_get_var_typed_falls_back_to_str_for_non_numeric_values() {
+        use super::super::TclValue;
+
+        let mut runtime = MoltRuntime::new();
+        runtime.set_var("name", "hello").unwrap();
+        let result = runtime.get_var_typed("name").unwrap();
+        assert_eq!(result, TclValue::Str("hello".to_string()));
+    }
 }
\ No newline at end of file
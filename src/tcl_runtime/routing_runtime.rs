@@ -0,0 +1,179 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use super::{Evaluator, VariableStore, Introspector, RuntimeInfo};
+
+/// A runtime that evaluates most scripts against a safe `primary` backend (typically Molt) but
+/// routes an allowlisted set of command names to a `secondary`, full-featured backend (typically
+/// the official TCL interpreter), so operators can expose sandboxed execution broadly while
+/// trusting a handful of commands (e.g. `file`, `exec`) to the unsandboxed interpreter.
+///
+/// Modeled on the same "runtime assembled from replacement parts" idea as [`super::CompoundRuntime`],
+/// but routes by command name at `eval` time instead of by capability.
+pub struct RoutingRuntime<P, S> {
+    primary: P,
+    secondary: Option<S>,
+    routed_commands: HashSet<String>,
+}
+
+impl<P, S> RoutingRuntime<P, S> {
+    /// `routed_commands` names commands whose scripts should go to `secondary` rather than
+    /// `primary`. Ignored (everything stays on `primary`) when `secondary` is `None`.
+    pub fn new(primary: P, secondary: Option<S>, routed_commands: Vec<String>) -> Self {
+        Self {
+            primary,
+            secondary,
+            routed_commands: routed_commands.into_iter().collect(),
+        }
+    }
+
+    /// The first whitespace-delimited token of `script`, i.e. the command it invokes. Only the
+    /// script's leading command is considered — routing does not re-evaluate per statement for
+    /// scripts that chain several commands with `;` or newlines.
+    fn leading_command(script: &str) -> &str {
+        script.trim_start().split_whitespace().next().unwrap_or("")
+    }
+}
+
+impl<P: Evaluator, S: Evaluator> Evaluator for RoutingRuntime<P, S> {
+    fn eval(&mut self, script: &str) -> Result<String> {
+        if let Some(secondary) = self.secondary.as_mut() {
+            if self.routed_commands.contains(Self::leading_command(script)) {
+                return secondary.eval(script);
+            }
+        }
+        self.primary.eval(script)
+    }
+}
+
+impl<P: VariableStore, S> VariableStore for RoutingRuntime<P, S> {
+    fn set_var(&mut self, name: &str, value: &str) -> Result<()> {
+        self.primary.set_var(name, value)
+    }
+
+    fn get_var(&self, name: &str) -> Result<String> {
+        self.primary.get_var(name)
+    }
+}
+
+impl<P: Introspector, S: Introspector> Introspector for RoutingRuntime<P, S> {
+    fn has_command(&self, command: &str) -> bool {
+        self.primary.has_command(command)
+            || self.secondary.as_ref().map(|s| s.has_command(command)).unwrap_or(false)
+    }
+}
+
+impl<P: RuntimeInfo, S: RuntimeInfo> RuntimeInfo for RoutingRuntime<P, S> {
+    fn name(&self) -> &'static str {
+        "Routing"
+    }
+
+    fn version(&self) -> &'static str {
+        self.primary.version()
+    }
+
+    fn features(&self) -> Vec<String> {
+        let mut features = self.primary.features();
+        if let Some(secondary) = self.secondary.as_ref() {
+            for feature in secondary.features() {
+                if !features.contains(&feature) {
+                    features.push(feature);
+                }
+            }
+        }
+        features
+    }
+
+    fn is_safe(&self) -> bool {
+        self.secondary.is_none() && self.primary.is_safe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeBackend {
+        name: &'static str,
+        commands: &'static [&'static str],
+    }
+
+    impl Evaluator for FakeBackend {
+        fn eval(&mut self, _script: &str) -> Result<String> {
+            Ok(self.name.to_string())
+        }
+    }
+
+    impl VariableStore for FakeBackend {
+        fn set_var(&mut self, _name: &str, _value: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn get_var(&self, _name: &str) -> Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    impl Introspector for FakeBackend {
+        fn has_command(&self, command: &str) -> bool {
+            self.commands.contains(&command)
+        }
+    }
+
+    impl RuntimeInfo for FakeBackend {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn version(&self) -> &'static str {
+            "0.0"
+        }
+
+        fn features(&self) -> Vec<String> {
+            self.commands.iter().map(|c| c.to_string()).collect()
+        }
+
+        fn is_safe(&self) -> bool {
+            self.name == "primary"
+        }
+    }
+
+    fn routing() -> RoutingRuntime<FakeBackend, FakeBackend> {
+        let primary = FakeBackend { name: "primary", commands: &["set", "expr"] };
+        let secondary = FakeBackend { name: "secondary", commands: &["file", "exec"] };
+        RoutingRuntime::new(primary, Some(secondary), vec!["file".to_string(), "exec".to_string()])
+    }
+
+    #[test]
+    fn test_routes_allowlisted_command_to_secondary() {
+        let mut runtime = routing();
+        assert_eq!(runtime.eval("file exists /tmp").unwrap(), "secondary");
+    }
+
+    #[test]
+    fn test_routes_everything_else_to_primary() {
+        let mut runtime = routing();
+        assert_eq!(runtime.eval("expr {1 + 1}").unwrap(), "primary");
+    }
+
+    #[test]
+    fn test_has_command_checks_both_backends() {
+        let runtime = routing();
+        assert!(runtime.has_command("set"));
+        assert!(runtime.has_command("file"));
+        assert!(!runtime.has_command("nonexistent"));
+    }
+
+    #[test]
+    fn test_without_secondary_everything_stays_on_primary() {
+        let primary = FakeBackend { name: "primary", commands: &["set"] };
+        let mut runtime = RoutingRuntime::new(primary, None::<FakeBackend>, vec!["file".to_string()]);
+        assert_eq!(runtime.eval("file exists /tmp").unwrap(), "primary");
+        assert!(runtime.is_safe());
+    }
+
+    #[test]
+    fn test_is_safe_requires_no_secondary() {
+        let runtime = routing();
+        assert!(!runtime.is_safe());
+    }
+}
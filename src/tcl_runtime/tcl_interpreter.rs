@@ -1,5 +1,5 @@
 use anyhow::{Result, anyhow};
-use super::TclRuntime;
+use super::{Evaluator, VariableStore, Introspector, RuntimeInfo, TclValue, TypedValues};
 
 /// Official TCL interpreter implementation using the tcl crate
 #[cfg(feature = "tcl")]
@@ -9,33 +9,98 @@ pub struct TclInterpreter {
 
 
 #[cfg(feature = "tcl")]
-impl TclRuntime for TclInterpreter {
-    fn new() -> Self {
+impl TclInterpreter {
+    pub fn new() -> Self {
         Self {
             interp: tcl::Interpreter::new().expect("Failed to create TCL interpreter"),
         }
     }
-    
+}
+
+#[cfg(feature = "tcl")]
+impl Evaluator for TclInterpreter {
     fn eval(&mut self, script: &str) -> Result<String> {
         match self.interp.eval(script) {
             Ok(result) => Ok(result.to_string()),
             Err(err) => Err(anyhow!("TCL execution error: {}", err)),
         }
     }
-    
+}
+
+#[cfg(feature = "tcl")]
+impl VariableStore for TclInterpreter {
     fn set_var(&mut self, name: &str, value: &str) -> Result<()> {
         let _result = self.interp.set(name, value);
         // TCL set always succeeds unless there's a serious error
         Ok(())
     }
-    
+
     fn get_var(&self, name: &str) -> Result<String> {
         match self.interp.get(name) {
             Ok(value) => Ok(value.to_string()),
             Err(err) => Err(anyhow!("Failed to get variable '{}': {}", name, err)),
         }
     }
-    
+}
+
+#[cfg(feature = "tcl")]
+impl TypedValues for TclInterpreter {
+    /// Converts `value` to a `tcl::Obj` of the matching native type and sets it directly, rather
+    /// than going through `set_var`'s `to_tcl_string()` round-trip — a `TclValue::List` still goes
+    /// through its TCL string form, since building a proper TCL list `Obj` element-by-element
+    /// isn't worth it for what's otherwise a write-only conversion.
+    fn set_var_typed(&mut self, name: &str, value: TclValue) -> Result<()> {
+        let result = match value {
+            TclValue::Int(i) => self.interp.set(name, tcl::Obj::from(i as i32)),
+            TclValue::Float(f) => self.interp.set(name, tcl::Obj::from(f)),
+            TclValue::Bool(b) => self.interp.set(name, tcl::Obj::from(b)),
+            TclValue::Str(s) => self.interp.set(name, tcl::Obj::from(s.as_str())),
+            TclValue::List(_) => self.interp.set(name, tcl::Obj::from(value.to_tcl_string().as_str())),
+        };
+        let _ = result;
+        // TCL set always succeeds unless there's a serious error (see `VariableStore::set_var`).
+        Ok(())
+    }
+
+    /// Reads the variable's `Obj` and classifies it by trying each native conversion in turn —
+    /// `i32::try_from`, then `as_f64`, then `as_bool` — rather than guessing from a string, since
+    /// the `tcl` crate's `Obj` knows its own internal representation.
+    fn get_var_typed(&self, name: &str) -> Result<TclValue> {
+        let obj = self
+            .interp
+            .get(name)
+            .map_err(|err| anyhow!("Failed to get variable '{}': {}", name, err))?;
+        Ok(classify_obj(&obj))
+    }
+
+    fn eval_typed(&mut self, script: &str) -> Result<TclValue> {
+        let obj = self
+            .interp
+            .eval(script)
+            .map_err(|err| anyhow!("TCL execution error: {}", err))?;
+        Ok(classify_obj(&obj))
+    }
+}
+
+/// Classifies a `tcl::Obj` into a [`TclValue`] by trying each native conversion in the order
+/// `i32` → `f64` → `bool`, falling back to its string form — mirrors the precedence a TCL
+/// script's own `string is` checks would use, so e.g. `"42"` comes back as `Int`, not `Bool`.
+#[cfg(feature = "tcl")]
+fn classify_obj(obj: &tcl::Obj) -> TclValue {
+    if let Ok(i) = i32::try_from(obj) {
+        return TclValue::Int(i as i64);
+    }
+    if let Ok(f) = obj.as_f64() {
+        return TclValue::Float(f);
+    }
+    if let Ok(b) = obj.as_bool() {
+        return TclValue::Bool(b);
+    }
+    TclValue::Str(obj.to_string())
+}
+
+#[cfg(feature = "tcl")]
+impl Introspector for TclInterpreter {
     fn has_command(&self, command: &str) -> bool {
         // Check if command exists by trying to get its info
         let check_cmd = format!("info commands {}", command);
@@ -43,18 +108,23 @@ impl TclRuntime for TclInterpreter {
             .map(|result| !result.to_string().is_empty())
             .unwrap_or(false)
     }
-    
+}
+
+#[cfg(feature = "tcl")]
+impl RuntimeInfo for TclInterpreter {
     fn name(&self) -> &'static str {
         "TCL (Official)"
     }
-    
+
     fn version(&self) -> &'static str {
-        "8.6"
+        // Set by `build.rs`'s `tclConfig.sh` discovery; falls back to the last-known-good
+        // version if the build somehow skipped discovery (e.g. an out-of-tree vendored build).
+        option_env!("TCL_MCP_DISCOVERED_TCL_VERSION").unwrap_or("8.6")
     }
-    
+
     fn features(&self) -> Vec<String> {
         vec![
-            "full_tcl_8_6".to_string(),
+            format!("full_tcl_{}", self.version().replace('.', "_")),
             "file_operations".to_string(),
             "networking".to_string(),
             "regex".to_string(),
@@ -64,7 +134,7 @@ impl TclRuntime for TclInterpreter {
             "native_performance".to_string(),
         ]
     }
-    
+
     fn is_safe(&self) -> bool {
         false // Full TCL has access to file system, exec, etc.
     }
@@ -114,4 +184,19 @@ mod tests {
         let result = runtime.eval("string length $text").unwrap();
         assert_eq!(result, "5");
     }
+
+    #[test]
+    fn test_tcl_runtime_eval_typed_returns_an_int_not_a_string() {
+        let mut runtime = TclInterpreter::new();
+        let result = runtime.eval_typed("expr {2 + 2}").unwrap();
+        assert_eq!(result, TclValue::Int(4));
+    }
+
+    #[test]
+    fn test_tcl_runtime_set_and_get_var_typed_round_trips_a_float() {
+        let mut runtime = TclInterpreter::new();
+        runtime.set_var_typed("pi", TclValue::Float(3.5)).unwrap();
+        let result = runtime.get_var_typed("pi").unwrap();
+        assert_eq!(result, TclValue::Float(3.5));
+    }
 }
\ No newline at end of file
@@ -1,31 +1,119 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use tokio::sync::{mpsc, oneshot};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, Semaphore};
 use tracing::info;
 
-use crate::tcl_executor::TclCommand;
+use crate::tcl_executor::{TclCommand, TclExecutorPool, EffectiveCapabilities};
 
 use crate::namespace::ToolPath;
+use crate::permissions::{self, Permission, Principal};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub struct ToolDefinition {
     pub path: ToolPath,
     pub description: String,
     pub script: String,
     pub parameters: Vec<ParameterDefinition>,
+    /// JSON-encoded `Vec<ToolTestCase>` attached at `AddTool` time, or `""` if none were given.
+    /// Kept as text (like `script`) rather than a typed field: `ToolTestCase::params` is an
+    /// untyped `serde_json::Value`, which doesn't implement `rkyv::Archive`, so it can't sit
+    /// directly in a struct this one derives `rkyv::Archive` for. See `encode_test_cases`/
+    /// `decode_test_cases`.
+    #[serde(default)]
+    pub test_cases: String,
+}
+
+/// One test case attached to a custom tool, exercised by `TclCommand::TestTool` /
+/// `TclToolBox::tcl_tool_test` the same way a `tools/call` would invoke the tool itself: `params`
+/// is bound exactly like a real call's arguments, and the resulting output (or error) is compared
+/// against `expect_output`/`expect_error`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolTestCase {
+    pub name: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// Expected to equal the tool's returned output exactly, if given.
+    #[serde(default)]
+    pub expect_output: Option<String>,
+    /// Expected to be a substring of the error message, if the call is expected to fail.
+    #[serde(default)]
+    pub expect_error: Option<String>,
+}
+
+/// Serializes `test_cases` for storage on `ToolDefinition::test_cases`. An empty list encodes to
+/// `""` rather than `"[]"`, so a tool added with no test cases round-trips to the same empty
+/// string `ToolDefinition`'s `#[serde(default)]` already produces for receipts written before
+/// this field existed.
+pub fn encode_test_cases(test_cases: &[ToolTestCase]) -> String {
+    if test_cases.is_empty() {
+        return String::new();
+    }
+    serde_json::to_string(test_cases).unwrap_or_default()
+}
+
+/// Inverse of `encode_test_cases`; an empty or malformed string decodes to no test cases rather
+/// than failing the caller, since a missing/corrupt test suite shouldn't block running the tool.
+pub fn decode_test_cases(encoded: &str) -> Vec<ToolTestCase> {
+    if encoded.is_empty() {
+        return Vec::new();
+    }
+    serde_json::from_str(encoded).unwrap_or_default()
+}
+
+/// The outcome of running one `ToolTestCase`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolTestCaseResult {
+    pub name: String,
+    pub ok: bool,
+    /// The tool's actual output, or its error message if the call failed.
+    pub actual: String,
+    /// What `actual` was checked against (`expect_output` or `expect_error`), if anything.
+    #[serde(default)]
+    pub expected: Option<String>,
 }
 
+/// Report returned by `TclToolBox::tcl_tool_test`, covering every case run (after `filter` is
+/// applied), in the tool's definition order.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolTestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub cases: Vec<ToolTestCaseResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub struct ParameterDefinition {
     pub name: String,
     pub description: String,
     pub required: bool,
     pub type_name: String,
+    /// Value injected (in the same textual form `bind_params_script` would receive a provided
+    /// argument) when this optional parameter is omitted.
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Allowed values; a provided argument outside this set is rejected. Checked against the
+    /// argument's textual form, the same way `default` is injected.
+    #[serde(default, rename = "enum")]
+    pub enum_values: Option<Vec<String>>,
+    /// Inclusive lower bound. When `min` or `max` is set, a provided value that isn't a JSON
+    /// number is rejected.
+    #[serde(default)]
+    pub min: Option<f64>,
+    /// Inclusive upper bound; see `min` for non-numeric values.
+    #[serde(default)]
+    pub max: Option<f64>,
+    /// Regex a provided string value must match.
+    #[serde(default)]
+    pub validate: Option<String>,
 }
 
 #[derive(Clone)]
 pub struct TclToolBox {
-    executor: mpsc::Sender<TclCommand>,
+    pool: TclExecutorPool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,18 +140,69 @@ pub struct TclToolAddRequest {
     /// Parameters that the tool accepts
     #[serde(default)]
     pub parameters: Vec<ParameterDefinition>,
+    /// If a tool already exists at this path, replace it instead of failing. The stored receipt
+    /// is only bumped if the script actually changed (see `FilePersistence::upsert_tool`).
+    #[serde(default)]
+    pub overwrite: bool,
+    /// Test cases to attach, runnable later via `TclToolBox::tcl_tool_test`.
+    #[serde(default)]
+    pub test_cases: Vec<ToolTestCase>,
 }
 
 fn default_version() -> String {
     "latest".to_string()
 }
 
+/// The principal name to use for the unrestricted `tcl_tool_remove` fallback: the owning user
+/// for a `Namespace::User` path, otherwise a placeholder (system namespaces never check `name`).
+fn path_owner(path: &ToolPath) -> &str {
+    match &path.namespace {
+        crate::namespace::Namespace::User(user) => user,
+        _ => "system",
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TclToolRemoveRequest {
     /// Full tool path (e.g., "/alice/utils/reverse_string:1.0")
     pub path: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclToolReceiptRequest {
+    /// Full tool path (e.g., "/alice/utils/reverse_string:1.0")
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclToolTestRequest {
+    /// Full tool path whose attached test cases should be run (e.g., "/alice/utils/reverse:1.0")
+    pub path: String,
+    /// Only run cases whose name contains this substring (optional)
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclToolTrustRequest {
+    /// Full tool path of a filesystem-discovered tool (e.g., "/alice/utils/reverse_string:1.0")
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclToolRevokeRequest {
+    /// Full tool path whose trust approval should be withdrawn
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TclDiscoverToolsRequest {
+    /// Bypasses the persisted discovery cache, re-reading every tool file even if its mtime
+    /// hasn't changed since the last scan.
+    #[serde(default)]
+    pub force: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TclToolListRequest {
     /// Filter tools by namespace (optional)
@@ -76,127 +215,583 @@ pub struct TclToolListRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TclExecToolRequest {
-    /// Tool path to execute (e.g., "/bin/list_dir")
+    /// Tool path to execute (e.g., "/bin/list_dir"). The version after `:` may be an exact
+    /// version, a semver requirement ("^1.2", "~1.0", ">=1.0,<2.0"), or "latest" — see
+    /// `version_resolver`.
     pub tool_path: String,
     /// Parameters to pass to the tool
     #[serde(default)]
     pub params: serde_json::Value,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclToolCoverageRequest {
+    /// Tool path to run with coverage instrumentation (e.g., "/alice/utils/reverse:1.0")
+    pub tool_path: String,
+    /// Parameters to pass to the tool
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// Line-coverage result of one `TclToolBox::tcl_tool_coverage` run, as reported by
+/// `TclCommand::ExecuteCustomToolWithCoverage`. `total_lines`/`covered_lines` count only the
+/// script's own top-level statement lines — see `instrument_for_coverage` in `tcl_executor` — so
+/// the instrumentation calls injected to collect them are never counted themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub total_lines: usize,
+    pub covered_lines: BTreeSet<u32>,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclExecBatchRequest {
+    /// Tools to invoke concurrently; results are returned in this same order
+    pub entries: Vec<TclExecToolRequest>,
+    /// Per-entry timeout in milliseconds (defaults to 30s)
+    #[serde(default = "default_batch_entry_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_batch_entry_timeout_ms() -> u64 {
+    30_000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclBatchEntryResult {
+    pub tool_path: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Returns the configured worker pool size for `bin___exec_batch`, defaulting to the number
+/// of logical CPUs (see `TCL_MCP_BATCH_CONCURRENCY`).
+fn batch_concurrency() -> usize {
+    std::env::var("TCL_MCP_BATCH_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or_else(num_cpus::get)
+}
+
+/// Compares a test case's actual call outcome against its `expect_output`/`expect_error`. A case
+/// with neither expectation set just checks the call succeeded.
+fn case_result(case: ToolTestCase, result: Result<String>) -> ToolTestCaseResult {
+    match result {
+        Ok(output) => {
+            let ok = match case.expect_output.as_ref() {
+                Some(expected) => expected == &output,
+                None => case.expect_error.is_none(),
+            };
+            ToolTestCaseResult { name: case.name, ok, actual: output, expected: case.expect_output }
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let ok = case.expect_error.as_ref().map(|expected| message.contains(expected.as_str())).unwrap_or(false);
+            ToolTestCaseResult { name: case.name, ok, actual: message, expected: case.expect_error }
+        }
+    }
+}
+
+/// One stage of a `bin___pipeline` request: the tool to invoke, its static parameters, and the
+/// name of the parameter that should receive the previous stage's output (ignored for the first
+/// stage, which instead receives `TclPipelineRequest::input`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclPipelineStage {
+    /// Tool path to execute (e.g., "/bin/tcl_execute" or "/alice/utils/reverse_string:1.0")
+    pub tool_path: String,
+    /// Static parameters to pass alongside the threaded input
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// Name of the parameter that receives the previous stage's output
+    pub input_param: String,
+}
+
+/// Mirrors tcllib's `pipeline::loop -separate`/`-buffer` switches: whether the value threaded
+/// between stages is kept as one opaque string, or split into TCL list elements that are each
+/// pushed through the remaining stages independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PipelineMode {
+    #[default]
+    Buffer,
+    Separate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclPipelineRequest {
+    /// Initial input fed to the first stage (or, in separate mode, split and fed element-wise)
+    #[serde(default)]
+    pub input: String,
+    /// Ordered stages to run the input through
+    #[serde(default)]
+    pub stages: Vec<TclPipelineStage>,
+    #[serde(default)]
+    pub mode: PipelineMode,
+    /// Script run once, before the first stage, to set up shared state
+    #[serde(default)]
+    pub init: Option<String>,
+}
+
+/// One step of a `TclToolBox::tcl_tool_compose` request: the tool to call and the parameters to
+/// call it with, plus an optional name under which its string output is bound for later steps to
+/// reference (see `TclCompositionStep::params`'s doc and `PipelineStep`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclCompositionStep {
+    /// Tool path to execute (e.g., "/bin/tcl_execute" or "/alice/utils/reverse_string:1.0")
+    pub tool_path: String,
+    /// Parameters to call the tool with. Any string value (at any depth) containing `"${name}"`
+    /// has it substituted with the bound output of an earlier step named `name`, before this
+    /// step's own `ParameterDefinition`s validate the result.
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// Name this step's output is bound under, for later steps' `params` to reference
+    #[serde(default)]
+    pub bind: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TclComposeRequest {
+    /// Ordered steps to run; later steps may reference earlier ones' `bind`s
+    pub steps: Vec<TclCompositionStep>,
+}
+
+/// Executor-internal form of a `TclCompositionStep`, with `tool_path` already resolved to a
+/// `ToolPath` the same way `TclToolBox::execute_custom_tool` resolves an MCP tool name — built by
+/// `TclToolBox::tcl_tool_compose` and carried unchanged through `TclCommand::ExecutePipeline`.
+#[derive(Debug, Clone)]
+pub struct PipelineStep {
+    pub path: ToolPath,
+    pub params: serde_json::Value,
+    pub bind: Option<String>,
+}
+
+/// One step's recorded outcome in a `PipelineExecutionResult`, in execution order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStepResult {
+    pub bind: Option<String>,
+    pub output: String,
+}
+
+/// Result of running `TclCommand::ExecutePipeline`. On success, `failed_step`/`error` are `None`
+/// and `partial_results` holds every step's outcome; if a step errors, `partial_results` holds
+/// only the steps that ran before it, and `failed_step`/`error` say where and why it stopped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineExecutionResult {
+    pub partial_results: Vec<PipelineStepResult>,
+    pub failed_step: Option<usize>,
+    pub error: Option<String>,
+}
+
 impl TclToolBox {
+    /// Wraps a single already-spawned executor channel as a one-worker pool. The stdio and HTTP
+    /// servers build a full pool and use [`Self::with_pool`] instead.
     pub fn new(executor: mpsc::Sender<TclCommand>) -> Self {
-        Self { executor }
+        Self { pool: TclExecutorPool::from_single(executor) }
     }
 
-    pub async fn tcl_execute(&self, request: TclExecuteRequest) -> Result<String> {
-        info!("Executing TCL script: {}", request.script);
-        
+    pub fn with_pool(pool: TclExecutorPool) -> Self {
+        Self { pool }
+    }
+
+    /// Sends a command built for a single worker (round-robin) and awaits its response.
+    async fn send_one<T>(
+        &self,
+        build: impl FnOnce(oneshot::Sender<Result<T>>) -> TclCommand,
+    ) -> Result<T> {
         let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::Execute {
-            script: request.script,
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
+        self.pool.next_sender().send(build(tx)).await
+            .map_err(|_| anyhow!("Failed to send command to executor"))?;
         rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
     }
-    
+
+    /// Sends a command to every worker in the pool so registry-mutating commands (add/remove
+    /// tool, initialize persistence, filesystem discovery) keep every interpreter's tool registry
+    /// consistent. Each worker is awaited in turn before the next is sent, so this also serializes
+    /// the mutation itself — two concurrent `tcl_tool_add` calls can't interleave their writes to
+    /// a single worker's registry. Returns the first worker's response.
+    async fn broadcast(
+        &self,
+        mut build: impl FnMut(oneshot::Sender<Result<String>>) -> TclCommand,
+    ) -> Result<String> {
+        let mut first = None;
+        for sender in self.pool.senders() {
+            let (tx, rx) = oneshot::channel();
+            sender.send(build(tx)).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
+            let result = rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?;
+            if first.is_none() {
+                first = Some(result);
+            }
+        }
+        first.ok_or_else(|| anyhow!("Executor pool is empty"))?
+    }
+
+    pub async fn tcl_execute(&self, request: TclExecuteRequest) -> Result<String> {
+        info!("Executing TCL script: {}", request.script);
+
+        self.send_one(|response| TclCommand::Execute {
+            script: request.script.clone(),
+            response,
+        }).await
+    }
+
+    /// Adds a tool with no permission check, for transports that haven't been wired up to pass a
+    /// [`Principal`] yet. Prefer [`TclToolBox::tcl_tool_add_as`] wherever a caller identity is
+    /// available.
     pub async fn tcl_tool_add(&self, request: TclToolAddRequest) -> Result<String> {
+        self.tcl_tool_add_as(&Principal::unrestricted(&request.user), request).await
+    }
+
+    /// Adds a tool after checking `principal` against the target namespace's ACL (see
+    /// `permissions::check`) — a user principal may only add tools under its own
+    /// `Namespace::User`, and needs `AddTool` granted.
+    pub async fn tcl_tool_add_as(&self, principal: &Principal, request: TclToolAddRequest) -> Result<String> {
         let path = ToolPath::user(&request.user, &request.package, &request.name, &request.version);
+        permissions::check_path(principal, &path, Permission::AddTool)?;
         info!("Adding new TCL tool: {}", path);
-        
-        let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::AddTool {
-            path,
-            description: request.description,
-            script: request.script,
-            parameters: request.parameters,
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
+
+        self.broadcast(|response| TclCommand::AddTool {
+            path: path.clone(),
+            description: request.description.clone(),
+            script: request.script.clone(),
+            parameters: request.parameters.clone(),
+            overwrite: request.overwrite,
+            test_cases: request.test_cases.clone(),
+            response,
+        }).await
     }
-    
+
+    pub async fn tcl_tool_receipt(&self, request: TclToolReceiptRequest) -> Result<String> {
+        let path = ToolPath::parse(&request.path)?;
+        info!("Fetching receipt for TCL tool: {}", path);
+
+        let receipt = self.send_one(|response| TclCommand::GetToolReceipt {
+            path: path.clone(),
+            response,
+        }).await?;
+
+        Ok(serde_json::to_string_pretty(&receipt)?)
+    }
+
+    /// Runs a tool's attached test cases (optionally narrowed by `filter`, matched the same way
+    /// `tcl_tool_list`'s filter is: a plain substring of the case name). Each case is executed
+    /// through the exact same path a real `tools/call` would take (`TclToolBox::execute_custom_tool`
+    /// via its MCP name), fanned out concurrently across the executor pool with `tokio::spawn` —
+    /// mirroring `exec_batch` — then compared against `expect_output`/`expect_error` once every
+    /// case has returned, in the tool's original definition order.
+    pub async fn tcl_tool_test(&self, request: TclToolTestRequest) -> Result<String> {
+        let path = ToolPath::parse(&request.path)?;
+        info!("Running tests for TCL tool: {}", path);
+
+        let cases = self.send_one(|response| TclCommand::TestTool {
+            path: path.clone(),
+            filter: request.filter.clone(),
+            response,
+        }).await?;
+
+        let mcp_name = path.to_mcp_name();
+        let handles: Vec<_> = cases.into_iter().map(|case| {
+            let tool_box = self.clone();
+            let mcp_name = mcp_name.clone();
+            tokio::spawn(async move {
+                let result = tool_box.execute_custom_tool(&mcp_name, case.params.clone()).await;
+                case_result(case, result)
+            })
+        }).collect();
+
+        let mut cases = Vec::with_capacity(handles.len());
+        for handle in handles {
+            cases.push(handle.await.map_err(|e| anyhow!("Test case task panicked: {e}"))?);
+        }
+
+        let passed = cases.iter().filter(|c| c.ok).count();
+        let failed = cases.len() - passed;
+        let report = ToolTestReport { passed, failed, cases };
+
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
+    /// Removes a tool with no permission check; see [`TclToolBox::tcl_tool_add`] for why this
+    /// exists alongside [`TclToolBox::tcl_tool_remove_as`].
     pub async fn tcl_tool_remove(&self, request: TclToolRemoveRequest) -> Result<String> {
         let path = ToolPath::parse(&request.path)?;
+        self.tcl_tool_remove_as(&Principal::unrestricted(path_owner(&path)), request).await
+    }
+
+    /// Removes a tool after checking `principal` against the target namespace's ACL; see
+    /// `permissions::check`.
+    pub async fn tcl_tool_remove_as(&self, principal: &Principal, request: TclToolRemoveRequest) -> Result<String> {
+        let path = ToolPath::parse(&request.path)?;
+        permissions::check_path(principal, &path, Permission::RemoveTool)?;
         info!("Removing TCL tool: {}", path);
-        
-        let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::RemoveTool {
-            path,
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
+
+        self.broadcast(|response| TclCommand::RemoveTool {
+            path: path.clone(),
+            response,
+        }).await
     }
-    
+
+    /// Approves a filesystem-discovered tool at its currently-indexed content hash (see
+    /// `crate::trust`). Broadcast to every worker so each pool member's in-memory trust store
+    /// stays consistent, the same way `tcl_tool_add`/`tcl_tool_remove` keep `custom_tools` in sync.
+    pub async fn tcl_tool_trust(&self, request: TclToolTrustRequest) -> Result<String> {
+        let path = ToolPath::parse(&request.path)?;
+        info!("Trusting TCL tool: {}", path);
+
+        self.broadcast(|response| TclCommand::TrustTool {
+            path: path.clone(),
+            response,
+        }).await
+    }
+
+    /// Withdraws a tool's trust approval, if any (see `crate::trust`).
+    pub async fn tcl_tool_revoke(&self, request: TclToolRevokeRequest) -> Result<String> {
+        let path = ToolPath::parse(&request.path)?;
+        info!("Revoking trust for TCL tool: {}", path);
+
+        self.broadcast(|response| TclCommand::RevokeTool {
+            path: path.clone(),
+            response,
+        }).await
+    }
+
     pub async fn tcl_tool_list(&self, request: TclToolListRequest) -> Result<String> {
         info!("Listing TCL tools with namespace: {:?}, filter: {:?}", request.namespace, request.filter);
-        
-        let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::ListTools {
-            namespace: request.namespace,
-            filter: request.filter,
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        let tools = rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))??;
-        
+
+        let tools: Vec<String> = self.send_one(|response| TclCommand::ListTools {
+            namespace: request.namespace.clone(),
+            filter: request.filter.clone(),
+            response,
+        }).await?;
+
         // Format as JSON with full paths
         Ok(serde_json::to_string_pretty(&tools)?)
     }
-    
+
     pub async fn execute_custom_tool(&self, mcp_name: &str, params: serde_json::Value) -> Result<String> {
         let path = ToolPath::from_mcp_name(mcp_name)?;
-        
-        let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::ExecuteCustomTool {
+
+        self.send_one(|response| TclCommand::ExecuteCustomTool {
             path,
             params,
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
+            response,
+        }).await
     }
-    
+
+    /// Runs a custom tool the same way `exec_tool` does, but instrumented to collect which of its
+    /// script's lines actually executed; see `CoverageReport`. Pre-execution failures (tool not
+    /// found, bad parameters) are returned as a plain `Err`; a TCL error raised by the script
+    /// itself is instead folded into the returned output text so the coverage gathered before the
+    /// error isn't discarded (see `TclExecutor::execute_custom_tool_with_coverage`).
+    pub async fn tcl_tool_coverage(&self, request: TclToolCoverageRequest) -> Result<String> {
+        info!("Running tool with coverage: {}", request.tool_path);
+
+        let path = ToolPath::parse(&request.tool_path)?;
+        let (output, coverage) = self.send_one(|response| TclCommand::ExecuteCustomToolWithCoverage {
+            path,
+            params: request.params.clone(),
+            response,
+        }).await?;
+
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "output": output,
+            "coverage": coverage,
+        }))?)
+    }
+
+    /// Runs a declarative multi-step composition of other tools (see `TclCompositionStep`): each
+    /// step executes through the normal `ExecuteCustomTool` path in order, so its own
+    /// `ParameterDefinition`s still validate its `params` exactly as they would for a standalone
+    /// call, after `"${name}"` in those `params` is substituted with an earlier step's bound
+    /// output. Stops at the first step that errors; see `PipelineExecutionResult`.
+    pub async fn tcl_tool_compose(&self, request: TclComposeRequest) -> Result<String> {
+        info!("Running tool composition with {} step(s)", request.steps.len());
+
+        let steps = request.steps.into_iter()
+            .map(|step| Ok(PipelineStep {
+                path: ToolPath::parse(&step.tool_path)?,
+                params: step.params,
+                bind: step.bind,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+
+        let result = self.send_one(|response| TclCommand::ExecutePipeline {
+            steps,
+            response,
+        }).await?;
+
+        Ok(serde_json::to_string_pretty(&result)?)
+    }
+
     pub async fn get_tool_definitions(&self) -> Result<Vec<ToolDefinition>> {
         let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::GetToolDefinitions {
+        self.pool.next_sender().send(TclCommand::GetToolDefinitions {
             response: tx,
         }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
+
         Ok(rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?)
     }
-    
+
     pub async fn initialize_persistence(&self) -> Result<String> {
-        let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::InitializePersistence {
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
+        self.broadcast(|response| TclCommand::InitializePersistence { response }).await
     }
-    
+
+    /// Stops every pool worker's tool-storage watcher (see `TclCommand::StopWatchingTools`).
+    /// Backs `ToolWatchGuard::stop` and its `Drop` impl, not meant to be called directly.
+    pub(crate) async fn stop_watching_tools(&self) -> Result<String> {
+        self.broadcast(|response| TclCommand::StopWatchingTools { response }).await
+    }
+
     pub async fn exec_tool(&self, request: TclExecToolRequest) -> Result<String> {
         info!("Executing tool: {} with params: {:?}", request.tool_path, request.params);
-        
-        let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::ExecTool {
-            tool_path: request.tool_path,
-            params: request.params,
-            response: tx,
-        }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
+
+        self.send_one(|response| TclCommand::ExecTool {
+            tool_path: request.tool_path.clone(),
+            params: request.params.clone(),
+            response,
+        }).await
     }
     
-    pub async fn discover_tools(&self) -> Result<String> {
-        info!("Discovering tools from filesystem");
-        
+    pub async fn exec_batch(&self, request: TclExecBatchRequest) -> Result<String> {
+        info!("Executing batch of {} tools", request.entries.len());
+
+        let semaphore = Arc::new(Semaphore::new(batch_concurrency()));
+        let timeout = std::time::Duration::from_millis(request.timeout_ms);
+
+        let handles: Vec<_> = request.entries.into_iter().map(|entry| {
+            let tool_box = self.clone();
+            let semaphore = semaphore.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                let tool_path = entry.tool_path.clone();
+                match tokio::time::timeout(timeout, tool_box.exec_tool(entry)).await {
+                    Ok(Ok(result)) => TclBatchEntryResult {
+                        tool_path,
+                        success: true,
+                        result: Some(result),
+                        error: None,
+                    },
+                    Ok(Err(e)) => TclBatchEntryResult {
+                        tool_path,
+                        success: false,
+                        result: None,
+                        error: Some(e.to_string()),
+                    },
+                    Err(_) => TclBatchEntryResult {
+                        tool_path,
+                        success: false,
+                        result: None,
+                        error: Some(format!("Timed out after {}ms", timeout.as_millis())),
+                    },
+                }
+            })
+        }).collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            results.push(handle.await.map_err(|e| anyhow!("Batch task panicked: {e}"))?);
+        }
+
+        Ok(serde_json::to_string_pretty(&results)?)
+    }
+
+    /// Composes existing tools into a data pipeline, the way tcllib's `pipeline::loop` chains
+    /// filters: each stage's result is threaded into the next stage's `input_param`, via the same
+    /// `exec_tool` path a standalone call would use. Stages are validated against the tool
+    /// registry before any of them run, so a pipeline referencing a missing tool fails fast
+    /// instead of after partway executing (and possibly side-effecting) earlier stages.
+    pub async fn pipeline(&self, request: TclPipelineRequest) -> Result<String> {
+        info!("Running pipeline of {} stage(s) in {:?} mode", request.stages.len(), request.mode);
+
+        if let Some(init) = &request.init {
+            self.tcl_execute(TclExecuteRequest { script: init.clone() }).await?;
+        }
+
+        if request.stages.is_empty() {
+            return Ok(request.input);
+        }
+
+        let known_tools: std::collections::HashSet<String> = self.send_one(|response| TclCommand::ListTools {
+            namespace: None,
+            filter: None,
+            response,
+        }).await?.into_iter().collect();
+
+        for stage in &request.stages {
+            if !known_tools.contains(&stage.tool_path) {
+                return Err(anyhow!("Pipeline stage references unknown tool '{}'", stage.tool_path));
+            }
+        }
+
+        match request.mode {
+            PipelineMode::Buffer => {
+                let mut value = request.input;
+                for stage in &request.stages {
+                    value = self.run_pipeline_stage(stage, value).await?;
+                }
+                Ok(value)
+            }
+            PipelineMode::Separate => {
+                let elements = self.send_one(|response| TclCommand::SplitList {
+                    value: request.input.clone(),
+                    response,
+                }).await?;
+
+                // Each element runs through every stage independently, in order, so the
+                // collected results line up with the input elements despite running sequentially
+                // rather than genuinely concurrently.
+                let mut results = Vec::with_capacity(elements.len());
+                for element in elements {
+                    let mut value = element;
+                    for stage in &request.stages {
+                        value = self.run_pipeline_stage(stage, value).await?;
+                    }
+                    results.push(value);
+                }
+
+                self.send_one(|response| TclCommand::JoinList {
+                    values: results,
+                    response,
+                }).await
+            }
+        }
+    }
+
+    /// Runs a single `TclPipelineRequest` stage, binding `input` to `stage.input_param` alongside
+    /// the stage's static `params`.
+    async fn run_pipeline_stage(&self, stage: &TclPipelineStage, input: String) -> Result<String> {
+        let mut params = stage.params.clone();
+        if !params.is_object() {
+            params = serde_json::json!({});
+        }
+        params[stage.input_param.clone()] = serde_json::Value::String(input);
+
+        self.exec_tool(TclExecToolRequest {
+            tool_path: stage.tool_path.clone(),
+            params,
+        }).await
+    }
+
+    /// Effective capability set of the worker that handles the next round-robin command, for
+    /// callers (and the MCP `tcl/capabilities` method) that want to advertise which commands and
+    /// tools are actually runnable rather than assuming every build is privileged.
+    pub async fn capabilities(&self) -> Result<EffectiveCapabilities> {
         let (tx, rx) = oneshot::channel();
-        self.executor.send(TclCommand::DiscoverTools {
+        self.pool.next_sender().send(TclCommand::GetCapabilities {
             response: tx,
         }).await.map_err(|_| anyhow!("Failed to send command to executor"))?;
-        
-        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))?
+
+        rx.await.map_err(|_| anyhow!("Failed to receive response from executor"))
+    }
+
+    pub async fn discover_tools(&self, request: TclDiscoverToolsRequest) -> Result<String> {
+        info!("Discovering tools from filesystem (force={})", request.force);
+
+        // Discovery results feed `discovered_tools`, which `exec_tool` reads on whichever
+        // worker handles the call, so every worker needs to (re-)scan.
+        self.broadcast(|response| TclCommand::DiscoverTools { force: request.force, response }).await
     }
 }
\ No newline at end of file
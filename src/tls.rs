@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use std::io::Read;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tower::ServiceBuilder;
+
+/// Paths to a PEM certificate chain and private key, as read from
+/// `TCL_MCP_TLS_CERT`/`TCL_MCP_TLS_KEY`. Either may be the literal `-` to read from stdin
+/// instead of a file, for containerized setups that mount secrets as a stream.
+pub struct TlsPaths {
+    pub cert_path: String,
+    pub key_path: String,
+    /// When set, the server additionally requires (or accepts) a client certificate per
+    /// [`ClientCertAuthPaths`], layering mTLS on top of the cert/key above.
+    pub client_cert_auth: Option<ClientCertAuthPaths>,
+}
+
+/// Configuration for verifying client certificates, as read from `TCL_MCP_MTLS_CA_CERT` (and
+/// optionally `TCL_MCP_MTLS_CRL` / `TCL_MCP_MTLS_REQUIRE`). Like [`TlsPaths`], paths may be `-`
+/// to read from stdin.
+pub struct ClientCertAuthPaths {
+    pub ca_cert_path: String,
+    pub crl_path: Option<String>,
+    /// When `true` (the default), clients that don't present a valid certificate are rejected
+    /// at the TLS handshake. When `false`, mTLS is accepted but not mandatory, so unauthenticated
+    /// clients can still fall back to the Bearer/API-key path.
+    pub require: bool,
+}
+
+/// The subject CN of a verified client certificate, attached to a request's extensions by
+/// [`ClientCertAcceptor`] when mTLS is in use. `auth_middleware` treats this as an alternative to
+/// a Bearer token/API key — the TLS handshake already proved possession of the private key, so
+/// the CN is trusted as-is.
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    pub common_name: String,
+}
+
+/// Reads PEM bytes from a file path, or from stdin when `source` is `-`.
+fn read_pem_source(source: &str) -> Result<Vec<u8>> {
+    if source == "-" {
+        let mut buf = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut buf)
+            .context("Failed to read PEM material from stdin")?;
+        Ok(buf)
+    } else {
+        std::fs::read(source).with_context(|| format!("Failed to read PEM file '{source}'"))
+    }
+}
+
+/// Reads the TLS cert/key paths (and, if configured, the mTLS client-auth paths) from the
+/// environment. Returns `Ok(None)` when neither cert nor key is set (plain HTTP) and no mTLS CA
+/// is configured; an error when only one of the pair is present, or when `TCL_MCP_MTLS_CA_CERT`
+/// is set without them.
+pub fn paths_from_env() -> Result<Option<TlsPaths>> {
+    let cert_path = std::env::var("TCL_MCP_TLS_CERT").ok();
+    let key_path = std::env::var("TCL_MCP_TLS_KEY").ok();
+
+    let client_cert_auth = match std::env::var("TCL_MCP_MTLS_CA_CERT").ok().filter(|s| !s.is_empty()) {
+        Some(ca_cert_path) => {
+            let crl_path = std::env::var("TCL_MCP_MTLS_CRL").ok().filter(|s| !s.is_empty());
+            let require = std::env::var("TCL_MCP_MTLS_REQUIRE")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true);
+            Some(ClientCertAuthPaths { ca_cert_path, crl_path, require })
+        }
+        None => None,
+    };
+
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => Ok(Some(TlsPaths { cert_path, key_path, client_cert_auth })),
+        (None, None) => {
+            if client_cert_auth.is_some() {
+                return Err(anyhow::anyhow!(
+                    "TCL_MCP_MTLS_CA_CERT requires TCL_MCP_TLS_CERT and TCL_MCP_TLS_KEY to also be set"
+                ));
+            }
+            Ok(None)
+        }
+        _ => Err(anyhow::anyhow!(
+            "TCL_MCP_TLS_CERT and TCL_MCP_TLS_KEY must both be set to enable HTTPS"
+        )),
+    }
+}
+
+/// Overlays `--tls-cert`/`--tls-key` CLI arguments on top of the environment-derived TLS paths:
+/// CLI wins when given, matching the precedence `RuntimeConfig::from_args_and_env` already uses
+/// for `--runtime`. mTLS client-auth configuration remains environment-only.
+pub fn resolve_tls_paths(
+    cli_cert: Option<&str>,
+    cli_key: Option<&str>,
+) -> Result<Option<TlsPaths>> {
+    let env_paths = paths_from_env()?;
+
+    match (cli_cert, cli_key) {
+        (None, None) => Ok(env_paths),
+        (Some(cert_path), Some(key_path)) => Ok(Some(TlsPaths {
+            cert_path: cert_path.to_string(),
+            key_path: key_path.to_string(),
+            client_cert_auth: env_paths.and_then(|p| p.client_cert_auth),
+        })),
+        _ => Err(anyhow::anyhow!("--tls-cert and --tls-key must both be given to enable HTTPS")),
+    }
+}
+
+/// Generates a throwaway self-signed certificate (and matching key), for `--tls-self-signed` to
+/// turn on encrypted transport for local development without any PKI setup. The certificate isn't
+/// signed by anything a client would trust by default; pair with `curl -k` or an explicitly
+/// trusted CA override during development only.
+#[cfg(feature = "self-signed-tls")]
+pub async fn self_signed_rustls_config(subject_alt_names: Vec<String>) -> Result<RustlsConfig> {
+    let rcgen::CertifiedKey { cert, signing_key } = rcgen::generate_simple_self_signed(subject_alt_names)
+        .context("Failed to generate self-signed certificate")?;
+
+    RustlsConfig::from_pem(cert.pem().into_bytes(), signing_key.serialize_pem().into_bytes())
+        .await
+        .context("Failed to load generated self-signed certificate")
+}
+
+/// Builds a client certificate verifier from `paths`: loads the root CA, parses the optional CRL,
+/// and validates the chain (including notBefore/notAfter) against them. Rustls itself enforces
+/// the validity window and CRL checks on every handshake, so a malformed or expired client
+/// certificate is rejected before any request reaches the application.
+fn build_client_cert_verifier(
+    paths: &ClientCertAuthPaths,
+) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>> {
+    let ca_pem = read_pem_source(&paths.ca_cert_path)?;
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut ca_pem.as_slice()) {
+        roots
+            .add(cert.context("Failed to parse client CA certificate")?)
+            .context("Failed to add client CA certificate to root store")?;
+    }
+
+    let mut builder = rustls::server::WebPkiClientVerifier::builder(Arc::new(roots));
+
+    if let Some(crl_path) = &paths.crl_path {
+        let crl_pem = read_pem_source(crl_path)?;
+        let crls = rustls_pemfile::crls(&mut crl_pem.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to parse client certificate revocation list")?;
+        builder = builder.with_crls(crls);
+    }
+
+    if !paths.require {
+        builder = builder.allow_unauthenticated();
+    }
+
+    builder.build().context("Failed to build mTLS client certificate verifier")
+}
+
+/// Loads the PEM cert chain and private key into a `rustls::ServerConfig` (wrapped by
+/// `axum_server`'s `RustlsConfig`), surfacing a clear error if either file fails to parse. When
+/// `paths.client_cert_auth` is set, the resulting config also validates client certificates.
+pub async fn load_rustls_config(paths: &TlsPaths) -> Result<RustlsConfig> {
+    match &paths.client_cert_auth {
+        None => RustlsConfig::from_pem(read_pem_source(&paths.cert_path)?, read_pem_source(&paths.key_path)?)
+            .await
+            .with_context(|| {
+                format!("Failed to load TLS certificate/key from '{}' / '{}'", paths.cert_path, paths.key_path)
+            }),
+        Some(client_cert_auth) => {
+            let verifier = build_client_cert_verifier(client_cert_auth)?;
+            let certs = rustls_pemfile::certs(&mut read_pem_source(&paths.cert_path)?.as_slice())
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("Failed to parse TLS certificate chain '{}'", paths.cert_path))?;
+            let key = rustls_pemfile::private_key(&mut read_pem_source(&paths.key_path)?.as_slice())
+                .with_context(|| format!("Failed to parse TLS private key '{}'", paths.key_path))?
+                .ok_or_else(|| anyhow::anyhow!("No private key found in '{}'", paths.key_path))?;
+
+            let config = rustls::ServerConfig::builder()
+                .with_client_cert_verifier(verifier)
+                .with_single_cert(certs, key)
+                .context("Failed to build mTLS server config")?;
+
+            Ok(RustlsConfig::from_config(Arc::new(config)))
+        }
+    }
+}
+
+/// Pulls the subject CN out of a DER-encoded client certificate. Returns `None` (rather than an
+/// error) on anything malformed, since a cert that made it past `ClientCertVerifier` is already
+/// trusted — a missing/unparseable CN just means the request proceeds without a [`ClientIdentity`].
+fn common_name_from_der(der: &[u8]) -> Option<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    cert.subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(|s| s.to_string())
+}
+
+/// Wraps axum-server's Rustls acceptor to additionally extract the client certificate presented
+/// during the handshake (when mTLS is enabled) and attach its subject CN to the request as a
+/// [`ClientIdentity`] extension, so `auth_middleware` can see it alongside the Bearer/API-key and
+/// signed-request paths.
+#[derive(Clone)]
+pub struct ClientCertAcceptor {
+    inner: RustlsAcceptor,
+}
+
+impl ClientCertAcceptor {
+    pub fn new(config: RustlsConfig) -> Self {
+        Self { inner: RustlsAcceptor::new(config) }
+    }
+}
+
+impl<I, S> Accept<I, S> for ClientCertAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = tokio_rustls::server::TlsStream<I>;
+    type Service = <axum::Extension<Option<ClientIdentity>> as tower::Layer<S>>::Service;
+    type Future =
+        std::pin::Pin<Box<dyn std::future::Future<Output = std::io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let (stream, service) = inner.accept(stream, service).await?;
+
+            let identity = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certs| certs.first())
+                .and_then(|cert| common_name_from_der(cert.as_ref()))
+                .map(|common_name| ClientIdentity { common_name });
+
+            let service = ServiceBuilder::new().layer(axum::Extension(identity)).service(service);
+
+            Ok((stream, service))
+        })
+    }
+}
@@ -1,199 +1,532 @@
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+use semver::{BuildMetadata, Prerelease, Version};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::SystemTime;
 use tokio::fs;
 use serde::{Deserialize, Serialize};
 use crate::namespace::{ToolPath, Namespace};
 use crate::tcl_tools::ParameterDefinition;
+use crate::trust;
+use crate::version_resolver::{self, VersionSpec};
 
-/// Tool discovery system for finding and indexing tools from the filesystem
+/// Name of the persisted discovery index, written directly under a `FilesystemSource`'s own
+/// root directory.
+const CACHE_FILE_NAME: &str = ".discovery-cache.json";
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A single file or directory that couldn't be turned into a `DiscoveredTool`, collected by a
+/// scan instead of aborting it outright (see `FilesystemSource::discover_tools`) — one malformed
+/// tool or unreadable directory shouldn't take down every other tool in the same run. Modeled on
+/// kittybox's file backend mapping `io::ErrorKind::NotFound`/`AlreadyExists` into its own
+/// `StorageError` variants.
 #[derive(Debug, Clone)]
-pub struct ToolDiscovery {
-    /// Base directory for tool discovery
-    tools_dir: PathBuf,
-    /// Cache of discovered tools
-    discovered_tools: HashMap<ToolPath, DiscoveredTool>,
+pub enum DiscoveryError {
+    /// A filesystem operation (`read_dir`, `metadata`, ...) failed for `path`.
+    Io { path: PathBuf, kind: std::io::ErrorKind, message: String },
+    /// `path`'s header comments and/or TOML manifest couldn't be read into a `ToolMetadata`.
+    Metadata { path: PathBuf, reason: String },
+    /// An `@param` header-comment line didn't match the `name:type:required description` shape.
+    BadParam { path: PathBuf, line: String },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DiscoveredTool {
-    pub path: ToolPath,
-    pub description: String,
-    pub file_path: PathBuf,
-    pub parameters: Vec<ParameterDefinition>,
+impl DiscoveryError {
+    fn io(path: &Path, source: std::io::Error) -> Self {
+        DiscoveryError::Io { path: path.to_path_buf(), kind: source.kind(), message: source.to_string() }
+    }
 }
 
-impl ToolDiscovery {
-    /// Create a new tool discovery instance
-    pub fn new() -> Self {
-        // Default tools directory - can be configured later
-        let tools_dir = PathBuf::from("tools");
-        Self {
-            tools_dir,
-            discovered_tools: HashMap::new(),
+impl fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoveryError::Io { path, kind, message } => {
+                write!(f, "{}: {} ({:?})", path.display(), message, kind)
+            }
+            DiscoveryError::Metadata { path, reason } => {
+                write!(f, "{}: failed to read tool metadata: {}", path.display(), reason)
+            }
+            DiscoveryError::BadParam { path, line } => {
+                write!(f, "{}: malformed @param line: {}", path.display(), line)
+            }
         }
     }
+}
 
-    /// Set the base directory for tool discovery (for testing)
-    #[cfg(test)]
-    pub fn with_tools_dir(mut self, dir: PathBuf) -> Self {
-        self.tools_dir = dir;
-        self
+impl std::error::Error for DiscoveryError {}
+
+/// The outcome of a discovery pass: every tool successfully resolved, plus every file or
+/// directory that failed along the way. A non-empty `errors` doesn't mean the pass failed as a
+/// whole — `tools` is still complete for everything that could be read.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryReport {
+    pub tools: Vec<DiscoveredTool>,
+    pub errors: Vec<DiscoveryError>,
+}
+
+/// A place `ToolDiscovery` can pull tools from. `FilesystemSource` (below) walks `tools_dir`;
+/// third parties can implement this for a git-backed registry, an HTTP tool index, or anything
+/// else, without touching `ToolDiscovery` itself — the same extension point forge's `Backend`
+/// trait gives DVCS implementations.
+///
+/// `discover` isn't a native `async fn` because the trait needs to be object-safe (`ToolDiscovery`
+/// holds a `Vec<Box<dyn DiscoverySource>>`); implementors can still write an `async fn` body and
+/// wrap it with `Box::pin(async move { ... })`.
+pub trait DiscoverySource: Send + Sync {
+    /// Short identifier used in conflict-resolution log messages (e.g. `"filesystem"`).
+    fn name(&self) -> &str;
+
+    /// Returns every tool this source currently knows about, plus any per-file/per-directory
+    /// errors encountered along the way (see `DiscoveryReport`). `force` asks the source to
+    /// bypass whatever caching it does internally (see `FilesystemSource`'s mtime cache) and
+    /// re-derive everything from scratch.
+    fn discover(&self, force: bool) -> BoxFuture<'_, Result<DiscoveryReport>>;
+}
+
+/// A `DiscoveredTool` plus the file mtime it was read at, so a later scan can tell whether the
+/// file has changed without re-parsing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedTool {
+    tool: DiscoveredTool,
+    modified: SystemTime,
+}
+
+/// On-disk shape of `<root>/.discovery-cache.json`. Keyed by canonicalized absolute file path
+/// (not `ToolPath`) so pointing a source's root at the same files through a different relative
+/// path can't produce a stale hit.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DiscoveryCache {
+    /// Must match `CARGO_PKG_VERSION`; a cache written by a different crate version is discarded
+    /// and fully rebuilt rather than risking a stale or incompatible `DiscoveredTool` shape.
+    version: String,
+    entries: HashMap<PathBuf, CachedTool>,
+}
+
+/// The original (and default) `DiscoverySource`: walks `tools_dir/{bin,sbin,docs,users}` for
+/// `.tcl` files, caching results under `tools_dir/.discovery-cache.json` keyed by file mtime.
+#[derive(Debug, Clone)]
+pub struct FilesystemSource {
+    tools_dir: PathBuf,
+}
+
+impl FilesystemSource {
+    pub fn new(tools_dir: PathBuf) -> Self {
+        Self { tools_dir }
     }
 
-    /// Discover all tools in the filesystem
-    pub async fn discover_tools(&mut self) -> Result<Vec<DiscoveredTool>> {
-        self.discovered_tools.clear();
-        
+    /// Scans every directory under `tools_dir`, collecting discovered tools and the errors of
+    /// whatever couldn't be read along the way (see `DiscoveryReport`) — one bad file or
+    /// directory never aborts the rest of the scan.
+    async fn discover_tools(&self, force: bool) -> Result<DiscoveryReport> {
+        let mut discovered_tools: HashMap<ToolPath, DiscoveredTool> = HashMap::new();
+        let mut errors: Vec<DiscoveryError> = Vec::new();
+
+        let cache_path = self.tools_dir.join(CACHE_FILE_NAME);
+        let old_cache = if force {
+            HashMap::new()
+        } else {
+            Self::load_cache(&cache_path).await
+        };
+        let mut new_cache: HashMap<PathBuf, CachedTool> = HashMap::new();
+
         // Scan system directories
-        self.scan_directory(&self.tools_dir.join("bin"), Namespace::Bin).await?;
-        self.scan_directory(&self.tools_dir.join("sbin"), Namespace::Sbin).await?;
-        self.scan_directory(&self.tools_dir.join("docs"), Namespace::Docs).await?;
-        
+        self.scan_directory(&self.tools_dir.join("bin"), Namespace::Bin, &old_cache, &mut new_cache, &mut discovered_tools, &mut errors).await;
+        self.scan_directory(&self.tools_dir.join("sbin"), Namespace::Sbin, &old_cache, &mut new_cache, &mut discovered_tools, &mut errors).await;
+        self.scan_directory(&self.tools_dir.join("docs"), Namespace::Docs, &old_cache, &mut new_cache, &mut discovered_tools, &mut errors).await;
+
         // Scan user directories
         let user_dir = self.tools_dir.join("users");
         if user_dir.exists() {
-            self.scan_user_directories(&user_dir).await?;
+            self.scan_user_directories(&user_dir, &old_cache, &mut new_cache, &mut discovered_tools, &mut errors).await;
         }
-        
-        Ok(self.discovered_tools.values().cloned().collect())
+
+        Self::save_cache(&cache_path, new_cache).await;
+
+        Ok(DiscoveryReport { tools: discovered_tools.into_values().collect(), errors })
     }
 
-    /// Scan a specific directory for tools
-    async fn scan_directory(&mut self, dir: &Path, namespace: Namespace) -> Result<()> {
+    /// Scan a specific directory for tools. For each `.tcl` file, reuses `old_cache`'s entry
+    /// verbatim when the file's mtime hasn't changed, otherwise re-reads it via
+    /// `read_tool_metadata`; either way the entry (carried over or freshly read) is recorded in
+    /// `new_cache`, so files that no longer exist are naturally dropped from the next save. A
+    /// directory that can't be listed, or a file that can't be resolved, is recorded in `errors`
+    /// rather than aborting the scan.
+    async fn scan_directory(
+        &self,
+        dir: &Path,
+        namespace: Namespace,
+        old_cache: &HashMap<PathBuf, CachedTool>,
+        new_cache: &mut HashMap<PathBuf, CachedTool>,
+        discovered_tools: &mut HashMap<ToolPath, DiscoveredTool>,
+        errors: &mut Vec<DiscoveryError>,
+    ) {
         if !dir.exists() {
-            return Ok(());
+            return;
         }
 
-        let mut entries = fs::read_dir(dir).await?;
-        while let Some(entry) = entries.next_entry().await? {
+        let mut entries = match fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(DiscoveryError::io(dir, e));
+                return;
+            }
+        };
+
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(e) => {
+                    errors.push(DiscoveryError::io(dir, e));
+                    break;
+                }
+            };
             let path = entry.path();
-            
+
             // Only process .tcl files
             if path.extension().and_then(|s| s.to_str()) == Some("tcl") {
                 if let Some(tool_name) = path.file_stem().and_then(|s| s.to_str()) {
-                    // Read tool metadata from file header
-                    let metadata = self.read_tool_metadata(&path).await?;
-                    
-                    let tool_path = match &namespace {
-                        Namespace::Bin => ToolPath::bin(tool_name),
-                        Namespace::Sbin => ToolPath::sbin(tool_name),
-                        Namespace::Docs => ToolPath::docs(tool_name),
-                        Namespace::User(_) => continue, // Handled separately
-                    };
-                    
-                    let discovered = DiscoveredTool {
-                        path: tool_path.clone(),
-                        description: metadata.description,
-                        file_path: path,
-                        parameters: metadata.parameters,
-                    };
-                    
-                    self.discovered_tools.insert(tool_path, discovered);
+                    let namespace = namespace.clone();
+                    let tool_name = tool_name.to_string();
+                    let resolved = self.resolve_tool(&path, old_cache, new_cache, errors, move |_| match &namespace {
+                        Namespace::Bin => ToolPath::bin(&tool_name),
+                        Namespace::Sbin => ToolPath::sbin(&tool_name),
+                        Namespace::Docs => ToolPath::docs(&tool_name),
+                        Namespace::User(_) => unreachable!("scan_directory is never called for a user namespace"),
+                    }).await;
+
+                    match resolved {
+                        Ok(discovered) => { discovered_tools.insert(discovered.path.clone(), discovered); }
+                        Err(e) => errors.push(e),
+                    }
                 }
             }
         }
-        
-        Ok(())
     }
 
-    /// Scan user directories for tools
-    async fn scan_user_directories(&mut self, users_dir: &Path) -> Result<()> {
-        let mut user_entries = fs::read_dir(users_dir).await?;
-        
-        while let Some(user_entry) = user_entries.next_entry().await? {
+    /// Scan user directories for tools. Like `scan_directory`, an unreadable directory at any
+    /// level (`users/`, a user's directory, a package's directory) or an unresolvable tool file
+    /// is recorded in `errors` and skipped rather than aborting the whole scan.
+    async fn scan_user_directories(
+        &self,
+        users_dir: &Path,
+        old_cache: &HashMap<PathBuf, CachedTool>,
+        new_cache: &mut HashMap<PathBuf, CachedTool>,
+        discovered_tools: &mut HashMap<ToolPath, DiscoveredTool>,
+        errors: &mut Vec<DiscoveryError>,
+    ) {
+        let mut user_entries = match fs::read_dir(users_dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(DiscoveryError::io(users_dir, e));
+                return;
+            }
+        };
+
+        loop {
+            let user_entry = match user_entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(e) => {
+                    errors.push(DiscoveryError::io(users_dir, e));
+                    break;
+                }
+            };
             let user_path = user_entry.path();
             if !user_path.is_dir() {
                 continue;
             }
-            
+
             let user_name = user_entry.file_name().to_string_lossy().to_string();
-            
+
             // Scan packages within user directory
-            let mut package_entries = fs::read_dir(&user_path).await?;
-            while let Some(package_entry) = package_entries.next_entry().await? {
+            let mut package_entries = match fs::read_dir(&user_path).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    errors.push(DiscoveryError::io(&user_path, e));
+                    continue;
+                }
+            };
+
+            loop {
+                let package_entry = match package_entries.next_entry().await {
+                    Ok(Some(entry)) => entry,
+                    Ok(None) => break,
+                    Err(e) => {
+                        errors.push(DiscoveryError::io(&user_path, e));
+                        break;
+                    }
+                };
                 let package_path = package_entry.path();
                 if !package_path.is_dir() {
                     continue;
                 }
-                
+
                 let package_name = package_entry.file_name().to_string_lossy().to_string();
-                
+
                 // Scan tools within package
-                let mut tool_entries = fs::read_dir(&package_path).await?;
-                while let Some(tool_entry) = tool_entries.next_entry().await? {
+                let mut tool_entries = match fs::read_dir(&package_path).await {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        errors.push(DiscoveryError::io(&package_path, e));
+                        continue;
+                    }
+                };
+
+                loop {
+                    let tool_entry = match tool_entries.next_entry().await {
+                        Ok(Some(entry)) => entry,
+                        Ok(None) => break,
+                        Err(e) => {
+                            errors.push(DiscoveryError::io(&package_path, e));
+                            break;
+                        }
+                    };
                     let tool_file = tool_entry.path();
-                    
+
                     if tool_file.extension().and_then(|s| s.to_str()) == Some("tcl") {
                         if let Some(tool_name) = tool_file.file_stem().and_then(|s| s.to_str()) {
-                            let metadata = self.read_tool_metadata(&tool_file).await?;
-                            
-                            let tool_path = ToolPath::user(
-                                &user_name,
-                                &package_name,
-                                tool_name,
-                                metadata.version.unwrap_or_else(|| "latest".to_string())
-                            );
-                            
-                            let discovered = DiscoveredTool {
-                                path: tool_path.clone(),
-                                description: metadata.description,
-                                file_path: tool_file,
-                                parameters: metadata.parameters,
-                            };
-                            
-                            self.discovered_tools.insert(tool_path, discovered);
+                            let user_name = user_name.clone();
+                            let package_name = package_name.clone();
+                            let tool_name = tool_name.to_string();
+                            let resolved = self.resolve_tool(&tool_file, old_cache, new_cache, errors, move |metadata| {
+                                ToolPath::user(
+                                    &user_name,
+                                    &package_name,
+                                    &tool_name,
+                                    metadata.version.clone().unwrap_or_else(|| "latest".to_string())
+                                )
+                            }).await;
+
+                            match resolved {
+                                Ok(discovered) => { discovered_tools.insert(discovered.path.clone(), discovered); }
+                                Err(e) => errors.push(e),
+                            }
                         }
                     }
                 }
             }
         }
-        
-        Ok(())
     }
 
-    /// Read tool metadata from file header comments
-    async fn read_tool_metadata(&self, file_path: &Path) -> Result<ToolMetadata> {
+    /// Resolves a single tool file to a `DiscoveredTool`, reusing `old_cache` when the file's
+    /// mtime matches and re-reading it (via `build_path`, which needs the freshly parsed
+    /// metadata to build a `Namespace::User` path's version) otherwise. Either way, records the
+    /// result in `new_cache` keyed by the file's canonicalized path. Malformed `@param` lines are
+    /// pushed onto `errors` rather than failing the tool outright; a read or manifest-parse
+    /// failure is returned as a hard `Err` instead, since there's no usable metadata to fall back
+    /// to.
+    async fn resolve_tool(
+        &self,
+        file_path: &Path,
+        old_cache: &HashMap<PathBuf, CachedTool>,
+        new_cache: &mut HashMap<PathBuf, CachedTool>,
+        errors: &mut Vec<DiscoveryError>,
+        build_path: impl FnOnce(&ToolMetadata) -> ToolPath,
+    ) -> Result<DiscoveredTool, DiscoveryError> {
+        let canonical = fs::canonicalize(file_path).await.unwrap_or_else(|_| file_path.to_path_buf());
+        let modified = fs::metadata(file_path).await
+            .and_then(|m| m.modified())
+            .map_err(|e| DiscoveryError::io(file_path, e))?;
+
+        if let Some(cached) = old_cache.get(&canonical) {
+            if cached.modified == modified {
+                new_cache.insert(canonical, cached.clone());
+                return Ok(cached.tool.clone());
+            }
+        }
+
+        let (metadata, bad_params) = self.read_tool_metadata(file_path).await
+            .map_err(|e| DiscoveryError::Metadata { path: file_path.to_path_buf(), reason: e.to_string() })?;
+        errors.extend(bad_params);
+
+        let tool_path = build_path(&metadata);
+        let discovered = DiscoveredTool {
+            path: tool_path,
+            description: metadata.description,
+            file_path: file_path.to_path_buf(),
+            parameters: metadata.parameters,
+            requires_privileged: metadata.requires_privileged,
+            content_hash: metadata.content_hash,
+        };
+
+        new_cache.insert(canonical, CachedTool { tool: discovered.clone(), modified });
+        Ok(discovered)
+    }
+
+    /// Loads the persisted discovery cache, discarding it (falling back to an empty cache, which
+    /// forces every file to be re-read) if it's missing, unparseable, or was written by a
+    /// different crate version.
+    async fn load_cache(cache_path: &Path) -> HashMap<PathBuf, CachedTool> {
+        let content = match fs::read_to_string(cache_path).await {
+            Ok(content) => content,
+            Err(_) => return HashMap::new(),
+        };
+
+        match serde_json::from_str::<DiscoveryCache>(&content) {
+            Ok(cache) if cache.version == env!("CARGO_PKG_VERSION") => cache.entries,
+            Ok(_) => {
+                tracing::warn!("Discarding discovery cache written by a different crate version");
+                HashMap::new()
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse discovery cache ({}), rebuilding it", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Persists `entries` to `<root>/.discovery-cache.json`, writing through a sibling `.tmp`
+    /// file and renaming it into place so a crash mid-write can't leave a truncated cache behind.
+    /// A failure here only costs the next scan its incremental speedup, so it's logged rather
+    /// than surfaced as a `discover` error.
+    async fn save_cache(cache_path: &Path, entries: HashMap<PathBuf, CachedTool>) {
+        let cache = DiscoveryCache {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            entries,
+        };
+
+        let json = match serde_json::to_string_pretty(&cache) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize discovery cache: {}", e);
+                return;
+            }
+        };
+
+        let mut tmp = cache_path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp_path = PathBuf::from(tmp);
+
+        if let Err(e) = fs::write(&tmp_path, json.as_bytes()).await {
+            tracing::warn!("Failed to write discovery cache: {}", e);
+            return;
+        }
+        if let Err(e) = fs::rename(&tmp_path, cache_path).await {
+            tracing::warn!("Failed to persist discovery cache: {}", e);
+        }
+    }
+
+    /// Read tool metadata from file header comments, then overlay a TOML manifest on top if one
+    /// exists (see `load_tool_manifest`) — the manifest wins field-by-field where it specifies
+    /// something; a tool with no manifest keeps today's comment-only behavior exactly. A
+    /// malformed `@param` line is collected as a `DiscoveryError::BadParam` alongside the
+    /// metadata rather than failing the whole file, so the rest of its header comments (and any
+    /// valid `@param` lines) still take effect.
+    async fn read_tool_metadata(&self, file_path: &Path) -> Result<(ToolMetadata, Vec<DiscoveryError>)> {
         let content = fs::read_to_string(file_path).await?;
-        let mut metadata = ToolMetadata::default();
-        
+        let mut metadata = ToolMetadata { content_hash: trust::content_hash(&content), ..Default::default() };
+        let mut bad_params = Vec::new();
+
         // Parse header comments for metadata
         for line in content.lines() {
             if !line.trim_start().starts_with('#') {
                 break; // Stop at first non-comment line
             }
-            
+
             let comment = line.trim_start_matches('#').trim();
-            
+
             if let Some(desc) = comment.strip_prefix("@description ") {
                 metadata.description = desc.to_string();
             } else if let Some(version) = comment.strip_prefix("@version ") {
                 metadata.version = Some(version.to_string());
+            } else if let Some(requires_privileged) = comment.strip_prefix("@requires_privileged ") {
+                metadata.requires_privileged = requires_privileged.trim() == "true";
             } else if let Some(param_line) = comment.strip_prefix("@param ") {
                 // Parse parameter definition: @param name:type:required description
-                if let Some((def, desc)) = param_line.split_once(' ') {
+                let parsed = param_line.split_once(' ').and_then(|(def, desc)| {
                     let parts: Vec<&str> = def.split(':').collect();
-                    if parts.len() >= 2 {
-                        let param = ParameterDefinition {
-                            name: parts[0].to_string(),
-                            type_name: parts[1].to_string(),
-                            required: parts.get(2).map(|&r| r == "required").unwrap_or(false),
-                            description: desc.to_string(),
-                        };
-                        metadata.parameters.push(param);
-                    }
+                    (parts.len() >= 2).then(|| ParameterDefinition {
+                        name: parts[0].to_string(),
+                        type_name: parts[1].to_string(),
+                        required: parts.get(2).map(|&r| r == "required").unwrap_or(false),
+                        description: desc.to_string(),
+                        ..Default::default()
+                    })
+                });
+
+                match parsed {
+                    Some(param) => metadata.parameters.push(param),
+                    None => bad_params.push(DiscoveryError::BadParam {
+                        path: file_path.to_path_buf(),
+                        line: param_line.to_string(),
+                    }),
                 }
             }
         }
-        
+
+        if let Some(manifest) = Self::load_tool_manifest(file_path).await? {
+            if let Some(description) = manifest.description {
+                metadata.description = description;
+            }
+            if let Some(version) = manifest.version {
+                metadata.version = Some(version);
+            }
+            if let Some(requires_privileged) = manifest.requires_privileged {
+                metadata.requires_privileged = requires_privileged;
+            }
+            if let Some(parameters) = manifest.parameters {
+                metadata.parameters = parameters;
+            }
+        }
+
         if metadata.description.is_empty() {
             metadata.description = format!("Tool from {}", file_path.display());
         }
-        
-        Ok(metadata)
+
+        Ok((metadata, bad_params))
+    }
+
+    /// Loads the TOML manifest, if any, that applies to `file_path`: a package-level
+    /// `package.toml` (shared defaults for every tool in the directory) merged under a sibling
+    /// `<tool>.toml` (specific to this one tool, and wins wherever both set the same field).
+    /// Returns `None` when neither file exists, so `read_tool_metadata` falls back entirely to
+    /// header comments.
+    async fn load_tool_manifest(file_path: &Path) -> Result<Option<ToolManifest>> {
+        let mut merged = None;
+
+        if let Some(dir) = file_path.parent() {
+            if let Some(package) = Self::read_manifest_file(&dir.join("package.toml")).await? {
+                merged = Some(package);
+            }
+        }
+
+        if let Some(tool) = Self::read_manifest_file(&file_path.with_extension("toml")).await? {
+            merged = Some(match merged {
+                Some(package) => package.merged_with(tool),
+                None => tool,
+            });
+        }
+
+        Ok(merged)
     }
 
+    /// Reads and parses a single manifest file. `Ok(None)` when it doesn't exist; a malformed
+    /// manifest that does exist is an error rather than a silent fallback, since an author who
+    /// wrote one expects it to be honored.
+    async fn read_manifest_file(path: &Path) -> Result<Option<ToolManifest>> {
+        let text = match fs::read_to_string(path).await {
+            Ok(text) => text,
+            Err(_) => return Ok(None),
+        };
 
+        let manifest: ToolManifest = toml::from_str(&text)
+            .map_err(|e| anyhow!("failed to parse tool manifest {}: {}", path.display(), e))?;
+        Ok(Some(manifest))
+    }
+}
+
+impl DiscoverySource for FilesystemSource {
+    fn name(&self) -> &str {
+        "filesystem"
+    }
+
+    fn discover(&self, force: bool) -> BoxFuture<'_, Result<DiscoveryReport>> {
+        Box::pin(async move { self.discover_tools(force).await })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -201,6 +534,225 @@ struct ToolMetadata {
     description: String,
     version: Option<String>,
     parameters: Vec<ParameterDefinition>,
+    requires_privileged: bool,
+    content_hash: String,
+}
+
+/// Structured alternative to `# @description`/`# @version`/`# @param` header comments, parsed
+/// from a sibling `<tool>.toml` and/or a package-level `package.toml` (see
+/// `FilesystemSource::load_tool_manifest`). Every field is optional so a manifest can override
+/// only what it needs to, deferring everything else to header comments or (for `package.toml`)
+/// to a more specific per-tool manifest. This mirrors `CapabilityFile`'s role in
+/// `capability_grants.rs`: a private, `Deserialize`-only shape that exists purely to describe the
+/// file on disk.
+#[derive(Debug, Default, Deserialize)]
+struct ToolManifest {
+    description: Option<String>,
+    version: Option<String>,
+    requires_privileged: Option<bool>,
+    parameters: Option<Vec<ParameterDefinition>>,
+}
+
+impl ToolManifest {
+    /// Overlays `more_specific`'s set fields onto `self`, preferring `more_specific` wherever it
+    /// specifies something. Used to let a per-tool `<tool>.toml` override a package-level
+    /// `package.toml` field-by-field rather than replacing it outright.
+    fn merged_with(self, more_specific: ToolManifest) -> ToolManifest {
+        ToolManifest {
+            description: more_specific.description.or(self.description),
+            version: more_specific.version.or(self.version),
+            requires_privileged: more_specific.requires_privileged.or(self.requires_privileged),
+            parameters: more_specific.parameters.or(self.parameters),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredTool {
+    pub path: ToolPath,
+    pub description: String,
+    pub file_path: PathBuf,
+    pub parameters: Vec<ParameterDefinition>,
+    /// Set by an `@requires_privileged true` header comment. A non-privileged `TclExecutor`
+    /// refuses to run this tool rather than executing it with the unsafe commands disabled.
+    pub requires_privileged: bool,
+    /// SHA-256 hex digest of the file's content at the time it was last read (see
+    /// `trust::content_hash`). `TrustStore::is_trusted` compares this against whatever hash a
+    /// tool was approved at, so an edited file drops back to untrusted even if it keeps the same
+    /// `ToolPath`.
+    pub content_hash: String,
+}
+
+/// Merges tools from one or more [`DiscoverySource`]s. Always starts with a [`FilesystemSource`]
+/// rooted at `tools_dir`; additional sources (a git-backed registry, an HTTP tool index, ...) can
+/// be registered with [`ToolDiscovery::add_source`].
+pub struct ToolDiscovery {
+    /// Base directory for the default filesystem source (kept around for `with_tools_dir`/tests).
+    tools_dir: PathBuf,
+    /// Cache of discovered tools from the most recent `discover_tools` call.
+    discovered_tools: HashMap<ToolPath, DiscoveredTool>,
+    /// Queried in order; when two sources yield the same `ToolPath`, the later source in this
+    /// list wins (see `merge`).
+    sources: Vec<Box<dyn DiscoverySource>>,
+    /// When true, a `ToolPath` collision between two sources aborts discovery with an error
+    /// instead of logging a warning and keeping the later source's entry.
+    strict_conflicts: bool,
+    /// Every discovered version of each user tool, keyed by `(user, package, name)`, so
+    /// `resolve` can find the highest version satisfying a [`VersionSpec`] without scanning
+    /// `discovered_tools` linearly. Rebuilt from `discovered_tools` at the end of every
+    /// `discover_tools` call.
+    versions: HashMap<(String, String, String), BTreeMap<Version, DiscoveredTool>>,
+}
+
+/// Orders a `ToolPath::version` string for the `versions` index. Mirrors
+/// `version_resolver::parse`'s padding for real semver strings; a version that isn't valid
+/// semver even after padding is kept (rather than dropped) as `0.0.0` with the original string
+/// folded into a prerelease tag, which sorts it below every real release. Characters outside
+/// `[A-Za-z0-9.-]` are each replaced with `-`, so tags differing only in those share a key, and a
+/// tag that still isn't a valid prerelease falls back to plain `0.0.0`.
+fn version_sort_key(raw: &str) -> Version {
+    if let Ok(version) = Version::parse(&version_resolver::pad_to_semver(raw)) {
+        return version;
+    }
+
+    let sanitized: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '-' })
+        .collect();
+    let sanitized = sanitized.trim_matches('-').to_string();
+    let tag = if sanitized.is_empty() { "unparsed".to_string() } else { sanitized };
+
+    Version {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        pre: Prerelease::new(&tag).unwrap_or(Prerelease::EMPTY),
+        build: BuildMetadata::EMPTY,
+    }
+}
+
+impl std::fmt::Debug for ToolDiscovery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolDiscovery")
+            .field("tools_dir", &self.tools_dir)
+            .field("discovered_tools", &self.discovered_tools)
+            .field("sources", &self.sources.iter().map(|s| s.name()).collect::<Vec<_>>())
+            .field("strict_conflicts", &self.strict_conflicts)
+            .field("versions", &self.versions)
+            .finish()
+    }
+}
+
+impl ToolDiscovery {
+    /// Create a new tool discovery instance
+    pub fn new() -> Self {
+        // Default tools directory - can be configured later
+        let tools_dir = PathBuf::from("tools");
+        Self {
+            sources: vec![Box::new(FilesystemSource::new(tools_dir.clone()))],
+            tools_dir,
+            discovered_tools: HashMap::new(),
+            strict_conflicts: false,
+            versions: HashMap::new(),
+        }
+    }
+
+    /// Set the base directory for tool discovery (for testing)
+    #[cfg(test)]
+    pub fn with_tools_dir(mut self, dir: PathBuf) -> Self {
+        self.tools_dir = dir.clone();
+        self.sources = vec![Box::new(FilesystemSource::new(dir))];
+        self
+    }
+
+    /// Registers an additional discovery source, queried after every source already present (so
+    /// its entries win ties against them — see `merge`).
+    pub fn add_source(&mut self, source: Box<dyn DiscoverySource>) {
+        self.sources.push(source);
+    }
+
+    /// When `strict` is true, two sources yielding the same `ToolPath` is treated as a discovery
+    /// error rather than resolved by last-source-wins.
+    pub fn set_strict_conflicts(&mut self, strict: bool) {
+        self.strict_conflicts = strict;
+    }
+
+    /// Queries every registered source and merges their results. `force` is forwarded to each
+    /// source (see `DiscoverySource::discover`) and asks it to bypass whatever caching it does
+    /// internally. Errors collected by individual sources (see `DiscoveryReport`) are
+    /// concatenated and returned alongside the merged tools rather than failing the whole pass.
+    pub async fn discover_tools(&mut self, force: bool) -> Result<DiscoveryReport> {
+        self.discovered_tools.clear();
+        let mut errors = Vec::new();
+
+        for index in 0..self.sources.len() {
+            let report = self.sources[index].discover(force).await?;
+            let source_name = self.sources[index].name().to_string();
+            errors.extend(report.errors);
+            self.merge(&source_name, report.tools)?;
+        }
+
+        self.rebuild_version_index();
+
+        Ok(DiscoveryReport { tools: self.discovered_tools.values().cloned().collect(), errors })
+    }
+
+    /// Rebuilds `versions` from the current `discovered_tools`, grouping every `Namespace::User`
+    /// tool by `(user, package, name)` so `resolve` can pick the best match for a requested
+    /// version range without re-deriving this grouping on every call.
+    fn rebuild_version_index(&mut self) {
+        self.versions.clear();
+
+        for tool in self.discovered_tools.values() {
+            let Namespace::User(user) = &tool.path.namespace else { continue };
+            let Some(package) = &tool.path.package else { continue };
+
+            let key = (user.clone(), package.clone(), tool.path.name.clone());
+            let version = version_sort_key(&tool.path.version);
+            self.versions.entry(key).or_default().insert(version, tool.clone());
+        }
+    }
+
+    /// Resolves `(user, package, tool)` against every discovered version, returning the one
+    /// `spec` picks: the highest version satisfying a `VersionSpec::Req`, the exact match for a
+    /// `VersionSpec::Exact`, or the highest version available for `VersionSpec::Latest`
+    /// (including the literal `"latest"` version tag, which sorts as `0.0.0` via
+    /// `version_sort_key` and loses to any real release — matching the "latest means highest
+    /// available" rule even when a tool author never bumped past `"latest"`).
+    pub fn resolve(&self, user: &str, package: &str, tool: &str, spec: &VersionSpec) -> Option<&DiscoveredTool> {
+        let key = (user.to_string(), package.to_string(), tool.to_string());
+        let versions = self.versions.get(&key)?;
+
+        match spec {
+            VersionSpec::Latest => versions.values().next_back(),
+            VersionSpec::Exact(exact) => versions.get(exact),
+            VersionSpec::Req(req) => versions.iter().rev().find(|(v, _)| req.matches(v)).map(|(_, tool)| tool),
+        }
+    }
+
+    /// Folds `tools` (from `source_name`) into `self.discovered_tools`. A `ToolPath` already
+    /// present from an earlier source is a conflict: in strict mode it's an error, otherwise the
+    /// new source's entry wins and a warning is logged.
+    fn merge(&mut self, source_name: &str, tools: Vec<DiscoveredTool>) -> Result<()> {
+        for tool in tools {
+            if let Some(existing) = self.discovered_tools.get(&tool.path) {
+                if self.strict_conflicts {
+                    return Err(anyhow!(
+                        "discovery conflict: '{}' was yielded by both an earlier source and '{}'",
+                        tool.path, source_name
+                    ));
+                }
+                tracing::warn!(
+                    "'{}' discovered by more than one source; '{}' overrides the earlier entry from {}",
+                    tool.path, source_name, existing.file_path.display()
+                );
+            }
+
+            self.discovered_tools.insert(tool.path.clone(), tool);
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -214,26 +766,28 @@ mod tests {
         // Create temporary directory structure
         let temp_dir = tempfile::tempdir().unwrap();
         let tools_dir = temp_dir.path().join("tools");
-        
+
         // Create bin directory with a tool
         let bin_dir = tools_dir.join("bin");
         fs::create_dir_all(&bin_dir).await.unwrap();
-        
+
         let tool_content = r#"#!/usr/bin/env tclsh
 # @description List directory contents
 # @param path:string:required Directory path to list
 
 puts [glob -directory $path *]
 "#;
-        
+
         let tool_path = bin_dir.join("list_dir.tcl");
         let mut file = std::fs::File::create(&tool_path).unwrap();
         file.write_all(tool_content.as_bytes()).unwrap();
-        
+
         // Test discovery
         let mut discovery = ToolDiscovery::new().with_tools_dir(tools_dir);
-        let tools = discovery.discover_tools().await.unwrap();
-        
+        let report = discovery.discover_tools(false).await.unwrap();
+        let tools = report.tools;
+        assert!(report.errors.is_empty());
+
         assert_eq!(tools.len(), 1);
         assert_eq!(tools[0].path.name, "list_dir");
         assert_eq!(tools[0].description, "List directory contents");
@@ -242,4 +796,220 @@ puts [glob -directory $path *]
         assert_eq!(tools[0].parameters[0].type_name, "string");
         assert!(tools[0].parameters[0].required);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_malformed_param_line_is_reported_without_failing_the_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let tools_dir = temp_dir.path().join("tools");
+        let bin_dir = tools_dir.join("bin");
+        fs::create_dir_all(&bin_dir).await.unwrap();
+
+        let tool_content = r#"#!/usr/bin/env tclsh
+# @description Has one good param and one malformed one
+# @param valid:string:required A valid param
+# @param missing_everything
+
+puts ok
+"#;
+        std::fs::write(bin_dir.join("quirky.tcl"), tool_content).unwrap();
+
+        let mut discovery = ToolDiscovery::new().with_tools_dir(tools_dir);
+        let report = discovery.discover_tools(false).await.unwrap();
+
+        // The malformed line is surfaced as an error, but the rest of the file's metadata
+        // (including the one valid @param) is still honored.
+        assert_eq!(report.errors.len(), 1);
+        assert!(matches!(&report.errors[0], DiscoveryError::BadParam { .. }));
+
+        assert_eq!(report.tools.len(), 1);
+        assert_eq!(report.tools[0].parameters.len(), 1);
+        assert_eq!(report.tools[0].parameters[0].name, "valid");
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_directory_is_reported_without_aborting_the_scan() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let tools_dir = temp_dir.path().join("tools");
+        let bin_dir = tools_dir.join("bin");
+        fs::create_dir_all(&bin_dir).await.unwrap();
+        std::fs::write(bin_dir.join("ok.tcl"), "# @description Fine\n").unwrap();
+
+        // `users/` exists but isn't a directory at all, so read_dir on it fails outright; the
+        // bin-directory scan should still have completed beforehand.
+        let users_path = tools_dir.join("users");
+        std::fs::write(&users_path, "not a directory").unwrap();
+
+        let mut discovery = ToolDiscovery::new().with_tools_dir(tools_dir);
+        let report = discovery.discover_tools(false).await.unwrap();
+
+        assert_eq!(report.tools.len(), 1);
+        assert_eq!(report.tools[0].path.name, "ok");
+        assert_eq!(report.errors.len(), 1);
+        assert!(matches!(&report.errors[0], DiscoveryError::Io { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_discovery_cache_persists_and_drops_stale_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let tools_dir = temp_dir.path().join("tools");
+        let bin_dir = tools_dir.join("bin");
+        fs::create_dir_all(&bin_dir).await.unwrap();
+
+        let tool_a = bin_dir.join("a.tcl");
+        let tool_b = bin_dir.join("b.tcl");
+        std::fs::write(&tool_a, "# @description A\n").unwrap();
+        std::fs::write(&tool_b, "# @description B\n").unwrap();
+
+        let mut discovery = ToolDiscovery::new().with_tools_dir(tools_dir.clone());
+        let tools = discovery.discover_tools(false).await.unwrap().tools;
+        assert_eq!(tools.len(), 2);
+
+        // The cache is persisted under tools_dir and keyed by canonicalized path.
+        let cache_path = tools_dir.join(CACHE_FILE_NAME);
+        assert!(cache_path.exists());
+        let cache: DiscoveryCache = serde_json::from_str(&fs::read_to_string(&cache_path).await.unwrap()).unwrap();
+        assert_eq!(cache.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(cache.entries.len(), 2);
+
+        // Removing a tool file and rescanning (without force) drops its stale cache entry rather
+        // than carrying it over forever.
+        std::fs::remove_file(&tool_b).unwrap();
+        let tools = discovery.discover_tools(false).await.unwrap().tools;
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].path.name, "a");
+
+        let cache: DiscoveryCache = serde_json::from_str(&fs::read_to_string(&cache_path).await.unwrap()).unwrap();
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_discovery_cache_rejects_mismatched_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let tools_dir = temp_dir.path().join("tools");
+        fs::create_dir_all(&tools_dir).await.unwrap();
+
+        let stale = DiscoveryCache {
+            version: "0.0.0-not-a-real-version".to_string(),
+            entries: HashMap::new(),
+        };
+        std::fs::write(
+            tools_dir.join(CACHE_FILE_NAME),
+            serde_json::to_string(&stale).unwrap(),
+        ).unwrap();
+
+        let loaded = FilesystemSource::load_cache(&tools_dir.join(CACHE_FILE_NAME)).await;
+        assert!(loaded.is_empty());
+    }
+
+    struct StaticSource {
+        name: String,
+        tools: Vec<DiscoveredTool>,
+    }
+
+    impl DiscoverySource for StaticSource {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn discover(&self, _force: bool) -> BoxFuture<'_, Result<DiscoveryReport>> {
+            Box::pin(async move { Ok(DiscoveryReport { tools: self.tools.clone(), errors: Vec::new() }) })
+        }
+    }
+
+    fn fake_tool(path: ToolPath, description: &str) -> DiscoveredTool {
+        DiscoveredTool {
+            path,
+            description: description.to_string(),
+            file_path: PathBuf::from("<fake>"),
+            parameters: Vec::new(),
+            requires_privileged: false,
+            content_hash: trust::content_hash(description),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_additional_sources_merge_with_last_source_winning() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut discovery = ToolDiscovery::new().with_tools_dir(temp_dir.path().join("tools"));
+        discovery.add_source(Box::new(StaticSource {
+            name: "registry-a".to_string(),
+            tools: vec![fake_tool(ToolPath::bin("shared"), "from registry-a")],
+        }));
+        discovery.add_source(Box::new(StaticSource {
+            name: "registry-b".to_string(),
+            tools: vec![fake_tool(ToolPath::bin("shared"), "from registry-b")],
+        }));
+
+        let tools = discovery.discover_tools(false).await.unwrap().tools;
+        let shared = tools.iter().find(|t| t.path.name == "shared").unwrap();
+        assert_eq!(shared.description, "from registry-b");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_picks_highest_version_satisfying_a_requirement() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut discovery = ToolDiscovery::new().with_tools_dir(temp_dir.path().join("tools"));
+        discovery.add_source(Box::new(StaticSource {
+            name: "registry".to_string(),
+            tools: vec![
+                fake_tool(ToolPath::user("alice", "math", "calculate", "1.0"), "v1.0"),
+                fake_tool(ToolPath::user("alice", "math", "calculate", "1.9.0"), "v1.9.0"),
+                fake_tool(ToolPath::user("alice", "math", "calculate", "2.0"), "v2.0"),
+            ],
+        }));
+
+        discovery.discover_tools(false).await.unwrap();
+
+        let resolved = discovery
+            .resolve("alice", "math", "calculate", &version_resolver::parse("^1.2").unwrap())
+            .unwrap();
+        assert_eq!(resolved.description, "v1.9.0");
+
+        let latest = discovery
+            .resolve("alice", "math", "calculate", &version_resolver::parse("latest").unwrap())
+            .unwrap();
+        assert_eq!(latest.description, "v2.0");
+
+        assert!(discovery
+            .resolve("alice", "math", "calculate", &version_resolver::parse(">=3.0").unwrap())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_keeps_unparseable_versions_instead_of_dropping_them() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut discovery = ToolDiscovery::new().with_tools_dir(temp_dir.path().join("tools"));
+        discovery.add_source(Box::new(StaticSource {
+            name: "registry".to_string(),
+            tools: vec![
+                fake_tool(ToolPath::user("bob", "util", "thing", "experimental"), "experimental build"),
+                fake_tool(ToolPath::user("bob", "util", "thing", "1.0"), "v1.0"),
+            ],
+        }));
+
+        discovery.discover_tools(false).await.unwrap();
+
+        // "latest" still resolves to the real release, not the unparseable tag.
+        let latest = discovery
+            .resolve("bob", "util", "thing", &version_resolver::parse("latest").unwrap())
+            .unwrap();
+        assert_eq!(latest.description, "v1.0");
+    }
+
+    #[tokio::test]
+    async fn test_strict_conflicts_errors_instead_of_overriding() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut discovery = ToolDiscovery::new().with_tools_dir(temp_dir.path().join("tools"));
+        discovery.set_strict_conflicts(true);
+        discovery.add_source(Box::new(StaticSource {
+            name: "registry-a".to_string(),
+            tools: vec![fake_tool(ToolPath::bin("shared"), "from registry-a")],
+        }));
+        discovery.add_source(Box::new(StaticSource {
+            name: "registry-b".to_string(),
+            tools: vec![fake_tool(ToolPath::bin("shared"), "from registry-b")],
+        }));
+
+        assert!(discovery.discover_tools(false).await.is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Glob-pattern filtering over [`ToolPath`], modeled on Deno's `PathOrPatternSet::matches_specifier`:
+//! a set of include and exclude patterns is compiled once into a [`ToolPathMatcher`] and then
+//! evaluated against a tool's full path string (e.g. `/alice/math/calculate:2.0`), so a caller can
+//! select across namespace + package + name + version in one query — `/alice/**` for everything
+//! alice owns, `/*/math/*` for every namespace's `math` package, `!**:*-experimental` to drop
+//! prerelease versions — instead of filtering client-side.
+
+use crate::namespace::ToolPath;
+
+/// A compiled set of include/exclude glob patterns. Build once with [`ToolPathMatcher::new`] and
+/// reuse across a whole `list_tools` call rather than recompiling per tool.
+pub struct ToolPathMatcher {
+    /// `(negated, pattern)` pairs in the order they were given. `negated` patterns came from a
+    /// `!`-prefixed string.
+    patterns: Vec<(bool, Vec<char>)>,
+}
+
+impl ToolPathMatcher {
+    /// Compiles `patterns`. A pattern prefixed with `!` is an exclude; every other pattern is an
+    /// include. A path matches the resulting set when it matches at least one include pattern
+    /// (or there are no include patterns at all — unlike Deno's `PathOrPatternSet`, an empty
+    /// include set is treated as "don't filter on inclusion" so a caller that only wants to
+    /// exclude something doesn't have to spell out `**` first) and no exclude pattern.
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                let pattern = pattern.as_ref();
+                match pattern.strip_prefix('!') {
+                    Some(rest) => (true, rest.chars().collect()),
+                    None => (false, pattern.chars().collect()),
+                }
+            })
+            .collect();
+
+        Self { patterns }
+    }
+
+    /// A matcher with no patterns at all, i.e. "everything matches". Equivalent to the unfiltered
+    /// `namespace_filter: None` case `FilePersistence::list_tools` supported before it took a
+    /// matcher.
+    pub fn all() -> Self {
+        Self { patterns: Vec::new() }
+    }
+
+    /// Whether `path`'s [`ToolPath`] `Display` string matches this set.
+    pub fn matches(&self, path: &ToolPath) -> bool {
+        let candidate: Vec<char> = path.to_string().chars().collect();
+
+        let has_includes = self.patterns.iter().any(|(negated, _)| !negated);
+        let mut included = !has_includes;
+
+        for (negated, pattern) in &self.patterns {
+            if !glob_match(pattern, &candidate) {
+                continue;
+            }
+            if *negated {
+                return false;
+            }
+            included = true;
+        }
+
+        included
+    }
+}
+
+/// Matches `pattern` against `text`, supporting `*` (any run of characters excluding `/`), `**`
+/// (any run of characters including `/`), and `?` (exactly one character excluding `/`). Every
+/// other character matches itself literally. Backtracking rather than a DP table — patterns here
+/// are a handful of path segments at most, so the naive approach is plenty fast.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => {
+            let double_star = pattern.get(1) == Some(&'*');
+            let rest = &pattern[if double_star { 2 } else { 1 }..];
+            let limit = if double_star {
+                text.len()
+            } else {
+                text.iter().position(|&c| c == '/').unwrap_or(text.len())
+            };
+
+            (0..=limit).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('?') => match text.first() {
+            Some(&c) if c != '/' => glob_match(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some(&p) => match text.first() {
+            Some(&c) if c == p => glob_match(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alice_tool() -> ToolPath {
+        ToolPath::user("alice", "utils", "reverse_string", "1.0")
+    }
+
+    fn bob_math_tool(version: &str) -> ToolPath {
+        ToolPath::user("bob", "math", "calculate", version)
+    }
+
+    #[test]
+    fn all_matches_everything() {
+        let matcher = ToolPathMatcher::all();
+        assert!(matcher.matches(&alice_tool()));
+        assert!(matcher.matches(&bob_math_tool("2.0")));
+    }
+
+    #[test]
+    fn include_pattern_scopes_to_a_namespace() {
+        let matcher = ToolPathMatcher::new(["/alice/**"]);
+        assert!(matcher.matches(&alice_tool()));
+        assert!(!matcher.matches(&bob_math_tool("2.0")));
+    }
+
+    #[test]
+    fn exclude_pattern_wins_over_a_matching_include() {
+        let matcher = ToolPathMatcher::new(["/*/math/**", "!**:experimental"]);
+        assert!(matcher.matches(&bob_math_tool("2.0")));
+        assert!(!matcher.matches(&bob_math_tool("experimental")));
+    }
+
+    #[test]
+    fn exclude_only_set_keeps_everything_else() {
+        let matcher = ToolPathMatcher::new(["!**:experimental"]);
+        assert!(matcher.matches(&alice_tool()));
+        assert!(!matcher.matches(&bob_math_tool("experimental")));
+    }
+}
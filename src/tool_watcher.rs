@@ -0,0 +1,148 @@
+//! Watches a `FilePersistence` storage directory for out-of-band changes — another process, a
+//! `git pull`, or a human editing a tool's JSON file by hand — so they're picked up without a
+//! restart. Mirrors how Deno's `file_watcher` drives its `--watch` subcommands: a raw `notify`
+//! event stream is debounced into a short coalescing window before anything re-reads a file, so
+//! editors that write-truncate-rewrite don't trigger a cascade of reloads.
+use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Instant;
+
+use crate::namespace::ToolPath;
+
+/// How a watched tool file changed, carried on `ToolChange`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+/// A tool whose on-disk definition changed outside of `FilePersistence`'s own write path.
+/// Broadcast over the channel `watch` returns so the MCP server can re-advertise its tool list
+/// to connected clients.
+#[derive(Debug, Clone)]
+pub struct ToolChange {
+    pub path: ToolPath,
+    pub kind: ChangeKind,
+}
+
+/// Coalescing window for rapid successive writes to the same file, so a single logical edit
+/// (write, then truncate, then rewrite) produces one reload instead of several.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How often the debounce loop checks for paths whose coalescing window has elapsed.
+const TICK: Duration = Duration::from_millis(50);
+
+/// Starts a background watcher over `storage_dir` and returns a receiver of `ToolChange` events.
+///
+/// `on_change` is called once per debounced path (never for `index_path`, which is
+/// `FilePersistence`'s own bookkeeping file and would otherwise self-trigger a reload loop) and
+/// is expected to re-read the affected tool file, recompute its checksum, and update whatever
+/// in-memory index it's closed over — this function only detects "something changed under
+/// `storage_dir`"; `on_change` owns what to do about it.
+pub fn watch<F, Fut>(
+    storage_dir: PathBuf,
+    index_path: PathBuf,
+    on_change: F,
+) -> Result<broadcast::Receiver<ToolChange>>
+where
+    F: Fn(PathBuf) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Option<ToolChange>> + Send + 'static,
+{
+    let (changes_tx, changes_rx) = broadcast::channel(256);
+    let (raw_tx, mut raw_rx) = mpsc::unbounded_channel::<PathBuf>();
+
+    let watch_index_path = index_path.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        for path in event.paths {
+            if path == watch_index_path {
+                continue;
+            }
+            let _ = raw_tx.send(path);
+        }
+    })?;
+    watcher.watch(&storage_dir, RecursiveMode::Recursive)?;
+
+    tokio::spawn(async move {
+        // Owning the watcher here keeps it alive for as long as this debounce loop runs.
+        let _watcher = watcher;
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                received = raw_rx.recv() => {
+                    match received {
+                        Some(path) => {
+                            pending.insert(path, Instant::now() + DEBOUNCE);
+                        }
+                        None => break,
+                    }
+                }
+                _ = tokio::time::sleep(TICK), if !pending.is_empty() => {
+                    let now = Instant::now();
+                    let ready: Vec<PathBuf> = pending
+                        .iter()
+                        .filter(|(_, &deadline)| now >= deadline)
+                        .map(|(path, _)| path.clone())
+                        .collect();
+
+                    for path in ready {
+                        pending.remove(&path);
+                        let changes_tx = changes_tx.clone();
+                        let fut = on_change(path);
+                        tokio::spawn(async move {
+                            if let Some(change) = fut.await {
+                                let _ = changes_tx.send(change);
+                            }
+                        });
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(changes_rx)
+}
+
+/// Reconstructs the `ToolPath` a tool receipt file on disk corresponds to, inverting
+/// `FilePersistence::get_tool_file_path`. Returns `None` for paths outside the recognized layout
+/// (e.g. the index file itself, a stray file in neither recognized `StorageFormat`, or a
+/// directory event). Accepts either `.json` or `.toml`, since a storage directory can contain a
+/// mix of both (see `StorageFormat`).
+pub fn tool_path_from_file(storage_dir: &Path, file_path: &Path) -> Option<ToolPath> {
+    let rel = file_path.strip_prefix(storage_dir).ok()?;
+    let mut components: Vec<&str> = rel
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+
+    let filename = components.pop()?;
+    let stem = filename
+        .strip_suffix(".json")
+        .or_else(|| filename.strip_suffix(".toml"))?;
+
+    // `name.json`/`name.toml` for "latest", `name_version.<ext>` otherwise (see
+    // `get_tool_file_path`); a
+    // suffix counts as a version only if it starts with a digit, so a tool whose version doesn't
+    // (e.g. `experimental`) is read back as a "latest" tool whose name includes that suffix.
+    let (name, version) = match stem.rsplit_once('_') {
+        Some((name, version)) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+            (name, version.to_string())
+        }
+        _ => (stem, "latest".to_string()),
+    };
+
+    match components.as_slice() {
+        ["system", "bin"] => Some(ToolPath::bin(name)),
+        ["system", "sbin"] => Some(ToolPath::sbin(name)),
+        ["system", "docs"] => Some(ToolPath::docs(name)),
+        ["users", user, package] => Some(ToolPath::user(*user, *package, name, version)),
+        _ => None,
+    }
+}
@@ -0,0 +1,250 @@
+//! Trust gate for filesystem-discovered tools, modeled on Rokit's `TrustCache`: a `.tcl` file
+//! under `users/*` is indexed by [`crate::tool_discovery::ToolDiscovery`] the moment it appears on
+//! disk, with no approval step of its own — risky for a namespace anyone can drop a file into.
+//! [`TrustStore`] tracks which tools have actually been reviewed, keyed by content hash rather
+//! than just path, so editing an already-trusted tool's script silently revokes it; the new
+//! content must be approved again before `TclExecutor::exec_tool` will run it.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::namespace::{Namespace, ToolPath};
+use crate::tool_discovery::DiscoveredTool;
+
+/// SHA-256 hex digest of a tool script's content. Mirrors `persistence::calculate_checksum`'s
+/// scheme exactly; kept as a separate function (rather than shared) since trust approvals and
+/// storage checksums are tracked independently and are free to diverge later.
+pub fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Where the trust store is persisted: `<local data dir>/tcl-mcp-server/trust-store.json`,
+/// alongside `persistence::get_storage_directory`'s `tools.storage`. Approvals are tracked
+/// per-`ToolPath` regardless of which `tools_dir` discovery happens to be scanning, so this
+/// deliberately doesn't live under `ToolDiscovery`'s own root the way its mtime cache does.
+pub fn default_trust_store_path() -> Result<PathBuf> {
+    let data_dir = dirs::data_local_dir()
+        .ok_or_else(|| anyhow!("Could not determine local data directory"))?;
+
+    Ok(data_dir.join("tcl-mcp-server").join("trust-store.json"))
+}
+
+/// On-disk shape of the trust store. Keyed by `ToolPath::to_string()` rather than `ToolPath`
+/// itself, since `ToolPath` doesn't serialize to a JSON-object-key-compatible string.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrustRecord {
+    /// Must match `CARGO_PKG_VERSION`; a record written by a different crate version is
+    /// discarded rather than risking a stale or incompatible shape.
+    version: String,
+    /// Approved tool path (as text) -> the content hash it was approved at.
+    approvals: HashMap<String, String>,
+}
+
+/// Gatekeeper consulted by `TclExecutor::exec_tool` before a filesystem-discovered tool is
+/// allowed to run. `bin`/`sbin`/`docs` tools ship with the server and are trusted outright when
+/// `trust_system_namespaces` is set (the default); `users/*` tools always require an explicit
+/// [`TrustStore::trust`] call recorded here, regardless of that flag.
+#[derive(Debug)]
+pub struct TrustStore {
+    approvals: HashMap<ToolPath, String>,
+    trust_system_namespaces: bool,
+}
+
+impl Default for TrustStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrustStore {
+    pub fn new() -> Self {
+        Self { approvals: HashMap::new(), trust_system_namespaces: true }
+    }
+
+    /// Governs whether `bin`/`sbin`/`docs` tools bypass the gate outright. `users/*` tools are
+    /// never affected by this flag — they always require an explicit approval.
+    pub fn set_trust_system_namespaces(&mut self, trust: bool) {
+        self.trust_system_namespaces = trust;
+    }
+
+    /// Loads approvals from `path`, falling back to an empty store if it's missing, unparseable,
+    /// or was written by a different crate version — matching `ToolDiscovery`'s own
+    /// cache-loading tolerance, since losing approvals only costs re-review, not correctness.
+    pub async fn load(path: &Path) -> Self {
+        let mut store = Self::new();
+
+        let content = match fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(_) => return store,
+        };
+
+        let record: TrustRecord = match serde_json::from_str::<TrustRecord>(&content) {
+            Ok(record) if record.version == env!("CARGO_PKG_VERSION") => record,
+            Ok(_) => {
+                tracing::warn!("Discarding trust store written by a different crate version");
+                return store;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse trust store ({}), starting from no approvals", e);
+                return store;
+            }
+        };
+
+        for (path_text, hash) in record.approvals {
+            match ToolPath::parse(&path_text) {
+                Ok(tool_path) => {
+                    store.approvals.insert(tool_path, hash);
+                }
+                Err(e) => {
+                    tracing::warn!("Dropping unparseable trust store entry '{}': {}", path_text, e);
+                }
+            }
+        }
+
+        store
+    }
+
+    /// Persists approvals to `path`, writing through a sibling `.tmp` file and renaming it into
+    /// place (see `persistence::write_atomic`). A failure here is logged rather than surfaced,
+    /// since it only risks re-approval being needed after a restart, not an incorrect grant.
+    pub async fn save(&self, path: &Path) {
+        let record = TrustRecord {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            approvals: self.approvals.iter()
+                .map(|(tool_path, hash)| (tool_path.to_string(), hash.clone()))
+                .collect(),
+        };
+
+        let json = match serde_json::to_string_pretty(&record) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Failed to serialize trust store: {}", e);
+                return;
+            }
+        };
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp_path = PathBuf::from(tmp);
+
+        if let Err(e) = fs::write(&tmp_path, json.as_bytes()).await {
+            tracing::warn!("Failed to write trust store: {}", e);
+            return;
+        }
+        if let Err(e) = fs::rename(&tmp_path, path).await {
+            tracing::warn!("Failed to persist trust store: {}", e);
+        }
+    }
+
+    /// Records `tool`'s current content hash as approved, re-approving (rather than erroring) a
+    /// tool that was already trusted at a different hash.
+    pub fn trust(&mut self, tool: &DiscoveredTool) {
+        self.approvals.insert(tool.path.clone(), tool.content_hash.clone());
+    }
+
+    /// Withdraws approval for `path`. Errors if nothing was approved there, so a caller can tell
+    /// a no-op revoke from a real one.
+    pub fn revoke(&mut self, path: &ToolPath) -> Result<()> {
+        if self.approvals.remove(path).is_none() {
+            return Err(anyhow!("'{}' has no recorded trust approval", path));
+        }
+        Ok(())
+    }
+
+    /// Whether `tool` is currently allowed to run. `bin`/`sbin`/`docs` short-circuit to `true`
+    /// when `trust_system_namespaces` is set; every other namespace (in practice always
+    /// `Namespace::User`, the only one `ToolDiscovery` emits besides those three) requires a
+    /// recorded approval whose hash still matches `tool.content_hash` — an edited file drops back
+    /// to untrusted even if an earlier version of it was approved.
+    pub fn is_trusted(&self, tool: &DiscoveredTool) -> bool {
+        if self.trust_system_namespaces && !matches!(tool.path.namespace, Namespace::User(_)) {
+            return true;
+        }
+
+        self.approvals.get(&tool.path).is_some_and(|approved| *approved == tool.content_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf as StdPathBuf;
+
+    fn tool(path: ToolPath, content_hash: &str) -> DiscoveredTool {
+        DiscoveredTool {
+            path,
+            description: "test".to_string(),
+            file_path: StdPathBuf::from("<fake>"),
+            parameters: Vec::new(),
+            requires_privileged: false,
+            content_hash: content_hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn bin_tools_are_trusted_by_default() {
+        let store = TrustStore::new();
+        assert!(store.is_trusted(&tool(ToolPath::bin("list_dir"), "anyhash")));
+    }
+
+    #[test]
+    fn user_tools_are_untrusted_until_explicitly_approved() {
+        let mut store = TrustStore::new();
+        let t = tool(ToolPath::user("alice", "utils", "reverse", "1.0"), "hash-a");
+        assert!(!store.is_trusted(&t));
+
+        store.trust(&t);
+        assert!(store.is_trusted(&t));
+    }
+
+    #[test]
+    fn editing_an_approved_tool_drops_it_back_to_untrusted() {
+        let mut store = TrustStore::new();
+        let path = ToolPath::user("alice", "utils", "reverse", "1.0");
+        store.trust(&tool(path.clone(), "hash-a"));
+
+        let edited = tool(path, "hash-b");
+        assert!(!store.is_trusted(&edited));
+    }
+
+    #[test]
+    fn revoke_withdraws_an_existing_approval() {
+        let mut store = TrustStore::new();
+        let t = tool(ToolPath::user("alice", "utils", "reverse", "1.0"), "hash-a");
+        store.trust(&t);
+
+        store.revoke(&t.path).unwrap();
+        assert!(!store.is_trusted(&t));
+    }
+
+    #[test]
+    fn revoking_an_untrusted_path_errors() {
+        let mut store = TrustStore::new();
+        assert!(store.revoke(&ToolPath::user("alice", "utils", "reverse", "1.0")).is_err());
+    }
+
+    #[test]
+    fn disabling_trust_system_namespaces_gates_bin_tools_too() {
+        let mut store = TrustStore::new();
+        store.set_trust_system_namespaces(false);
+        assert!(!store.is_trusted(&tool(ToolPath::bin("list_dir"), "anyhash")));
+    }
+
+    #[tokio::test]
+    async fn save_and_load_round_trips_approvals() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(".trust-store.json");
+
+        let mut store = TrustStore::new();
+        let t = tool(ToolPath::user("alice", "utils", "reverse", "1.0"), "hash-a");
+        store.trust(&t);
+        store.save(&path).await;
+
+        let loaded = TrustStore::load(&path).await;
+        assert!(loaded.is_trusted(&t));
+    }
+}
@@ -0,0 +1,141 @@
+//! Semver-based version resolution for [`ToolPath`], modeled on Cargo's `VersionReq`: a caller
+//! can name a requirement (`^1.2`, `~1.0`, `>=1.0,<2.0`, or `latest`) after the `:` in a tool
+//! path instead of pinning an exact version, and get back the highest installed version of that
+//! `(namespace, package, name)` that satisfies it. Used by `exec_tool` and the `tools/call`
+//! custom-tool dispatch so tools can evolve without every caller hard-pinning a version.
+
+use anyhow::{anyhow, Result};
+use semver::{Version, VersionReq};
+
+use crate::namespace::ToolPath;
+
+/// A parsed `ToolPath::version` string, constructed with [`parse`]. `Exact` and `Req` carry real
+/// `semver` types rather than raw strings, so resolving them against the registry is just
+/// `semver::Version` comparison/matching instead of bespoke parsing.
+#[derive(Debug, Clone)]
+pub enum VersionSpec {
+    /// A version pinned outright, e.g. `"1.2.3"`.
+    Exact(Version),
+    /// A range requirement, e.g. `"^1.2"`, `"~1.0"`, `">=1.0,<2.0"`.
+    Req(VersionReq),
+    /// `"latest"` — the highest installed version, semver-ordered.
+    Latest,
+}
+
+/// Zero-pads a version string with any missing `.minor`/`.patch` components (`"1"` -> `"1.0.0"`,
+/// `"1.2"` -> `"1.2.0"`), since `semver::Version::parse` requires all three but this repo's tool
+/// versions have always been written with as few as one (e.g. `"1.0"`, `ToolPath::bin`'s
+/// `"latest"` sentinel aside).
+pub(crate) fn pad_to_semver(raw: &str) -> String {
+    let mut parts: Vec<&str> = raw.split('.').collect();
+    while parts.len() < 3 {
+        parts.push("0");
+    }
+    parts.join(".")
+}
+
+/// Parses a `ToolPath::version` string into a [`VersionSpec`]. Errors if `raw` is neither
+/// `"latest"`, an exact version, nor valid `VersionReq` syntax — e.g. a literal non-semver tag
+/// like `"experimental"`. Those remain perfectly valid `ToolPath` versions; they just can only
+/// ever be reached by an exact registry match, never resolved as a requirement.
+pub fn parse(raw: &str) -> Result<VersionSpec> {
+    let raw = raw.trim();
+    if raw == "latest" {
+        return Ok(VersionSpec::Latest);
+    }
+    if let Ok(version) = Version::parse(&pad_to_semver(raw)) {
+        return Ok(VersionSpec::Exact(version));
+    }
+    VersionReq::parse(raw)
+        .map(VersionSpec::Req)
+        .map_err(|e| anyhow!("'{}' is neither a version nor a valid version requirement: {}", raw, e))
+}
+
+/// Resolves `spec` against `candidates`, returning the highest installed version satisfying it,
+/// or `None` if nothing does. Candidates whose version isn't valid semver (even after
+/// [`pad_to_semver`]) are skipped, since neither an exact version nor a requirement can
+/// meaningfully match one.
+pub fn resolve<'a, I>(candidates: I, spec: &VersionSpec) -> Option<&'a ToolPath>
+where
+    I: Iterator<Item = &'a ToolPath>,
+{
+    candidates
+        .filter_map(|candidate| {
+            Version::parse(&pad_to_semver(&candidate.version)).ok().map(|version| (candidate, version))
+        })
+        .filter(|(_, version)| match spec {
+            VersionSpec::Latest => true,
+            VersionSpec::Exact(exact) => version == exact,
+            VersionSpec::Req(req) => req.matches(version),
+        })
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+        .map(|(candidate, _)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(version: &str) -> ToolPath {
+        ToolPath::user("alice", "math", "calculate", version)
+    }
+
+    #[test]
+    fn parses_latest_and_exact() {
+        assert!(matches!(parse("latest").unwrap(), VersionSpec::Latest));
+        assert!(matches!(parse("1.0").unwrap(), VersionSpec::Exact(_)));
+    }
+
+    #[test]
+    fn parses_caret_tilde_and_comparator_list_requirements() {
+        assert!(matches!(parse("^1.2").unwrap(), VersionSpec::Req(_)));
+        assert!(matches!(parse("~1.0").unwrap(), VersionSpec::Req(_)));
+        assert!(matches!(parse(">=1.0,<2.0").unwrap(), VersionSpec::Req(_)));
+    }
+
+    #[test]
+    fn non_semver_literal_tags_fail_to_parse_as_a_spec() {
+        assert!(parse("experimental").is_err());
+    }
+
+    #[test]
+    fn caret_picks_the_highest_compatible_version() {
+        let candidates = vec![path("1.0"), path("1.2"), path("1.9.0"), path("2.0"), path("0.9")];
+        let resolved = resolve(candidates.iter(), &parse("^1.2").unwrap()).unwrap();
+        assert_eq!(resolved.version, "1.9.0");
+    }
+
+    #[test]
+    fn tilde_stays_within_the_same_minor_version() {
+        let candidates = vec![path("1.0"), path("1.0.5"), path("1.1"), path("2.0")];
+        let resolved = resolve(candidates.iter(), &parse("~1.0").unwrap()).unwrap();
+        assert_eq!(resolved.version, "1.0.5");
+    }
+
+    #[test]
+    fn comparator_list_bounds_above_and_below() {
+        let candidates = vec![path("0.9"), path("1.0"), path("1.5"), path("2.0")];
+        let resolved = resolve(candidates.iter(), &parse(">=1.0,<2.0").unwrap()).unwrap();
+        assert_eq!(resolved.version, "1.5");
+    }
+
+    #[test]
+    fn exact_matches_an_equivalent_concrete_version_regardless_of_padding() {
+        let candidates = vec![path("1.0.0"), path("2.0")];
+        let resolved = resolve(candidates.iter(), &parse("1.0").unwrap()).unwrap();
+        assert_eq!(resolved.version, "1.0.0");
+    }
+
+    #[test]
+    fn latest_falls_back_to_the_highest_installed_version() {
+        let candidates = vec![path("1.0"), path("3.1"), path("2.0")];
+        let resolved = resolve(candidates.iter(), &parse("latest").unwrap()).unwrap();
+        assert_eq!(resolved.version, "3.1");
+    }
+
+    #[test]
+    fn no_candidate_satisfies_the_requirement() {
+        let candidates = vec![path("1.0"), path("1.1")];
+        assert!(resolve(candidates.iter(), &parse(">=2.0").unwrap()).is_none());
+    }
+}